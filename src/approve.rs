@@ -0,0 +1,104 @@
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::emoji_generator::EmojiGenerator;
+
+/// The tone of an approval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Style {
+    #[default]
+    Short,
+    Enthusiastic,
+    Sarcastic,
+}
+
+impl Style {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "short" => Some(Self::Short),
+            "enthusiastic" | "hype" => Some(Self::Enthusiastic),
+            "sarcastic" | "sarcasm" => Some(Self::Sarcastic),
+            _ => None,
+        }
+    }
+
+    /// How many emojis go with a phrase in this style.
+    pub fn emoji_count(&self) -> usize {
+        match self {
+            Self::Short => 2,
+            Self::Enthusiastic => 7,
+            Self::Sarcastic => 1,
+        }
+    }
+}
+
+/// The phrases approvals are made of, one list per style. A list left out of
+/// the config keeps the built in phrases.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ApproveConfig {
+    pub short: Vec<String>,
+    pub enthusiastic: Vec<String>,
+    pub sarcastic: Vec<String>,
+}
+
+impl Default for ApproveConfig {
+    fn default() -> Self {
+        let phrases = |list: &[&str]| list.iter().map(|p| p.to_string()).collect();
+        Self {
+            short: phrases(&[
+                "LGTM",
+                "Ship it",
+                "Approved",
+                "👍 from me",
+                "চলবে",
+                "ঠিক আছে, মার্জ করেন",
+            ]),
+            enthusiastic: phrases(&[
+                "LGTM!!! Best PR I've seen all week!",
+                "SHIP IT! SHIP IT NOW!",
+                "This is beautiful, I'm crying.",
+                "অসাধারণ! এখনই মার্জ করেন!",
+                "ভাই আপনি তো জিনিয়াস!",
+            ]),
+            sarcastic: phrases(&[
+                "LGTM, I didn't read it.",
+                "Ship it, what could possibly go wrong?",
+                "Approved. Tests are for people who make mistakes.",
+                "দেখসি, বুঝি নাই, এপ্রুভ।",
+                "প্রোডাকশনে টেস্ট করে নিয়েন।",
+            ]),
+        }
+    }
+}
+
+impl ApproveConfig {
+    pub fn phrases(&self, style: Style) -> &[String] {
+        match style {
+            Style::Short => &self.short,
+            Style::Enthusiastic => &self.enthusiastic,
+            Style::Sarcastic => &self.sarcastic,
+        }
+    }
+}
+
+/// Put a phrase and its emojis together.
+pub fn format_approval(phrase: &str, emojis: &[String]) -> String {
+    if emojis.is_empty() {
+        phrase.to_string()
+    } else {
+        format!("{} {}", phrase, emojis.join(" "))
+    }
+}
+
+/// A random approval in the given style.
+pub fn approval(config: &ApproveConfig, style: Style) -> String {
+    let phrases = config.phrases(style);
+    let phrase = if phrases.is_empty() {
+        "LGTM"
+    } else {
+        &phrases[rand::rng().random_range(0..phrases.len())]
+    };
+    let emojis = EmojiGenerator::new().generate(style.emoji_count());
+    format_approval(phrase, &emojis)
+}
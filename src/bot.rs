@@ -1,403 +1,2800 @@
 use std::env;
 
+use crate::approve::{self, Style};
+use crate::buttons::{self, ButtonAction};
+use crate::commands;
+use crate::config::BotConfig;
 use crate::emoji_generator::EmojiGenerator;
-use crate::guysay::say;
-use crate::health_checker::check_health;
-use crate::jokes::fetch_joke;
+use crate::guild_settings::{self, GuildSettings, GuildSettingsStore};
+use crate::guysay::{say, say_within};
+use crate::health_checker;
+use crate::help::{self, HelpQuery, MESSAGE_LIMIT};
+use crate::jokes::{self, Punchlines, THINKING_EMOJI, fetch_joke};
+use crate::karma::{self, KarmaCooldown, KarmaTarget};
+use crate::notify;
+use crate::output::{self, Reply};
+use crate::permissions::{self, Caller, Permissions, denied_message};
+use crate::poll::{self, Poll};
+use crate::pr_links::SeenMessages;
+use crate::rate_limit::{RateLimiter, throttle_message};
+use crate::reactions::{EmojiMode, pick_reactions};
+use crate::reminders::{self, DEFAULT_TIMEZONE, MAX_PER_USER, Reminder};
+use crate::sprint::{self, Sprint, SprintCommand};
+use crate::standup::{self, Delivery, TeamConfig};
+use crate::stats::{self, CommandUsage, StatsQuery};
+use crate::storage::{Storage, StorageError, unix_now};
+use crate::summon::{self, SummonRequest};
+use crate::tracker::{self, FILE_AS_BUG, NewIssue, TrackerError};
+use crate::unfurl::{self, TicketCache};
+use crate::updates::{self, Since, StatusUpdate, UpdatesQuery};
+use crate::webhook::{self, Post, WebhookConfig};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
 use rand::Rng;
 use serenity::async_trait;
-use serenity::model::channel::Message;
+use serenity::builder::{
+    CreateAllowedMentions, CreateAttachment, CreateCommand, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse, EditMessage,
+    GetMessages,
+};
+use serenity::http::Http;
+use serenity::model::application::{
+    Command as ApplicationCommand, CommandInteraction, CommandType, ComponentInteraction,
+    Interaction, ResolvedTarget,
+};
+use serenity::model::channel::{Message, Reaction, ReactionType};
+use serenity::model::event::MessageUpdateEvent;
 use serenity::model::gateway::Ready;
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
 use serenity::prelude::*;
-use tracing::{debug, error, info};
+use thiserror::Error;
+use tracing::{debug, error, info, warn};
 
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::{Mutex, RwLock, mpsc};
 
 pub struct BotState {
     pub quotes: Arc<RwLock<Vec<String>>>,
+    /// The same quotes, grouped by the file they came from.
+    pub quote_categories: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    pub config: BotConfig,
+    pub rate_limiter: Mutex<RateLimiter>,
+    pub permissions: Permissions,
+    pub guild_settings: RwLock<GuildSettingsStore>,
+    pub storage: Arc<Storage>,
+    /// Messages that already got pull request reactions.
+    pub pr_reacted: Mutex<SeenMessages>,
+    /// Who last changed whose karma, against farming.
+    pub karma_cooldown: Mutex<KarmaCooldown>,
+    /// Tickets looked up for unfurling.
+    pub tickets: Mutex<TicketCache>,
+    /// Joke punchlines waiting to be posted.
+    pub punchlines: Mutex<Punchlines>,
+}
+
+#[derive(Error, Debug)]
+pub enum BotStateError {
+    #[error("Failed to load quotes: {0}")]
+    Quotes(#[from] std::io::Error),
+    #[error("Failed to open storage: {0}")]
+    Storage(#[from] StorageError),
 }
 
 impl BotState {
-    pub async fn new(quotes_folder: &str) -> Result<Self, std::io::Error> {
+    /// Bot state with the default config and an in-memory database.
+    pub async fn new(quotes_folder: &str) -> Result<Self, BotStateError> {
+        Self::build(quotes_folder, BotConfig::default(), Storage::in_memory()?).await
+    }
+
+    pub async fn with_config(
+        quotes_folder: &str,
+        config: BotConfig,
+    ) -> Result<Self, BotStateError> {
+        let storage = Storage::open(&config.storage.path)?;
+        if let Some(path) = &config.storage.guild_settings_path {
+            guild_settings::import_json(&storage, path)?;
+        }
+        Self::build(quotes_folder, config, storage).await
+    }
+
+    async fn build(
+        quotes_folder: &str,
+        config: BotConfig,
+        storage: Storage,
+    ) -> Result<Self, BotStateError> {
         // Load quotes from the specified folder
-        let quotes = crate::quote_loader::load_from_folder(quotes_folder)?;
+        let quote_categories = crate::quote_loader::load_categories_from_folder(quotes_folder)?;
+        let quotes = quote_categories.values().flatten().cloned().collect();
+        let storage = Arc::new(storage);
         Ok(Self {
             quotes: Arc::new(RwLock::new(quotes)),
+            quote_categories: Arc::new(RwLock::new(quote_categories)),
+            rate_limiter: Mutex::new(RateLimiter::new(config.rate_limit.clone())),
+            permissions: Permissions::new(config.permissions.clone()),
+            guild_settings: RwLock::new(GuildSettingsStore::load(storage.clone())?),
+            storage,
+            pr_reacted: Mutex::new(SeenMessages::new()),
+            karma_cooldown: Mutex::new(KarmaCooldown::new(&config.karma)),
+            tickets: Mutex::new(TicketCache::new(&config.unfurl)),
+            punchlines: Mutex::new(Punchlines::new()),
+            config,
         })
     }
-}
+}
+
+/// Roles of the member who sent a guild message, empty for DMs.
+fn member_roles(msg: &Message) -> &[RoleId] {
+    msg.member
+        .as_ref()
+        .map(|member| member.roles.as_slice())
+        .unwrap_or_default()
+}
+
+/// Emojis for an `!oracle` answer.
+fn oracle_emojis() -> Vec<String> {
+    let emoji_count = rand::rng().random_range(5..=15);
+    EmojiGenerator::new().generate(emoji_count)
+}
+
+/// Send messages in order, stopping at the first that fails. Returns the
+/// last one sent.
+async fn send_all(
+    http: &Http,
+    channel: ChannelId,
+    messages: Vec<CreateMessage>,
+) -> Result<Option<Message>, SerenityError> {
+    let mut last = None;
+    for message in messages {
+        last = Some(channel.send_message(http, message).await?);
+    }
+    Ok(last)
+}
+
+/// Disable a reply's buttons once they time out.
+async fn expire_buttons(
+    http: Arc<Http>,
+    state: Arc<BotState>,
+    channel: ChannelId,
+    message: MessageId,
+    command: &'static str,
+) {
+    tokio::time::sleep(Duration::from_secs(state.config.buttons.timeout_secs)).await;
+
+    let edit = EditMessage::new().components(buttons::buttons(command, false, true));
+    if let Err(why) = channel.edit_message(&http, message, edit).await {
+        // most likely the message was deleted in the meantime
+        debug!(error = ?why, message_id = %message, "Failed to disable buttons");
+    }
+}
+
+/// Post a joke's punchline as a reply to its setup, unless it's out already
+/// or the setup was deleted. Returns whether it was posted.
+async fn post_punchline(
+    http: &Http,
+    state: &BotState,
+    channel: ChannelId,
+    setup: MessageId,
+    embeds: bool,
+) -> bool {
+    let Some(punchline) = state.punchlines.lock().await.take(setup) else {
+        return false;
+    };
+
+    let reply = jokes::punchline_reply(&punchline)
+        .to_message(embeds)
+        .reference_message((channel, setup))
+        .allowed_mentions(CreateAllowedMentions::new());
+    if let Err(why) = channel.send_message(http, reply).await {
+        error!(error = ?why, channel_id = %channel, "Failed to send punchline");
+        return false;
+    }
+
+    // nothing left to reveal
+    let edit = EditMessage::new().components(buttons::buttons("joke", false, false));
+    if let Err(why) = channel.edit_message(http, setup, edit).await {
+        debug!(error = ?why, message_id = %setup, "Failed to remove the reveal button");
+    }
+    true
+}
+
+/// Keep typing for the punchline delay, then post it. Stops early when the
+/// punchline went out some other way or the setup was deleted.
+async fn deliver_punchline(
+    http: Arc<Http>,
+    state: Arc<BotState>,
+    channel: ChannelId,
+    setup: MessageId,
+    embeds: bool,
+) {
+    let typing = channel.start_typing(&http);
+    for _ in 0..state.config.joke.punchline_delay_secs {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        if !state.punchlines.lock().await.is_pending(setup) {
+            break;
+        }
+    }
+    typing.stop();
+
+    post_punchline(&http, &state, channel, setup, embeds).await;
+}
+
+/// Wait for the summoned to show up in the channel, and ping the ones that
+/// didn't again, this time with a DM as well.
+async fn escalate_summon(
+    http: Arc<Http>,
+    msg: Message,
+    users: Vec<UserId>,
+    request: SummonRequest,
+    delay: Duration,
+) {
+    tokio::time::sleep(delay).await;
+
+    let since = GetMessages::new().after(msg.id).limit(100);
+    let posted: Vec<UserId> = match msg.channel_id.messages(&http, since).await {
+        Ok(messages) => messages
+            .iter()
+            .filter(|m| !m.author.bot && m.author.id != msg.author.id)
+            .map(|m| m.author.id)
+            .collect(),
+        Err(why) => {
+            error!(
+                error = ?why,
+                channel_id = %msg.channel_id,
+                "Failed to fetch messages for summon escalation"
+            );
+            return;
+        }
+    };
+
+    let pending = summon::unanswered(&users, &posted);
+    // a role counts as answered as soon as anyone answered
+    let roles: &[RoleId] = if posted.is_empty() {
+        &msg.mention_roles
+    } else {
+        &[]
+    };
+    if pending.is_empty() && roles.is_empty() {
+        info!(channel_id = %msg.channel_id, "Summon was answered, not escalating");
+        return;
+    }
+
+    info!(
+        channel_id = %msg.channel_id,
+        pending_count = pending.len(),
+        role_count = roles.len(),
+        "Escalating summon"
+    );
+
+    let message = CreateMessage::new()
+        .content(summon::escalation_message(&pending, roles, &request))
+        .allowed_mentions(
+            CreateAllowedMentions::new()
+                .users(pending.clone())
+                .roles(roles.to_vec()),
+        );
+    if let Err(why) = msg.channel_id.send_message(&http, message).await {
+        error!(
+            error = ?why,
+            channel_id = %msg.channel_id,
+            "Failed to send summon escalation"
+        );
+    }
+
+    let dm = summon::escalation_dm(&msg.author.name, &msg.link(), &request);
+    for user in pending {
+        if let Err(why) = user
+            .direct_message(&http, CreateMessage::new().content(&dm))
+            .await
+        {
+            warn!(error = ?why, user_id = %user, "Failed to DM summoned user");
+        }
+    }
+}
+
+/// Add the emojis as reactions to a message that already has `existing`
+/// reactions. Emojis Discord won't take are skipped; returns how many were added.
+async fn add_reactions(
+    http: &Http,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    existing: usize,
+    emojis: &[String],
+) -> usize {
+    let mut added = 0;
+    for emoji in pick_reactions(emojis, existing) {
+        match channel_id
+            .create_reaction(http, message_id, ReactionType::Unicode(emoji.to_string()))
+            .await
+        {
+            Ok(()) => added += 1,
+            Err(why) => {
+                debug!(
+                    error = ?why,
+                    emoji = %emoji,
+                    message_id = %message_id,
+                    "Discord rejected reaction, skipping it"
+                );
+            }
+        }
+    }
+
+    info!(
+        channel_id = %channel_id,
+        message_id = %message_id,
+        reaction_count = added,
+        "Reacted with emojis"
+    );
+    added
+}
+
+/// Start the webhook listener when it is configured. Its announcements are
+/// posted through `http`.
+async fn start_webhook_listener(config: &WebhookConfig, http: Arc<Http>) {
+    let Some(bind) = &config.bind else {
+        return;
+    };
+    let secrets = config.secrets();
+    if secrets.is_empty() {
+        error!(
+            "Webhook listener needs a secret, set {} or {}",
+            webhook::SECRET_ENV_VAR,
+            notify::TOKEN_ENV_VAR
+        );
+        return;
+    }
+
+    let listener = match TcpListener::bind(bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!(error = ?e, bind = %bind, "Failed to bind webhook listener");
+            return;
+        }
+    };
+
+    let (posts, mut announcements) = mpsc::unbounded_channel::<Post>();
+    let router = webhook::router(config.clone(), secrets, posts);
+    tokio::spawn(async move {
+        if let Err(e) = webhook::serve(listener, router).await {
+            error!(error = ?e, "Webhook listener stopped");
+        }
+    });
+
+    tokio::spawn(async move {
+        while let Some(post) = announcements.recv().await {
+            if let Err(why) = post.channel.say(&http, &post.content).await {
+                error!(
+                    error = ?why,
+                    channel_id = %post.channel,
+                    "Failed to post webhook announcement"
+                );
+            }
+        }
+    });
+}
+
+/// How often the reminder table is checked for due reminders.
+const REMINDER_TICK: Duration = Duration::from_secs(10);
+
+/// Deliver due reminders for as long as the bot runs.
+fn start_reminder_scheduler(storage: Arc<Storage>, http: Arc<Http>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REMINDER_TICK);
+        loop {
+            interval.tick().await;
+            let due = match storage.run(|s| s.due_reminders(unix_now())).await {
+                Ok(due) => due,
+                Err(e) => {
+                    error!(error = ?e, "Failed to load due reminders");
+                    continue;
+                }
+            };
+            for reminder in due {
+                deliver_reminder(&http, &reminder).await;
+                // a reminder that can't be delivered won't get better by retrying every tick
+                let id = reminder.id;
+                if let Err(e) = storage.run(move |s| s.delete_reminder(id)).await {
+                    error!(error = ?e, reminder_id = reminder.id, "Failed to delete reminder");
+                }
+            }
+        }
+    });
+}
+
+async fn deliver_reminder(http: &Http, reminder: &Reminder) {
+    let content = reminders::delivery_message(reminder);
+    let result = if reminder.dm {
+        reminder
+            .target
+            .direct_message(http, CreateMessage::new().content(&content))
+            .await
+    } else {
+        // only the person being reminded gets pinged, whatever the text says
+        let message = CreateMessage::new()
+            .content(&content)
+            .allowed_mentions(CreateAllowedMentions::new().users(vec![reminder.target]));
+        reminder.channel.send_message(http, message).await
+    };
+
+    match result {
+        Ok(_) => info!(
+            reminder_id = reminder.id,
+            user_id = %reminder.target,
+            dm = reminder.dm,
+            "Delivered reminder"
+        ),
+        Err(why) => error!(
+            error = ?why,
+            reminder_id = reminder.id,
+            user_id = %reminder.target,
+            channel_id = %reminder.channel,
+            "Failed to deliver reminder"
+        ),
+    }
+}
+
+/// How often standup schedules are checked.
+const STANDUP_TICK: Duration = Duration::from_secs(30);
+
+/// Open and close the configured standup rounds for as long as the bot runs.
+fn start_standup_scheduler(state: Arc<BotState>, http: Arc<Http>) {
+    let mut schedulable = 0;
+    for team in &state.config.standup.teams {
+        match team.problem() {
+            // due_round never opens a round for these
+            Some(problem) => {
+                error!(team = %team.name, problem = %problem, "Standup team can't be scheduled, skipping it");
+            }
+            None => schedulable += 1,
+        }
+    }
+    if schedulable == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(STANDUP_TICK);
+        loop {
+            interval.tick().await;
+            let now = Utc::now();
+
+            for team in &state.config.standup.teams {
+                let Some(day) = team.due_round(now) else {
+                    continue;
+                };
+                let name = team.name.clone();
+                match state
+                    .storage
+                    .run(move |s| s.start_standup(&name, day))
+                    .await
+                {
+                    Ok(true) => ask_standup(&state, &http, team).await,
+                    Ok(false) => {}
+                    Err(e) => error!(error = ?e, team = %team.name, "Failed to start standup"),
+                }
+            }
+
+            let open = match state.storage.run(|s| s.open_standups()).await {
+                Ok(open) => open,
+                Err(e) => {
+                    error!(error = ?e, "Failed to load open standups");
+                    continue;
+                }
+            };
+            for (name, day) in open {
+                let team = state.config.standup.team(&name);
+                if let Some(team) = team
+                    && team.closes_at(day).is_some_and(|closes_at| now < closes_at)
+                {
+                    continue;
+                }
+                if let Some(team) = team {
+                    post_standup_summary(&state.storage, &http, team, day).await;
+                }
+                // teams removed from the config are closed without a summary
+                let closed = name.clone();
+                if let Err(e) = state
+                    .storage
+                    .run(move |s| s.close_standup(&closed, day))
+                    .await
+                {
+                    error!(error = ?e, team = %name, "Failed to close standup");
+                }
+            }
+        }
+    });
+}
+
+/// Send a fresh round's questions, to every member or to the team channel.
+async fn ask_standup(state: &BotState, http: &Http, team: &TeamConfig) {
+    info!(
+        team = %team.name,
+        member_count = team.members.len(),
+        delivery = ?team.delivery,
+        "Starting standup"
+    );
+    match team.delivery {
+        Delivery::Dm => {
+            let question = standup::question_message(team, 0);
+            for member in &team.members {
+                if let Err(why) = member
+                    .direct_message(http, CreateMessage::new().content(&question))
+                    .await
+                {
+                    warn!(error = ?why, user_id = %member, team = %team.name, "Failed to DM standup question");
+                }
+            }
+        }
+        Delivery::Channel => {
+            let guild = match team.channel.to_channel(http).await {
+                Ok(channel) => channel.guild().map(|channel| channel.guild_id),
+                Err(why) => {
+                    warn!(error = ?why, channel_id = %team.channel, "Failed to look up standup channel");
+                    None
+                }
+            };
+            let prefix = state.guild_settings.read().await.get(guild).prefix;
+            let message = CreateMessage::new()
+                .content(standup::channel_message(team, &prefix))
+                .allowed_mentions(CreateAllowedMentions::new().users(team.members.clone()));
+            if let Err(why) = team.channel.send_message(http, message).await {
+                error!(error = ?why, channel_id = %team.channel, team = %team.name, "Failed to post standup call");
+            }
+        }
+    }
+}
+
+async fn post_standup_summary(
+    storage: &Arc<Storage>,
+    http: &Http,
+    team: &TeamConfig,
+    day: NaiveDate,
+) {
+    let name = team.name.clone();
+    let answers = match storage.run(move |s| s.standup_answers(&name, day)).await {
+        Ok(answers) => answers,
+        Err(e) => {
+            error!(error = ?e, team = %team.name, "Failed to load standup answers");
+            return;
+        }
+    };
+    info!(
+        team = %team.name,
+        answered = answers.len(),
+        member_count = team.members.len(),
+        "Posting standup summary"
+    );
+    // the summary names everyone, it shouldn't ping them all again
+    let message = CreateMessage::new()
+        .content(standup::summary(team, day, &answers))
+        .allowed_mentions(CreateAllowedMentions::new());
+    if let Err(why) = team.channel.send_message(http, message).await {
+        error!(error = ?why, channel_id = %team.channel, team = %team.name, "Failed to post standup summary");
+    }
+}
+
+/// How often polls are checked for ones that should close.
+const POLL_TICK: Duration = Duration::from_secs(15);
+
+/// Close polls and post their results for as long as the bot runs.
+fn start_poll_scheduler(storage: Arc<Storage>, http: Arc<Http>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_TICK);
+        loop {
+            interval.tick().await;
+            let due = match storage.run(|s| s.due_polls(unix_now())).await {
+                Ok(due) => due,
+                Err(e) => {
+                    error!(error = ?e, "Failed to load due polls");
+                    continue;
+                }
+            };
+            for poll in due {
+                close_poll(&storage, &http, &poll).await;
+            }
+        }
+    });
+}
+
+async fn close_poll(storage: &Arc<Storage>, http: &Http, poll: &Poll) {
+    let id = poll.id;
+    let votes = match storage.run(move |s| s.poll_votes(id)).await {
+        Ok(votes) => votes,
+        Err(e) => {
+            error!(error = ?e, poll_id = poll.id, "Failed to load poll votes");
+            return;
+        }
+    };
+    let counts = poll::tally(poll.options.len(), &votes);
+    info!(poll_id = poll.id, vote_count = votes.len(), "Closing poll");
+
+    let message = CreateMessage::new()
+        .content(poll::results_message(poll, &counts))
+        .reference_message((poll.channel, poll.message))
+        .allowed_mentions(CreateAllowedMentions::new());
+    if let Err(why) = poll.channel.send_message(http, message).await {
+        error!(error = ?why, poll_id = poll.id, channel_id = %poll.channel, "Failed to post poll results");
+    }
+    // closed either way, a channel we can't post in won't take the results later
+    if let Err(e) = storage.run(move |s| s.close_poll(id)).await {
+        error!(error = ?e, poll_id = poll.id, "Failed to close poll");
+    }
+}
+
+// Define a struct to hold our event handler.
+// It doesn't need any data for this simple bot.
+struct Handler {
+    state: Arc<BotState>,
+}
+
+impl Handler {
+    pub fn new(state: Arc<BotState>) -> Self {
+        Self { state }
+    }
+
+    async fn send_simple_reply(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        command_name: &str,
+        response: &str,
+        error_note: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing {} command",
+            command_name
+        );
+
+        let messages = output::fit_messages(response, &self.state.config.output, "reply.txt");
+        if let Err(why) = send_all(&ctx.http, msg.channel_id, messages).await {
+            error!(
+                error = ?why,
+                channel_id = %msg.channel_id,
+                user_id = %msg.author.id,
+                "{}",
+                error_note
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Run the rate limiter in front of a command. Returns `false` when the
+    /// command is cooling down, after (maybe) telling the user off.
+    async fn allow_command(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        command_name: &str,
+    ) -> bool {
+        let mut limiter = self.state.rate_limiter.lock().await;
+
+        if limiter.is_exempt(member_roles(msg)) {
+            return true;
+        }
+
+        let now = Instant::now();
+        let throttled = match limiter.check(command_name, msg.author.id, msg.channel_id, now) {
+            Ok(()) => return true,
+            Err(throttled) => throttled,
+        };
+
+        info!(
+            user_id = %msg.author.id,
+            channel_id = %msg.channel_id,
+            scope = ?throttled.scope,
+            retry_after_secs = throttled.retry_after.as_secs(),
+            "Throttled {} command",
+            command_name
+        );
+
+        if limiter.should_notify(msg.author.id, now) {
+            drop(limiter);
+            let notice = throttle_message(&throttled, settings.language);
+            if let Err(why) = msg.reply(&ctx.http, notice).await {
+                error!(
+                    error = ?why,
+                    channel_id = %msg.channel_id,
+                    user_id = %msg.author.id,
+                    "Failed to send throttle notice"
+                );
+            }
+        }
+
+        false
+    }
+
+    /// Check the command against the permission rules. Returns `false` when the
+    /// caller isn't allowed to run it here, after (maybe) telling them so.
+    async fn is_permitted(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        command_name: &str,
+    ) -> bool {
+        // guild owners are admins too, but only the bot knows who they are
+        if permissions::is_admin_command(command_name) && self.is_admin(ctx, msg) {
+            return true;
+        }
+
+        let caller = Caller {
+            user: msg.author.id,
+            roles: member_roles(msg),
+            channel: msg.channel_id,
+        };
+
+        let denied =
+            match self
+                .state
+                .permissions
+                .check(&settings.permissions, command_name, &caller)
+            {
+                Ok(()) => return true,
+                Err(denied) => denied,
+            };
+
+        warn!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            guild_id = ?msg.guild_id,
+            rule = %denied.rule,
+            reason = ?denied.reason,
+            "Denied {} command",
+            command_name
+        );
+
+        // refusals share the throttle notice cooldown, so a denied user
+        // can't make the bot answer every message
+        let notify = self
+            .state
+            .rate_limiter
+            .lock()
+            .await
+            .should_notify(msg.author.id, Instant::now());
+        if notify
+            && let Err(why) = msg
+                .reply(&ctx.http, denied_message(&denied, settings.language))
+                .await
+        {
+            error!(
+                error = ?why,
+                channel_id = %msg.channel_id,
+                user_id = %msg.author.id,
+                "Failed to send permission denied reply"
+            );
+        }
+
+        false
+    }
+
+    /// Whether the author may administer the bot in this guild: either listed
+    /// as an admin in the config or the owner of the guild.
+    fn is_admin(&self, ctx: &Context, msg: &Message) -> bool {
+        if self
+            .state
+            .permissions
+            .is_admin(msg.author.id, member_roles(msg))
+        {
+            return true;
+        }
+
+        msg.guild_id
+            .and_then(|guild_id| ctx.cache.guild(guild_id).map(|guild| guild.owner_id))
+            .is_some_and(|owner_id| owner_id == msg.author.id)
+    }
+
+    /// Run an admin command that changes the guild's settings. `apply` gets the
+    /// guild's settings to modify and returns the reply.
+    async fn update_guild_settings(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        command_name: &str,
+        apply: impl FnOnce(&mut GuildSettings) -> String,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing {} command",
+            command_name
+        );
+
+        let (response, updated) = match msg.guild_id {
+            None => ("এইটা শুধু সার্ভারে সেট করা যায়।".to_string(), false),
+            Some(guild_id) if !self.is_admin(ctx, msg) => {
+                warn!(
+                    user_id = %msg.author.id,
+                    guild_id = %guild_id,
+                    "Non admin tried to run {} command",
+                    command_name
+                );
+                ("আপনি এডমিন না, এইসব আপনার কাজ না। 🙅".to_string(), false)
+            }
+            Some(guild_id) => {
+                let mut store = self.state.guild_settings.write().await;
+                match store.update(guild_id, apply).await {
+                    Ok(response) => {
+                        info!(
+                            user_id = %msg.author.id,
+                            guild_id = %guild_id,
+                            content = %msg.content,
+                            "Updated guild settings"
+                        );
+                        (response, true)
+                    }
+                    Err(e) => {
+                        error!(error = ?e, guild_id = %guild_id, "Failed to save guild settings");
+                        ("সেটিংস সেভ করতে পারলাম না, লগ দেখেন। 😵".to_string(), false)
+                    }
+                }
+            }
+        };
+
+        if let Err(why) = msg.reply(&ctx.http, response).await {
+            error!(
+                error = ?why,
+                channel_id = %msg.channel_id,
+                user_id = %msg.author.id,
+                "Failed to send {} response",
+                command_name
+            );
+            return false;
+        }
+        updated
+    }
+
+    async fn perm(&self, ctx: &Context, msg: &Message, args: &str) -> bool {
+        let permissions = &self.state.permissions;
+        self.update_guild_settings(ctx, msg, "perm", |settings| {
+            let reply = permissions.apply_command(&mut settings.permissions, args);
+            commands::with_prefix(&reply, &settings.prefix)
+        })
+        .await
+    }
+
+    async fn config(&self, ctx: &Context, msg: &Message, args: &str) -> bool {
+        let mut categories: Vec<String> = self
+            .state
+            .quote_categories
+            .read()
+            .await
+            .keys()
+            .cloned()
+            .collect();
+        categories.sort();
+
+        self.update_guild_settings(ctx, msg, "config", |settings| {
+            settings.apply_command(args, &categories)
+        })
+        .await
+    }
+
+    async fn unfurl(&self, ctx: &Context, msg: &Message, args: &str) -> bool {
+        let channel = msg.channel_id;
+        self.update_guild_settings(ctx, msg, "unfurl", |settings| {
+            settings.apply_unfurl(channel, args)
+        })
+        .await
+    }
+
+    /// Reply with a summary of the tickets a message mentions, in channels
+    /// that opted in with `!unfurl on`.
+    async fn unfurl_tickets(&self, ctx: &Context, msg: &Message, settings: &GuildSettings) {
+        if msg.guild_id.is_none() || !settings.unfurl_channels.contains(&msg.channel_id) {
+            return;
+        }
+        let config = &self.state.config.unfurl;
+        let references = unfurl::find_references(&msg.content, config);
+        if references.is_empty() {
+            return;
+        }
+
+        let mut tickets = Vec::new();
+        for reference in references {
+            let cached = self
+                .state
+                .tickets
+                .lock()
+                .await
+                .get(&reference, Instant::now());
+            let ticket = match cached {
+                Some(ticket) => ticket,
+                None => {
+                    let Some(source) = config.source_for(&reference) else {
+                        continue;
+                    };
+                    match unfurl::lookup(source, &reference).await {
+                        Ok(ticket) => {
+                            self.state.tickets.lock().await.insert(
+                                reference.clone(),
+                                ticket.clone(),
+                                Instant::now(),
+                            );
+                            ticket
+                        }
+                        Err(e) => {
+                            error!(error = ?e, reference = %reference, "Failed to look up ticket");
+                            None
+                        }
+                    }
+                }
+            };
+            tickets.extend(ticket);
+        }
+        if tickets.is_empty() {
+            return;
+        }
+
+        info!(
+            channel_id = %msg.channel_id,
+            ticket_count = tickets.len(),
+            "Unfurling tickets"
+        );
+        self.quiet_reply(ctx, msg, &unfurl::unfurl_message(&tickets))
+            .await;
+    }
+
+    /// Whether the author would get past the admin, enabled and permission
+    /// checks for a command, so `!help` only lists what they can run.
+    fn can_run(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        command: &commands::Command,
+    ) -> bool {
+        if command.admin_only && !self.is_admin(ctx, msg) {
+            return false;
+        }
+
+        let caller = Caller {
+            user: msg.author.id,
+            roles: member_roles(msg),
+            channel: msg.channel_id,
+        };
+        settings.is_enabled(command.name)
+            && self
+                .state
+                .permissions
+                .check(&settings.permissions, command.name, &caller)
+                .is_ok()
+    }
+
+    async fn help(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing help command"
+        );
+
+        let visible: Vec<&commands::Command> = commands::COMMANDS
+            .iter()
+            .filter(|command| self.can_run(ctx, msg, settings, command))
+            .collect();
+
+        let response = match HelpQuery::parse(args, &settings.prefix) {
+            HelpQuery::Page(page) => {
+                let pages = help::pages(&visible, &settings.prefix);
+                match pages.get(page - 1) {
+                    Some(text) => text.clone(),
+                    None => format!("মাত্র {} পেজ আছে। 📄", pages.len()),
+                }
+            }
+            HelpQuery::Command(name) => match commands::find(name) {
+                Some(command) if visible.contains(&command) => {
+                    help::command_help(command, &settings.prefix)
+                }
+                _ => format!("`{}` নামে কোন কমান্ড নাই। 🤔", name),
+            },
+        };
+
+        let messages = output::fit_messages(&response, &self.state.config.output, "help.txt");
+        if let Err(why) = send_all(&ctx.http, msg.channel_id, messages).await {
+            error!(
+                error = ?why,
+                channel_id = %msg.channel_id,
+                user_id = %msg.author.id,
+                "Failed to send help response"
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Record a dispatched command for `!stats`.
+    fn record_usage(
+        &self,
+        user: UserId,
+        guild: Option<GuildId>,
+        channel: ChannelId,
+        command_name: &str,
+        success: bool,
+    ) {
+        let usage = CommandUsage {
+            command: command_name.to_string(),
+            user,
+            guild,
+            channel,
+            used_at: unix_now(),
+            success,
+        };
+
+        // nobody waits on the record, so it doesn't hold up the reply
+        let storage = self.state.storage.clone();
+        tokio::spawn(async move {
+            let command = usage.command.clone();
+            if let Err(e) = storage.run(move |s| s.record_command_usage(&usage)).await {
+                error!(error = ?e, command = %command, "Failed to record command usage");
+            }
+        });
+    }
+
+    async fn stats(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing stats command"
+        );
+
+        let response = match StatsQuery::parse(args, |name| commands::find(name).map(|c| c.name)) {
+            Some(query) => match self
+                .state
+                .storage
+                .run({
+                    let (guild, caller) = (msg.guild_id, msg.author.id);
+                    move |s| stats::report(s, guild, caller, &query, unix_now())
+                })
+                .await
+            {
+                Ok(report) => report,
+                Err(e) => {
+                    error!(error = ?e, "Failed to build stats report");
+                    return false;
+                }
+            },
+            None => Reply::text(commands::with_prefix(
+                "Usage: `!stats [day|week|all]`, `!stats me [day|week|all]`, `!stats <command>`",
+                &settings.prefix,
+            )),
+        };
+
+        // the leaderboard mentions people, nobody wants to be pinged for it
+        let message = response
+            .to_message(settings.embeds)
+            .allowed_mentions(CreateAllowedMentions::new());
+        if let Err(why) = msg.channel_id.send_message(&ctx.http, message).await {
+            error!(
+                error = ?why,
+                channel_id = %msg.channel_id,
+                user_id = %msg.author.id,
+                "Failed to send stats response"
+            );
+            return false;
+        }
+        true
+    }
+
+    /// The timezone a user reads and writes reminder times in.
+    async fn timezone_of(&self, user: UserId) -> Tz {
+        match self.state.storage.run(move |s| s.user_timezone(user)).await {
+            Ok(Some(name)) => reminders::parse_timezone(&name).unwrap_or(DEFAULT_TIMEZONE),
+            Ok(None) => DEFAULT_TIMEZONE,
+            Err(e) => {
+                error!(error = ?e, user_id = %user, "Failed to load timezone");
+                DEFAULT_TIMEZONE
+            }
+        }
+    }
+
+    /// Reply without pinging anyone mentioned in the reply. Long replies are
+    /// split or attached, only the first part references `msg`.
+    async fn quiet_reply(&self, ctx: &Context, msg: &Message, content: &str) -> bool {
+        let messages = output::fit_messages(content, &self.state.config.output, "reply.txt")
+            .into_iter()
+            .enumerate()
+            .map(|(index, message)| {
+                let message = message.allowed_mentions(CreateAllowedMentions::new());
+                if index == 0 {
+                    message.reference_message(msg)
+                } else {
+                    message
+                }
+            })
+            .collect();
+        if let Err(why) = send_all(&ctx.http, msg.channel_id, messages).await {
+            error!(
+                error = ?why,
+                channel_id = %msg.channel_id,
+                user_id = %msg.author.id,
+                "Failed to send reply"
+            );
+            return false;
+        }
+        true
+    }
+
+    async fn remindme(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        let usage = reminders::REMINDME_USAGE;
+        self.set_reminder(ctx, msg, settings, msg.author.id, args, usage)
+            .await
+    }
+
+    async fn remind(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        let (mention, rest) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+        let target = mention
+            .strip_prefix("<@")
+            .and_then(|id| id.strip_suffix('>'))
+            .and_then(|id| id.trim_start_matches('!').parse::<u64>().ok())
+            .filter(|id| *id != 0)
+            .map(UserId::new);
+        let usage = reminders::REMIND_USAGE;
+        let Some(target) = target else {
+            let reply = commands::with_prefix(usage, &settings.prefix);
+            return self.quiet_reply(ctx, msg, &reply).await;
+        };
+        self.set_reminder(ctx, msg, settings, target, rest, usage)
+            .await
+    }
+
+    async fn set_reminder(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        target: UserId,
+        args: &str,
+        usage: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            target_id = %target,
+            channel_id = %msg.channel_id,
+            "Processing reminder command"
+        );
+
+        let tz = self.timezone_of(msg.author.id).await;
+        let now: DateTime<Utc> = Utc::now();
+        let parsed = match reminders::parse(args, now, tz) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let reply = format!("{}\n{}", e, commands::with_prefix(usage, &settings.prefix));
+                return self.quiet_reply(ctx, msg, &reply).await;
+            }
+        };
+
+        let author = msg.author.id;
+        match self
+            .state
+            .storage
+            .run(move |s| s.reminder_count(author))
+            .await
+        {
+            Ok(count) if count >= MAX_PER_USER => {
+                let reply = format!(
+                    "আপনার {} টা রিমাইন্ডার জমে আছে, আগে কিছু `{}reminders cancel` করেন।",
+                    count, settings.prefix
+                );
+                return self.quiet_reply(ctx, msg, &reply).await;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!(error = ?e, user_id = %msg.author.id, "Failed to count reminders");
+                return false;
+            }
+        }
+
+        let reminder = Reminder {
+            id: 0,
+            guild: msg.guild_id,
+            channel: msg.channel_id,
+            author: msg.author.id,
+            target,
+            text: parsed.text,
+            due_at: parsed.due_at.timestamp(),
+            dm: parsed.dm,
+        };
+        let stored = reminder.clone();
+        let id = match self
+            .state
+            .storage
+            .run(move |s| s.add_reminder(&stored))
+            .await
+        {
+            Ok(id) => id,
+            Err(e) => {
+                error!(error = ?e, user_id = %msg.author.id, "Failed to store reminder");
+                return false;
+            }
+        };
+
+        let who = if target == msg.author.id {
+            "আপনাকে".to_string()
+        } else {
+            format!("<@{}> কে", target)
+        };
+        let reply = format!(
+            "⏰ ঠিক আছে, {} মনে করাবো {} (`#{}`)",
+            who,
+            reminders::format_due(reminder.due_at, tz),
+            id
+        );
+        self.quiet_reply(ctx, msg, &reply).await
+    }
+
+    async fn reminders(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        let author = msg.author.id;
+        let words: Vec<&str> = args.split_whitespace().collect();
+        let reply = match words.as_slice() {
+            [] => match self
+                .state
+                .storage
+                .run(move |s| s.reminders_for(author))
+                .await
+            {
+                Ok(list) => reminders::list_message(
+                    &list,
+                    msg.author.id,
+                    self.timezone_of(msg.author.id).await,
+                    &settings.prefix,
+                ),
+                Err(e) => {
+                    error!(error = ?e, user_id = %msg.author.id, "Failed to list reminders");
+                    return false;
+                }
+            },
+            ["cancel", id] => {
+                let Ok(id) = id.trim_start_matches('#').parse::<i64>() else {
+                    let usage = format!("Usage: `{}reminders cancel <id>`", settings.prefix);
+                    return self.quiet_reply(ctx, msg, &usage).await;
+                };
+                match self
+                    .state
+                    .storage
+                    .run(move |s| s.cancel_reminder(id, author))
+                    .await
+                {
+                    Ok(true) => format!("`#{}` বাতিল।", id),
+                    Ok(false) => format!("`#{}` নামে আপনার কোন রিমাইন্ডার নাই।", id),
+                    Err(e) => {
+                        error!(error = ?e, user_id = %msg.author.id, "Failed to cancel reminder");
+                        return false;
+                    }
+                }
+            }
+            _ => commands::with_prefix(
+                "Usage: `!reminders`, `!reminders cancel <id>`",
+                &settings.prefix,
+            ),
+        };
+        self.quiet_reply(ctx, msg, &reply).await
+    }
+
+    async fn timezone(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        let name = args.trim();
+        if name.is_empty() {
+            let reply = format!(
+                "Your timezone is `{}`. Change it with `{}timezone Asia/Dhaka`.",
+                self.timezone_of(msg.author.id).await.name(),
+                settings.prefix
+            );
+            return self.quiet_reply(ctx, msg, &reply).await;
+        }
+
+        let Some(tz) = reminders::parse_timezone(name) else {
+            let reply = format!("`{}` চিনি না। `Asia/Dhaka` বা `UTC` এর মত নাম দেন।", name);
+            return self.quiet_reply(ctx, msg, &reply).await;
+        };
+        let user = msg.author.id;
+        if let Err(e) = self
+            .state
+            .storage
+            .run(move |s| s.set_user_timezone(user, tz.name()))
+            .await
+        {
+            error!(error = ?e, user_id = %msg.author.id, "Failed to save timezone");
+            return false;
+        }
+        info!(user_id = %msg.author.id, timezone = %tz.name(), "Timezone set");
+        let reply = format!("Timezone set to `{}`.", tz.name());
+        self.quiet_reply(ctx, msg, &reply).await
+    }
+
+    /// The newest open standup round of a team `user` is in, optionally
+    /// only for the team called `name`.
+    async fn open_standup_for(
+        &self,
+        user: UserId,
+        name: Option<&str>,
+    ) -> Result<Option<(&TeamConfig, NaiveDate)>, StorageError> {
+        let config = &self.state.config.standup;
+        for (team, day) in self.state.storage.run(|s| s.open_standups()).await? {
+            if name.is_some_and(|name| !name.eq_ignore_ascii_case(&team)) {
+                continue;
+            }
+            if let Some(team) = config.team(&team)
+                && team.is_member(user)
+            {
+                return Ok(Some((team, day)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Take a DM as the answer to the next standup question, when the author
+    /// has one waiting.
+    async fn standup_dm(&self, ctx: &Context, msg: &Message) {
+        let rounds = match self.state.storage.run(|s| s.open_standups()).await {
+            Ok(rounds) => rounds,
+            Err(e) => {
+                error!(error = ?e, "Failed to load open standups");
+                return;
+            }
+        };
+
+        for (name, day) in rounds {
+            let Some(team) = self.state.config.standup.team(&name) else {
+                continue;
+            };
+            if team.delivery != Delivery::Dm || !team.is_member(msg.author.id) {
+                continue;
+            }
+            let round = name.clone();
+            let answered = match self
+                .state
+                .storage
+                .run(move |s| s.standup_answers(&round, day))
+                .await
+            {
+                Ok(answers) => answers.get(&msg.author.id).map_or(0, Vec::len),
+                Err(e) => {
+                    error!(error = ?e, team = %name, "Failed to load standup answers");
+                    return;
+                }
+            };
+            if answered >= team.questions.len() {
+                continue;
+            }
+
+            let (round, user, answer) = (name.clone(), msg.author.id, msg.content.clone());
+            if let Err(e) = self
+                .state
+                .storage
+                .run(move |s| s.add_standup_answer(&round, day, user, answered, &answer))
+                .await
+            {
+                error!(error = ?e, team = %name, user_id = %msg.author.id, "Failed to store standup answer");
+                return;
+            }
+            debug!(team = %name, user_id = %msg.author.id, question = answered, "Standup answer received");
+
+            let reply = if answered + 1 < team.questions.len() {
+                standup::question_message(team, answered + 1)
+            } else {
+                standup::thanks_message(team)
+            };
+            if let Err(why) = msg.channel_id.say(&ctx.http, reply).await {
+                error!(error = ?why, user_id = %msg.author.id, "Failed to send standup question");
+            }
+            return;
+        }
+    }
+
+    async fn standup(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            channel_id = %msg.channel_id,
+            "Processing standup command"
+        );
+
+        let (first, rest) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+        let (name, args) = match self.state.config.standup.team(first) {
+            Some(team) => (Some(team.name.as_str()), rest),
+            None => (None, args),
+        };
+
+        let answers = standup::parse_answers(args);
+        if answers.is_empty() {
+            let usage = commands::with_prefix(standup::USAGE, &settings.prefix);
+            return self.quiet_reply(ctx, msg, &usage).await;
+        }
+
+        let (team, day) = match self.open_standup_for(msg.author.id, name).await {
+            Ok(Some(round)) => round,
+            Ok(None) => {
+                return self
+                    .quiet_reply(ctx, msg, "এখন কোন standup চলছে না। 😴")
+                    .await;
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to load open standups");
+                return false;
+            }
+        };
+
+        let (round, user) = (team.name.clone(), msg.author.id);
+        let answers: Vec<String> = answers.into_iter().take(team.questions.len()).collect();
+        let stored = self
+            .state
+            .storage
+            .run(move |s| {
+                for (index, answer) in answers.iter().enumerate() {
+                    s.add_standup_answer(&round, day, user, index, answer)?;
+                }
+                Ok(())
+            })
+            .await;
+        if let Err(e) = stored {
+            error!(error = ?e, team = %team.name, user_id = %msg.author.id, "Failed to store standup answer");
+            return false;
+        }
+        self.quiet_reply(ctx, msg, &standup::thanks_message(team))
+            .await
+    }
+
+    async fn poll(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            channel_id = %msg.channel_id,
+            "Processing poll command"
+        );
+
+        let parsed = match poll::parse(args) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let usage = commands::with_prefix(poll::USAGE, &settings.prefix);
+                let reply = format!("{}\n{}", e, usage);
+                return self.quiet_reply(ctx, msg, &reply).await;
+            }
+        };
+
+        let closes_at = unix_now() + parsed.duration_secs;
+        let message = CreateMessage::new()
+            .content(poll::poll_message(&parsed, closes_at))
+            .allowed_mentions(CreateAllowedMentions::new());
+        let posted = match msg.channel_id.send_message(&ctx.http, message).await {
+            Ok(posted) => posted,
+            Err(why) => {
+                error!(error = ?why, channel_id = %msg.channel_id, "Failed to post poll");
+                return false;
+            }
+        };
+
+        let poll = Poll {
+            id: 0,
+            guild: msg.guild_id,
+            channel: msg.channel_id,
+            message: posted.id,
+            author: msg.author.id,
+            question: parsed.question,
+            options: parsed.options,
+            multi: parsed.multi,
+            closes_at,
+        };
+        let stored = poll.clone();
+        if let Err(e) = self.state.storage.run(move |s| s.add_poll(&stored)).await {
+            error!(error = ?e, "Failed to store poll");
+            return false;
+        }
+
+        let emojis: Vec<String> = poll::NUMBER_EMOJIS[..poll.options.len()]
+            .iter()
+            .map(|emoji| emoji.to_string())
+            .collect();
+        add_reactions(&ctx.http, posted.channel_id, posted.id, 0, &emojis).await;
+        true
+    }
+
+    /// Count (or take back) a vote when the reaction is on an open poll.
+    async fn count_vote(&self, ctx: &Context, reaction: &Reaction, added: bool) {
+        let Some(user) = reaction.user_id else {
+            return;
+        };
+        if user == ctx.cache.current_user().id {
+            return;
+        }
+        let ReactionType::Unicode(emoji) = &reaction.emoji else {
+            return;
+        };
+        let Some(option) = poll::option_for(emoji) else {
+            return;
+        };
+        let message = reaction.message_id;
+        let poll = match self.state.storage.run(move |s| s.open_poll(message)).await {
+            Ok(Some(poll)) if option < poll.options.len() => poll,
+            Ok(_) => return,
+            Err(e) => {
+                error!(error = ?e, "Failed to look up poll");
+                return;
+            }
+        };
+
+        if !added {
+            let id = poll.id;
+            if let Err(e) = self
+                .state
+                .storage
+                .run(move |s| s.remove_vote(id, user, option))
+                .await
+            {
+                error!(error = ?e, poll_id = poll.id, "Failed to remove vote");
+            }
+            return;
+        }
+
+        let (id, multi) = (poll.id, poll.multi);
+        let replaced = match self
+            .state
+            .storage
+            .run(move |s| s.record_vote(id, user, option, multi))
+            .await
+        {
+            Ok(replaced) => replaced,
+            Err(e) => {
+                error!(error = ?e, poll_id = poll.id, "Failed to record vote");
+                return;
+            }
+        };
+        debug!(poll_id = poll.id, user_id = %user, option = option, "Vote recorded");
+
+        // take the old reaction away so the message shows the single vote,
+        // this needs Manage Messages and the count is right without it
+        for old in replaced {
+            let emoji = ReactionType::Unicode(poll::NUMBER_EMOJIS[old].to_string());
+            if let Err(why) = reaction
+                .channel_id
+                .delete_reaction(&ctx.http, reaction.message_id, Some(user), emoji)
+                .await
+            {
+                debug!(error = ?why, poll_id = poll.id, "Couldn't remove replaced vote reaction");
+            }
+        }
+    }
+
+    /// Apply the `@user ++` / `thing--` changes in a guild message.
+    async fn give_karma(&self, ctx: &Context, msg: &Message) {
+        let Some(guild_id) = msg.guild_id else {
+            return;
+        };
+        let changes = karma::find_changes(&msg.content);
+        if changes.is_empty() {
+            return;
+        }
+
+        let mut lines = Vec::new();
+        for change in changes {
+            if change.target == KarmaTarget::User(msg.author.id) {
+                lines.push(karma::SELF_KARMA_MESSAGE.to_string());
+                continue;
+            }
+            let allowed = self.state.karma_cooldown.lock().await.allow(
+                msg.author.id,
+                &change.target,
+                Instant::now(),
+            );
+            if !allowed {
+                debug!(
+                    user_id = %msg.author.id,
+                    target = %change.target.key(),
+                    "Ignoring repeated karma change"
+                );
+                continue;
+            }
+
+            let (target, delta) = (change.target.clone(), change.delta);
+            let score = match self
+                .state
+                .storage
+                .run(move |s| s.add_karma(guild_id, &target, delta))
+                .await
+            {
+                Ok(score) => score,
+                Err(e) => {
+                    error!(error = ?e, target = %change.target.key(), "Failed to store karma");
+                    continue;
+                }
+            };
+            info!(
+                user_id = %msg.author.id,
+                target = %change.target.key(),
+                delta = change.delta,
+                score = score,
+                "Karma changed"
+            );
+            let emoji = EmojiGenerator::new().generate(1).join("");
+            lines.push(karma::score_message(
+                &change.target,
+                change.delta,
+                score,
+                &emoji,
+            ));
+        }
+
+        if !lines.is_empty() {
+            self.quiet_reply(ctx, msg, &lines.join("\n")).await;
+        }
+    }
+
+    async fn karma(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        let Some(guild_id) = msg.guild_id else {
+            return self
+                .quiet_reply(ctx, msg, "Karma only counts in servers.")
+                .await;
+        };
+
+        let reply = match args.trim() {
+            "top" => match self
+                .state
+                .storage
+                .run(move |s| s.karma_top(guild_id, karma::TOP_LIMIT))
+                .await
+            {
+                Ok(top) => karma::leaderboard(&top),
+                Err(e) => {
+                    error!(error = ?e, guild_id = %guild_id, "Failed to load karma leaderboard");
+                    return false;
+                }
+            },
+            target => {
+                let target = if target.is_empty() {
+                    Some(KarmaTarget::User(msg.author.id))
+                } else {
+                    KarmaTarget::parse(target)
+                };
+                let Some(target) = target else {
+                    let usage = commands::with_prefix(karma::USAGE, &settings.prefix);
+                    return self.quiet_reply(ctx, msg, &usage).await;
+                };
+                let stored = target.clone();
+                match self
+                    .state
+                    .storage
+                    .run(move |s| s.karma(guild_id, &stored))
+                    .await
+                {
+                    Ok(score) => format!("{} has **{}** karma", target.label(), score),
+                    Err(e) => {
+                        error!(error = ?e, target = %target.key(), "Failed to load karma");
+                        return false;
+                    }
+                }
+            }
+        };
+        self.quiet_reply(ctx, msg, &reply).await
+    }
+
+    async fn update(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        let text = args.trim();
+        if text.is_empty() {
+            let usage = commands::with_prefix(updates::UPDATE_USAGE, &settings.prefix);
+            return self.quiet_reply(ctx, msg, &usage).await;
+        }
+
+        let update = StatusUpdate {
+            id: 0,
+            guild: msg.guild_id,
+            user: msg.author.id,
+            user_name: msg.author.display_name().to_string(),
+            text: text.to_string(),
+            posted_at: unix_now(),
+        };
+        if let Err(e) = self.state.storage.run(move |s| s.add_update(&update)).await {
+            error!(error = ?e, user_id = %msg.author.id, "Failed to store status update");
+            return false;
+        }
+        info!(user_id = %msg.author.id, guild_id = ?msg.guild_id, "Status update recorded");
+
+        if let Err(why) = msg
+            .react(&ctx.http, ReactionType::Unicode("✅".to_string()))
+            .await
+        {
+            debug!(error = ?why, "Failed to acknowledge status update");
+        }
+        true
+    }
+
+    async fn updates(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        let Some(query) = UpdatesQuery::parse(args) else {
+            let usage = commands::with_prefix(updates::UPDATES_USAGE, &settings.prefix);
+            return self.quiet_reply(ctx, msg, &usage).await;
+        };
+
+        let tz = self.state.config.updates.tz();
+        let now = Utc::now();
+        let since = query.since.timestamp(now, tz);
+        let (guild, user) = (msg.guild_id, query.user);
+        let list = match self
+            .state
+            .storage
+            .run(move |s| s.updates(guild, since, user))
+            .await
+        {
+            Ok(list) => list,
+            Err(e) => {
+                error!(error = ?e, guild_id = ?msg.guild_id, "Failed to load status updates");
+                return false;
+            }
+        };
+
+        if !query.export {
+            return self
+                .quiet_reply(ctx, msg, &updates::list_message(&list, tz))
+                .await;
+        }
+
+        let first_day = Utc
+            .timestamp_opt(since, 0)
+            .single()
+            .map(|start| start.with_timezone(&tz).date_naive())
+            .unwrap_or_else(|| now.date_naive());
+        let title = format!(
+            "Status updates, {} to {}",
+            first_day,
+            now.with_timezone(&tz).date_naive()
+        );
+        let report = updates::markdown(&list, tz, &title);
+        let file =
+            CreateAttachment::bytes(report.into_bytes(), format!("updates-{}.md", first_day));
+        let message = CreateMessage::new()
+            .content(format!("{} update(s)", list.len()))
+            .add_file(file)
+            .reference_message(msg);
+        if let Err(why) = msg.channel_id.send_message(&ctx.http, message).await {
+            error!(error = ?why, channel_id = %msg.channel_id, "Failed to send updates export");
+            return false;
+        }
+        true
+    }
+
+    /// Call out the team members without an update today, or just say the
+    /// line when no team is configured.
+    async fn talha(&self, ctx: &Context, msg: &Message, settings: &GuildSettings) -> bool {
+        let line = commands::simple_reply("talha")
+            .map(|reply| reply.text.get(settings.language))
+            .unwrap_or_default();
+        let config = &self.state.config.updates;
+        if config.members.is_empty() {
+            return self
+                .send_simple_reply(ctx, msg, "talha", line, "talha")
+                .await;
+        }
+
+        let tz = config.tz();
+        let since = Since::Today.timestamp(Utc::now(), tz);
+        let guild = msg.guild_id;
+        let today = match self
+            .state
+            .storage
+            .run(move |s| s.updates(guild, since, None))
+            .await
+        {
+            Ok(today) => today,
+            Err(e) => {
+                error!(error = ?e, guild_id = ?msg.guild_id, "Failed to load status updates");
+                return false;
+            }
+        };
+        let missing = updates::missing(&config.members, &today);
+        info!(
+            user_id = %msg.author.id,
+            missing_count = missing.len(),
+            "Processing talha command"
+        );
+
+        // the point is to ping the people without an update, and only them
+        let message = CreateMessage::new()
+            .content(updates::talha_message(&missing, line, &settings.prefix))
+            .allowed_mentions(CreateAllowedMentions::new().users(missing));
+        if let Err(why) = msg.channel_id.send_message(&ctx.http, message).await {
+            error!(error = ?why, channel_id = %msg.channel_id, "Failed to send talha response");
+            return false;
+        }
+        true
+    }
+
+    async fn sprint(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            channel_id = %msg.channel_id,
+            "Processing sprint command"
+        );
+
+        // days follow the timezone of whoever is asking
+        let today = Utc::now()
+            .with_timezone(&self.timezone_of(msg.author.id).await)
+            .date_naive();
+        let command = match SprintCommand::parse(args, today) {
+            Ok(command) => command,
+            Err(e) => {
+                let reply =
+                    commands::with_prefix(&format!("{}\n{}", e, sprint::USAGE), &settings.prefix);
+                return self.quiet_reply(ctx, msg, &reply).await;
+            }
+        };
+
+        let (channel, sprint_settings) = (msg.channel_id, settings.clone());
+        let ran = self
+            .state
+            .storage
+            .run(move |s| Self::run_sprint_command(s, channel, &sprint_settings, command, today))
+            .await;
+        match ran {
+            Ok(reply) => self.quiet_reply(ctx, msg, &reply).await,
+            Err(e) => {
+                error!(error = ?e, channel_id = %msg.channel_id, "Sprint command failed");
+                false
+            }
+        }
+    }
+
+    /// Run a sprint action against storage. Blocking, see [`Storage::run`].
+    fn run_sprint_command(
+        storage: &Storage,
+        channel: ChannelId,
+        settings: &GuildSettings,
+        command: SprintCommand,
+        today: NaiveDate,
+    ) -> Result<String, StorageError> {
+        let no_sprint = || {
+            format!(
+                "No sprint running here, `{}sprint start <name> <end>` one.",
+                settings.prefix
+            )
+        };
+
+        Ok(match command {
+            SprintCommand::Start { name, ends_on } => {
+                if let Some(active) = storage.active_sprint(channel)? {
+                    return Ok(format!(
+                        "**{}** is still running here, `{}sprint end` it first.",
+                        active.name, settings.prefix
+                    ));
+                }
+                let sprint = Sprint {
+                    id: 0,
+                    channel,
+                    name,
+                    started_on: today,
+                    ends_on,
+                    goals: Vec::new(),
+                };
+                match storage.start_sprint(&sprint)? {
+                    Some(_) => format!(
+                        "🏃 **{}** started, it ends {}. Add goals with `{2}sprint goal` and work with `{2}sprint add`.",
+                        sprint.name,
+                        sprint.ends_on.format("%a %d %b"),
+                        settings.prefix
+                    ),
+                    None => "A sprint is already running here.".to_string(),
+                }
+            }
+            SprintCommand::Status => {
+                let Some(active) = storage.active_sprint(channel)? else {
+                    return Ok(no_sprint());
+                };
+                let items = storage.sprint_items(active.id)?;
+                let joke = commands::simple_reply("sprint")
+                    .map(|reply| reply.text.get(settings.language))
+                    .unwrap_or_default();
+                let flavor = sprint::flavor(joke);
+                sprint::status_message(&active, &items, today, &flavor, &settings.prefix)
+            }
+            SprintCommand::Goal(goal) => {
+                let Some(mut active) = storage.active_sprint(channel)? else {
+                    return Ok(no_sprint());
+                };
+                active.goals.push(goal);
+                storage.set_sprint_goals(active.id, &active.goals)?;
+                format!("🎯 Goal {} added.", active.goals.len())
+            }
+            SprintCommand::Add(title) => {
+                let Some(active) = storage.active_sprint(channel)? else {
+                    return Ok(no_sprint());
+                };
+                let number = storage.add_sprint_item(active.id, &title, today)?;
+                format!("☐ `#{}` {}", number, title)
+            }
+            SprintCommand::Done(number) => {
+                let Some(active) = storage.active_sprint(channel)? else {
+                    return Ok(no_sprint());
+                };
+                if storage.complete_sprint_item(active.id, number, today)? {
+                    format!("☑ `#{}` done!", number)
+                } else {
+                    format!("No open item `#{}` in this sprint.", number)
+                }
+            }
+            SprintCommand::End => {
+                let Some(active) = storage.active_sprint(channel)? else {
+                    return Ok(no_sprint());
+                };
+                let items = storage.sprint_items(active.id)?;
+                storage.end_sprint(active.id)?;
+                info!(sprint_id = active.id, channel_id = %channel, "Sprint ended");
+                sprint::retrospective(&active, &items, today)
+            }
+        })
+    }
+
+    /// File an issue with the configured tracker and return its link.
+    async fn file_issue(&self, issue: &NewIssue) -> Result<String, TrackerError> {
+        let config = &self.state.config.tracker;
+        if !config.is_configured() {
+            return Err(TrackerError::NotConfigured);
+        }
+        let request = tracker::issue_request(config, issue)?;
+        tracker::create_issue(config, &request).await
+    }
+
+    async fn qa(&self, ctx: &Context, msg: &Message, settings: &GuildSettings, args: &str) -> bool {
+        let line = commands::simple_reply("QA")
+            .map(|reply| reply.text.get(settings.language))
+            .unwrap_or_default();
+        if args.trim().is_empty() || !self.state.config.tracker.is_configured() {
+            return self.send_simple_reply(ctx, msg, "QA", line, "qa").await;
+        }
+
+        let Some(issue) = NewIssue::parse(args) else {
+            let usage = commands::with_prefix(tracker::QA_USAGE, &settings.prefix);
+            return self.quiet_reply(ctx, msg, &usage).await;
+        };
+        info!(
+            user_id = %msg.author.id,
+            channel_id = %msg.channel_id,
+            title = %issue.title,
+            "Processing QA command"
+        );
+        let issue = issue.reported_from(msg.author.display_name(), &msg.link());
+
+        let typing = msg.channel_id.start_typing(&ctx.http);
+        let filed = self.file_issue(&issue).await;
+        typing.stop();
+
+        match filed {
+            Ok(link) => {
+                self.quiet_reply(ctx, msg, &format!("🐞 Filed: {}", link))
+                    .await
+            }
+            Err(e) => {
+                error!(error = ?e, user_id = %msg.author.id, "Failed to file issue");
+                self.quiet_reply(ctx, msg, "টিকেট করা গেল না, পরে আবার চেষ্টা করেন। 😓")
+                    .await;
+                false
+            }
+        }
+    }
 
-// Define a struct to hold our event handler.
-// It doesn't need any data for this simple bot.
-struct Handler {
-    state: Arc<BotState>,
-}
+    /// The message context action that files a message as a bug.
+    async fn file_as_bug(&self, ctx: &Context, command: &CommandInteraction) {
+        let Some(ResolvedTarget::Message(target)) = command.data.target() else {
+            return;
+        };
 
-impl Handler {
-    pub fn new(state: Arc<BotState>) -> Self {
-        Self { state }
+        // the same checks `!QA` goes through
+        let roles = command
+            .member
+            .as_ref()
+            .map(|member| member.roles.as_slice())
+            .unwrap_or_default();
+        let caller = Caller {
+            user: command.user.id,
+            roles,
+            channel: command.channel_id,
+        };
+        if let Err(refusal) = self
+            .interaction_refusal(command.guild_id, &caller, "QA")
+            .await
+        {
+            info!(user_id = %command.user.id, channel_id = %command.channel_id, "Refused file as bug");
+            let response = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(refusal)
+                    .ephemeral(true),
+            );
+            if let Err(why) = command.create_response(&ctx.http, response).await {
+                error!(error = ?why, "Failed to answer refused file as bug");
+            }
+            return;
+        }
+
+        info!(
+            user_id = %command.user.id,
+            message_id = %target.id,
+            "Filing message as bug"
+        );
+
+        // filing can take longer than the 3 seconds Discord waits for an answer
+        if let Err(why) = command.defer_ephemeral(&ctx.http).await {
+            error!(error = ?why, "Failed to acknowledge file as bug");
+            return;
+        }
+
+        let content = match NewIssue::from_message(&target.content) {
+            Some(issue) => {
+                let issue = issue.reported_from(command.user.display_name(), &target.link());
+                match self.file_issue(&issue).await {
+                    Ok(link) => format!("🐞 Filed: {}", link),
+                    Err(e) => {
+                        error!(error = ?e, user_id = %command.user.id, "Failed to file issue");
+                        "টিকেট করা গেল না, পরে আবার চেষ্টা করেন। 😓".to_string()
+                    }
+                }
+            }
+            None => "That message has no text to file.".to_string(),
+        };
+        if let Err(why) = command
+            .edit_response(&ctx.http, EditInteractionResponse::new().content(content))
+            .await
+        {
+            error!(error = ?why, "Failed to answer file as bug");
+        }
     }
 
-    async fn send_simple_reply(
+    async fn summon(
         &self,
         ctx: &Context,
         msg: &Message,
-        command_name: &str,
-        response: &str,
-        error_note: &str,
-    ) {
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
         info!(
             user_id = %msg.author.id,
             username = %msg.author.name,
             channel_id = %msg.channel_id,
-            "Processing {} command",
-            command_name
+            mentions_count = msg.mentions.len(),
+            role_mentions_count = msg.mention_roles.len(),
+            "Processing summon command"
+        );
+
+        let request = SummonRequest::parse(args);
+        let users: Vec<UserId> = msg.mentions.iter().map(|user| user.id).collect();
+
+        let emoji_generator: EmojiGenerator = EmojiGenerator::new();
+        // Generate a list of unique emojis.
+        let unique_emojis: Vec<String> = emoji_generator.generate(7);
+        // Convert the emojis to a single string.
+        let result: String = unique_emojis.join(" ");
+
+        debug!(emojis = %result, "Generated emojis for summon command");
+
+        let reacted = match settings.emoji_mode {
+            EmojiMode::Reactions => self.react_with(ctx, msg, &unique_emojis).await,
+            EmojiMode::Text => 0,
+        };
+        let emojis = if reacted > 0 { "" } else { result.as_str() };
+
+        let response = summon::summon_message(&users, &msg.mention_roles, emojis, &request);
+        // only ping who was summoned, even if the reason mentions someone else
+        let message = CreateMessage::new().content(response).allowed_mentions(
+            CreateAllowedMentions::new()
+                .users(users.clone())
+                .roles(msg.mention_roles.clone()),
         );
 
-        if let Err(why) = msg.channel_id.say(&ctx.http, response).await {
+        if let Err(why) = msg.channel_id.send_message(&ctx.http, message).await {
             error!(
                 error = ?why,
                 channel_id = %msg.channel_id,
                 user_id = %msg.author.id,
-                "{}",
-                error_note
+                "Failed to send summon command response"
             );
+            return false;
+        }
+
+        info!(
+            channel_id = %msg.channel_id,
+            user_id = %msg.author.id,
+            emoji_count = unique_emojis.len(),
+            urgent = request.urgent,
+            "Successfully sent summon command response"
+        );
+
+        if request.urgent {
+            let delay = Duration::from_secs(self.state.config.summon.escalation_delay_secs);
+            tokio::spawn(escalate_summon(
+                ctx.http.clone(),
+                msg.clone(),
+                users,
+                request,
+                delay,
+            ));
         }
+        true
     }
-}
 
-// Implement the `EventHandler` trait for our `Handler` struct.
-// This trait defines how our bot will react to different events from Discord.
-#[async_trait]
-impl EventHandler for Handler {
-    // This method is called when a new message is created in a channel the bot can see.
-    async fn message(&self, ctx: Context, msg: Message) {
-        // Check for the `!summon` command.
-        // The `starts_with` method checks if the message begins with the specified string.
-        // We also check that the `mentions` vector is not empty to ensure a user was tagged.
-        if msg.content.starts_with("!summon") && !msg.mentions.is_empty() {
-            info!(
-                user_id = %msg.author.id,
-                username = %msg.author.name,
-                channel_id = %msg.channel_id,
-                mentions_count = msg.mentions.len(),
-                "Processing summon command"
-            );
+    async fn oracle(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        settings: &GuildSettings,
+        args: &str,
+    ) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing oracle command"
+        );
+
+        let unique_emojis = oracle_emojis();
+        debug!(
+            emoji_count = unique_emojis.len(),
+            "Generated emojis for oracle command"
+        );
 
-            let emoji_generator: EmojiGenerator = EmojiGenerator::new();
-            // Generate a list of unique emojis.
-            let unique_emojis: Vec<String> = emoji_generator.generate(7);
-            // Convert the emojis to a single string.
-            let result: String = unique_emojis.join(" ");
+        // `!oracle react` or `!oracle text` overrides the guild's mode once
+        let mode = EmojiMode::parse(args).unwrap_or(settings.emoji_mode);
+        if mode == EmojiMode::Reactions && self.react_with(ctx, msg, &unique_emojis).await > 0 {
+            return true;
+        }
 
-            debug!(emojis = %result, "Generated emojis for summon command");
+        let message = CreateMessage::new().content(unique_emojis.join(" "));
+        self.send_with_buttons(ctx, msg.channel_id, "oracle", message, false)
+            .await
+            .is_some()
+    }
 
-            if let Err(why) = msg.channel_id.say(&ctx.http, &result).await {
+    /// Send a reply that carries an "Another" button, and for jokes a reveal
+    /// button, until they time out.
+    async fn send_with_buttons(
+        &self,
+        ctx: &Context,
+        channel: ChannelId,
+        command: &'static str,
+        message: CreateMessage,
+        reveal: bool,
+    ) -> Option<Message> {
+        let message = message.components(buttons::buttons(command, reveal, false));
+        match channel.send_message(&ctx.http, message).await {
+            Ok(sent) => {
+                info!(
+                    channel_id = %channel,
+                    message_id = %sent.id,
+                    "Successfully sent {} response",
+                    command
+                );
+                tokio::spawn(expire_buttons(
+                    ctx.http.clone(),
+                    self.state.clone(),
+                    channel,
+                    sent.id,
+                    command,
+                ));
+                Some(sent)
+            }
+            Err(why) => {
                 error!(
                     error = ?why,
-                    channel_id = %msg.channel_id,
-                    user_id = %msg.author.id,
-                    "Failed to send summon command response"
-                );
-            } else {
-                info!(
-                    channel_id = %msg.channel_id,
-                    user_id = %msg.author.id,
-                    emoji_count = unique_emojis.len(),
-                    "Successfully sent summon command response"
+                    channel_id = %channel,
+                    "Failed to send {} response",
+                    command
                 );
+                None
             }
         }
+    }
 
-        // check for the !oracle command
-        if msg.content.starts_with("!oracle") {
-            info!(
-                user_id = %msg.author.id,
-                username = %msg.author.name,
+    /// React with the emojis to the message the command replies to, or to the
+    /// command itself. Returns how many reactions were added so callers can
+    /// fall back to text when none were.
+    async fn react_with(&self, ctx: &Context, msg: &Message, emojis: &[String]) -> usize {
+        let target = msg.referenced_message.as_deref().unwrap_or(msg);
+        add_reactions(
+            &ctx.http,
+            target.channel_id,
+            target.id,
+            target.reactions.len(),
+            emojis,
+        )
+        .await
+    }
+
+    /// React with approving emojis to pull request links in the watched
+    /// channels. Each message only gets them once, edits included.
+    async fn approve_pull_requests(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        content: &str,
+    ) {
+        let config = &self.state.config.pr_reactions;
+        if !config.matches(channel_id, content) {
+            return;
+        }
+
+        if !self.state.pr_reacted.lock().await.insert(message_id) {
+            debug!(message_id = %message_id, "Already reacted to pull request message");
+            return;
+        }
+
+        info!(
+            channel_id = %channel_id,
+            message_id = %message_id,
+            "Approving pull request link"
+        );
+        let emojis = EmojiGenerator::new().generate(config.emoji_count);
+        add_reactions(&ctx.http, channel_id, message_id, 0, &emojis).await;
+    }
+
+    async fn approve(&self, ctx: &Context, msg: &Message, args: &str) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing approve command"
+        );
+
+        let style = if args.is_empty() {
+            Some(Style::default())
+        } else {
+            Style::parse(args)
+        };
+        let Some(style) = style else {
+            let usage = "Style হইলো `short`, `enthusiastic` অথবা `sarcastic`।";
+            if let Err(why) = msg.reply(&ctx.http, usage).await {
+                error!(error = ?why, "Failed to send approve usage");
+            }
+            return false;
+        };
+
+        let approval = approve::approval(&self.state.config.approve, style);
+        // approve the message being replied to, if there is one
+        let sent = match &msg.referenced_message {
+            Some(target) => target.reply(&ctx.http, &approval).await,
+            None => msg.channel_id.say(&ctx.http, &approval).await,
+        };
+
+        if let Err(why) = sent {
+            error!(
+                error = ?why,
                 channel_id = %msg.channel_id,
-                "Processing oracle command"
+                user_id = %msg.author.id,
+                "Failed to send approve response"
             );
+            return false;
+        }
+        true
+    }
+
+    async fn joke(&self, ctx: &Context, msg: &Message, settings: &GuildSettings) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing joke command"
+        );
 
-            let emoji_generator: EmojiGenerator = EmojiGenerator::new();
-            let emoji_count = rand::rng().random_range(5..=15);
-            let unique_emojis: Vec<String> = emoji_generator.generate(emoji_count);
-            let result: String = unique_emojis.join(" ");
+        self.send_joke(ctx, msg.channel_id, settings.embeds).await
+    }
 
-            debug!(
-                emojis = %result,
-                emoji_count = emoji_count,
-                "Generated emojis for oracle command"
-            );
+    /// Send a joke's setup, the punchline follows as a reply.
+    async fn send_joke(&self, ctx: &Context, channel: ChannelId, embeds: bool) -> bool {
+        match fetch_joke().await {
+            Ok(fetched) => {
+                let Some(joke) = fetched.first() else {
+                    error!(channel_id = %channel, "No jokes found in the response");
+                    return false;
+                };
+                let setup = jokes::setup_reply(&joke.setup).to_message(embeds);
+                let Some(sent) = self
+                    .send_with_buttons(ctx, channel, "joke", setup, true)
+                    .await
+                else {
+                    return false;
+                };
+                self.state
+                    .punchlines
+                    .lock()
+                    .await
+                    .hold(sent.id, &joke.punchline);
+                tokio::spawn(deliver_punchline(
+                    ctx.http.clone(),
+                    self.state.clone(),
+                    channel,
+                    sent.id,
+                    embeds,
+                ));
+                true
+            }
+            Err(e) => {
+                error!(error = ?e, channel_id = %channel, "Failed to fetch joke");
+                false
+            }
+        }
+    }
+
+    async fn health(&self, ctx: &Context, msg: &Message, settings: &GuildSettings) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing health command"
+        );
 
-            if let Err(why) = msg.channel_id.say(&ctx.http, &result).await {
+        match health_checker::check(&msg.content).await {
+            Ok(reply) => {
+                let message = reply.to_message(settings.embeds);
+                if let Err(why) = msg.channel_id.send_message(&ctx.http, message).await {
+                    error!(error = ?why, "Failed to send health response");
+                    return false;
+                }
+                // a service that's down is worth an alert where people look for them
+                if reply.down
+                    && let Some(alert) = settings.alert_channel
+                    && alert != msg.channel_id
+                {
+                    let message = reply.to_message(settings.embeds);
+                    if let Err(why) = alert.send_message(&ctx.http, message).await {
+                        error!(error = ?why, channel_id = %alert, "Failed to send health alert");
+                    }
+                }
+                true
+            }
+            Err(e) => {
                 error!(
-                    error = ?why,
-                    channel_id = %msg.channel_id,
-                    user_id = %msg.author.id,
-                    emoji_count = emoji_count,
-                    "Failed to send oracle command response"
-                );
-            } else {
-                info!(
+                    error = ?e,
                     channel_id = %msg.channel_id,
                     user_id = %msg.author.id,
-                    emoji_count = emoji_count,
-                    "Successfully sent oracle command response"
+                    "Health check failed"
                 );
+                false
             }
         }
+    }
 
-        // Check for the `!joke` command.
-        if msg.content.starts_with("!joke") {
-            info!(
-                user_id = %msg.author.id,
-                username = %msg.author.name,
-                channel_id = %msg.channel_id,
-                "Processing joke command"
-            );
+    async fn guysay(&self, ctx: &Context, msg: &Message, settings: &GuildSettings) -> bool {
+        info!(
+            user_id = %msg.author.id,
+            username = %msg.author.name,
+            channel_id = %msg.channel_id,
+            "Processing guysay command"
+        );
 
-            match fetch_joke().await {
-                Ok(jokes) => {
-                    if let Some(joke) = jokes.first() {
-                        let response = format!("🎭 **{}**\n💡 _{}_", joke.setup, joke.punchline);
-
-                        if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-                            error!(
-                                error = ?why,
-                                channel_id = %msg.channel_id,
-                                user_id = %msg.author.id,
-                                "Failed to send joke response"
-                            );
-                        } else {
-                            info!(
-                                channel_id = %msg.channel_id,
-                                user_id = %msg.author.id,
-                                "Successfully sent joke response"
-                            );
-                        }
-                    } else {
-                        error!(
-                            channel_id = %msg.channel_id,
-                            user_id = %msg.author.id,
-                            "No jokes found in the response"
-                        );
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        error = ?e,
-                        channel_id = %msg.channel_id,
-                        user_id = %msg.author.id,
-                        "Failed to fetch joke"
-                    );
+        self.send_guysay(ctx, msg.channel_id, settings).await
+    }
+
+    async fn send_guysay(
+        &self,
+        ctx: &Context,
+        channel: ChannelId,
+        settings: &GuildSettings,
+    ) -> bool {
+        let skip_long = self.state.config.guysay.skip_long;
+        let pick = |quotes: &[String]| {
+            skip_long
+                .then(|| say_within(quotes, true, MESSAGE_LIMIT))
+                .flatten()
+                .unwrap_or_else(|| say(quotes, true))
+        };
+        let response = match &settings.quote_category {
+            Some(category) => {
+                let categories = self.state.quote_categories.read().await;
+                match categories.get(category) {
+                    Some(quotes) if !quotes.is_empty() => pick(quotes),
+                    _ => pick(&self.state.quotes.read().await),
                 }
             }
+            None => pick(&self.state.quotes.read().await),
+        };
+
+        // the buttons go under the last part of a long one
+        let mut messages = output::fit_messages(&response, &self.state.config.output, "guysay.txt");
+        let last = messages.pop().unwrap_or_default();
+        if let Err(why) = send_all(&ctx.http, channel, messages).await {
+            error!(error = ?why, channel_id = %channel, "Failed to send guysay response");
+            return false;
         }
+        self.send_with_buttons(ctx, channel, "guysay", last, false)
+            .await
+            .is_some()
+    }
 
-        if msg.content.starts_with("!health") {
-            info!(
-                user_id = %msg.author.id,
-                username = %msg.author.name,
-                channel_id = %msg.channel_id,
-                "Processing health command"
-            );
+    /// Answer a button press on a joke, oracle or guysay reply.
+    async fn press_button(&self, ctx: &Context, component: &ComponentInteraction) {
+        let Some(action) = ButtonAction::parse(&component.data.custom_id) else {
+            return;
+        };
+        info!(
+            user_id = %component.user.id,
+            channel_id = %component.channel_id,
+            button = %component.data.custom_id,
+            "Processing button press"
+        );
 
-            // avoid ownership movement by cloning msg!
-            match check_health(msg.clone().content).await {
-                Ok(status) => {
-                    if let Err(why) = msg.channel_id.say(&ctx.http, &status).await {
-                        error!(error = ?why, "Failed to send health response");
+        let command = match action {
+            ButtonAction::Reveal => {
+                let setup = component.message.id;
+                if !self.state.punchlines.lock().await.is_pending(setup) {
+                    // the bot restarted, or someone else was faster
+                    let response = CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content("পাঞ্চলাইন আর নাই। 🤷")
+                            .ephemeral(true),
+                    );
+                    if let Err(why) = component.create_response(&ctx.http, response).await {
+                        error!(error = ?why, "Failed to answer reveal button");
                     }
+                    return;
                 }
-                Err(e) => {
-                    error!(
-                        error = ?e,
-                        channel_id = %msg.channel_id,
-                        user_id = %msg.author.id,
-                        "Health check failed"
-                    );
+                if let Err(why) = component
+                    .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+                    .await
+                {
+                    error!(error = ?why, "Failed to acknowledge reveal button");
                 }
+                let embeds = self
+                    .state
+                    .guild_settings
+                    .read()
+                    .await
+                    .get(component.guild_id)
+                    .embeds;
+                post_punchline(&ctx.http, &self.state, component.channel_id, setup, embeds).await;
+                return;
             }
-        }
+            ButtonAction::Another(command) => command,
+        };
 
+        let Some(settings) = self.allow_button(ctx, component, command).await else {
+            return;
+        };
+        if let Err(why) = component
+            .create_response(&ctx.http, CreateInteractionResponse::Acknowledge)
+            .await
+        {
+            error!(error = ?why, "Failed to acknowledge button press");
+            return;
+        }
 
-        if msg.content.starts_with("!guysay") {
-            info!(
-                user_id = %msg.author.id,
-                username = %msg.author.name,
-                channel_id = %msg.channel_id,
-                "Processing guysay command"
-            );
+        let channel = component.channel_id;
+        let success = match command {
+            "joke" => self.send_joke(ctx, channel, settings.embeds).await,
+            "oracle" => {
+                let message = CreateMessage::new().content(oracle_emojis().join(" "));
+                self.send_with_buttons(ctx, channel, "oracle", message, false)
+                    .await
+                    .is_some()
+            }
+            "guysay" => self.send_guysay(ctx, channel, &settings).await,
+            _ => return,
+        };
+        self.record_usage(
+            component.user.id,
+            component.guild_id,
+            channel,
+            command,
+            success,
+        );
+    }
 
-            let quotes = self.state.quotes.read().await;
-            let response = say(&quotes, true);
+    /// The enabled, permission and rate limit checks of a command, for a
+    /// button that runs it again. Refusals are answered only to the presser.
+    async fn allow_button(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        command: &str,
+    ) -> Option<GuildSettings> {
+        let roles = component
+            .member
+            .as_ref()
+            .map(|member| member.roles.as_slice())
+            .unwrap_or_default();
+        let caller = Caller {
+            user: component.user.id,
+            roles,
+            channel: component.channel_id,
+        };
+        let refusal = match self
+            .interaction_refusal(component.guild_id, &caller, command)
+            .await
+        {
+            Ok(settings) => return Some(settings),
+            Err(refusal) => refusal,
+        };
 
-            if let Err(why) = msg.channel_id.say(&ctx.http, &response).await {
-                error!(
-                    error = ?why,
-                    channel_id = %msg.channel_id,
-                    user_id = %msg.author.id,
-                    "Failed to send guysay response"
-                );
-            }
+        info!(
+            user_id = %component.user.id,
+            channel_id = %component.channel_id,
+            "Refused {} button",
+            command
+        );
+        let response = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(refusal)
+                .ephemeral(true),
+        );
+        if let Err(why) = component.create_response(&ctx.http, response).await {
+            error!(error = ?why, "Failed to answer refused button press");
         }
+        None
+    }
 
-        // just single string return blocks
-        if msg.content.starts_with("!gaysay") {
-            // Disclaimer: this command isn't used to demean people of any community or class
-            // :)
-            // but this is a typo that people willfully are going to make given the name of the bot
-            // besides if you're homophobic, get some help!
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "gaysay",
-                    "ব্রো, এসো তোমাকে ব্লেম দেই <3 ",
-                    "Cheeky!",
-                )
-                .await;
-        }
+    /// The guild's settings when `caller` may run `command` from an
+    /// interaction, or the refusal to show them.
+    async fn interaction_refusal(
+        &self,
+        guild: Option<GuildId>,
+        caller: &Caller<'_>,
+        command: &str,
+    ) -> Result<GuildSettings, String> {
+        let settings = self.state.guild_settings.read().await.get(guild);
 
-        if msg.content.starts_with("!sprint") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "sprint",
-                    "Sprint in the AI world means, really fast.",
-                    "The sprint failed perhaps?",
-                )
-                .await;
+        if !settings.is_enabled(command) {
+            return Err("এই কমান্ড এখানে বন্ধ। 🚫".to_string());
+        }
+        if let Err(denied) = self
+            .state
+            .permissions
+            .check(&settings.permissions, command, caller)
+        {
+            return Err(denied_message(&denied, settings.language).to_string());
         }
+        let mut limiter = self.state.rate_limiter.lock().await;
+        if !limiter.is_exempt(caller.roles)
+            && let Err(throttled) =
+                limiter.check(command, caller.user, caller.channel, Instant::now())
+        {
+            return Err(throttle_message(&throttled, settings.language));
+        }
+        Ok(settings)
+    }
+}
 
-        if msg.content.starts_with("!no") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "no",
-                    "The no word has deep philosophical meaning to me. It tells me that I can tell anyone, no. Nobody can stop me.",
-                    "No!",
-                )
+// Implement the `EventHandler` trait for our `Handler` struct.
+// This trait defines how our bot will react to different events from Discord.
+#[async_trait]
+impl EventHandler for Handler {
+    // This method is called when a new message is created in a channel the bot can see.
+    async fn message(&self, ctx: Context, msg: Message) {
+        if !msg.author.bot {
+            self.approve_pull_requests(&ctx, msg.channel_id, msg.id, &msg.content)
                 .await;
         }
 
-        if msg.content.starts_with("!breakfast") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "breakfast",
-                    "I had granola and corn flakes this breakfast, but decided to add AI on top of it anyway.",
-                    "breakfast",
-                )
-                .await;
+        let settings = self.state.guild_settings.read().await.get(msg.guild_id);
+
+        let Some(invocation) = commands::parse(&msg.content, &settings.prefix) else {
+            if msg.guild_id.is_none() && !msg.author.bot {
+                self.standup_dm(&ctx, &msg).await;
+            } else if !msg.author.bot {
+                if settings.is_enabled("karma") {
+                    self.give_karma(&ctx, &msg).await;
+                }
+                self.unfurl_tickets(&ctx, &msg, &settings).await;
+            }
+            return;
+        };
+
+        if !settings.is_enabled(invocation.name) {
+            debug!(
+                guild_id = ?msg.guild_id,
+                "Ignoring disabled {} command",
+                invocation.name
+            );
+            return;
         }
 
-        if msg.content.starts_with("!PM") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "PM",
-                    "LONG LIVE THE PM!",
-                    "pm",
-                )
-                .await;
+        // `!summon` only makes sense when someone was tagged.
+        if invocation.name == "summon" && msg.mentions.is_empty() && msg.mention_roles.is_empty() {
+            return;
         }
 
-        if msg.content.starts_with("!QA") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "!QA",
-                    "বাগ পাইসেন? আচ্ছা লিনিয়ারে টিকেট দেন। দেখতেসি বিষয়টা।",
-                    "qa",
-                )
-                .await;
+        if !self
+            .is_permitted(&ctx, &msg, &settings, invocation.name)
+            .await
+        {
+            return;
         }
 
-        if msg.content.starts_with("!abubakar") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "!abubakar",
-                    "All I want for Eid is chunks!",
-                    "abubakar"
-                )
-                .await;
+        if !self
+            .allow_command(&ctx, &msg, &settings, invocation.name)
+            .await
+        {
+            return;
         }
 
-        if msg.content.starts_with("!biriyani") {
-            let url = "https://www.youtube.com/watch?v=xvFZjo5PgG0";
-            let response = format!("🎭 💡 {}\n", url);
+        let success = match invocation.name {
+            "summon" => self.summon(&ctx, &msg, &settings, invocation.args).await,
+            "oracle" => self.oracle(&ctx, &msg, &settings, invocation.args).await,
+            "joke" => self.joke(&ctx, &msg, &settings).await,
+            "health" => self.health(&ctx, &msg, &settings).await,
+            "guysay" => self.guysay(&ctx, &msg, &settings).await,
+            "perm" => self.perm(&ctx, &msg, invocation.args).await,
+            "config" => self.config(&ctx, &msg, invocation.args).await,
+            "unfurl" => self.unfurl(&ctx, &msg, invocation.args).await,
+            "stats" => self.stats(&ctx, &msg, &settings, invocation.args).await,
+            "help" => self.help(&ctx, &msg, &settings, invocation.args).await,
+            "approve" => self.approve(&ctx, &msg, invocation.args).await,
+            "remindme" => self.remindme(&ctx, &msg, &settings, invocation.args).await,
+            "remind" => self.remind(&ctx, &msg, &settings, invocation.args).await,
+            "reminders" => self.reminders(&ctx, &msg, &settings, invocation.args).await,
+            "timezone" => self.timezone(&ctx, &msg, &settings, invocation.args).await,
+            "standup" => self.standup(&ctx, &msg, &settings, invocation.args).await,
+            "poll" => self.poll(&ctx, &msg, &settings, invocation.args).await,
+            "karma" => self.karma(&ctx, &msg, &settings, invocation.args).await,
+            "update" => self.update(&ctx, &msg, &settings, invocation.args).await,
+            "updates" => self.updates(&ctx, &msg, &settings, invocation.args).await,
+            "talha" => self.talha(&ctx, &msg, &settings).await,
+            "sprint" => self.sprint(&ctx, &msg, &settings, invocation.args).await,
+            "QA" => self.qa(&ctx, &msg, &settings, invocation.args).await,
+            name => match commands::simple_reply(name) {
+                Some(reply) => {
+                    self.send_simple_reply(
+                        &ctx,
+                        &msg,
+                        reply.name,
+                        reply.text.get(settings.language),
+                        reply.error_note,
+                    )
+                    .await
+                }
+                None => return,
+            },
+        };
 
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "!biriyani",
-                    response.as_str(),
-                    "biriyani"
-                )
-                .await;
-        }
+        self.record_usage(
+            msg.author.id,
+            msg.guild_id,
+            msg.channel_id,
+            invocation.name,
+            success,
+        );
+    }
 
-        if msg.content.starts_with("!failed") {
-            self.
-                send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "!failed",
-                    "Don't fix it just revert!",
-                    "failed"
-                )
-                .await;
+    // Edits can add a pull request link to a message, so they get checked too.
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _old_if_available: Option<Message>,
+        _new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        if event.author.as_ref().is_some_and(|author| author.bot) {
+            return;
         }
-
-        if msg.content.starts_with("!talha") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "!talha",
-                    "আপডেট ছাড়া আরেকবার ডাকলে বেতন 10% মাইনাস",
-                    "talha"
-                )
+        if let Some(content) = &event.content {
+            self.approve_pull_requests(&ctx, event.channel_id, event.id, content)
                 .await;
         }
+    }
 
-        if msg.content.starts_with("!jiggu") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "!jiggu",
-                    "লোকে বলে আমি প্রোজেক্ট ম্যানেজার কিন্তু আমি আসলে আস্ত অপদার্থ, মুনিয়ার মা, প্লেটে আরো থ্যাপলা দাও, খাই।",
-                    "jiggu"
-                )
-                .await;
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        if reaction.emoji.unicode_eq(THINKING_EMOJI) {
+            let embeds = self
+                .state
+                .guild_settings
+                .read()
+                .await
+                .get(reaction.guild_id)
+                .embeds;
+            let (channel, setup) = (reaction.channel_id, reaction.message_id);
+            if post_punchline(&ctx.http, &self.state, channel, setup, embeds).await {
+                info!(message_id = %setup, "Posted punchline early on a reaction");
+                return;
+            }
         }
+        self.count_vote(&ctx, &reaction, true).await;
+    }
+
+    async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
+        self.count_vote(&ctx, &reaction, false).await;
+    }
 
-        if msg.content.starts_with("!ale") {
-            self
-                .send_simple_reply(
-                    &ctx,
-                    &msg,
-                    "!ale",
-                    "You drink the ale, I EAT THE ALE. We're not same bro!",
-                    "ale"
-                ).await;
+    async fn message_delete(
+        &self,
+        _: Context,
+        _channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        _guild_id: Option<GuildId>,
+    ) {
+        if self
+            .state
+            .punchlines
+            .lock()
+            .await
+            .take(deleted_message_id)
+            .is_some()
+        {
+            debug!(message_id = %deleted_message_id, "Setup deleted, dropped its punchline");
         }
+    }
 
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        match interaction {
+            Interaction::Command(command) if command.data.name == FILE_AS_BUG => {
+                self.file_as_bug(&ctx, &command).await;
+            }
+            Interaction::Component(component) => self.press_button(&ctx, &component).await,
+            _ => {}
+        }
     }
 
     // This method is called when the bot is ready to start receiving events.
-    async fn ready(&self, _: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         // When the bot is ready, we'll log connection details
         info!(
             bot_name = %ready.user.name,
@@ -405,6 +2802,13 @@ impl EventHandler for Handler {
             guild_count = ready.guilds.len(),
             "Discord bot is connected and ready"
         );
+
+        if self.state.config.tracker.is_configured() {
+            let action = CreateCommand::new(FILE_AS_BUG).kind(CommandType::Message);
+            if let Err(why) = ApplicationCommand::create_global_command(&ctx.http, action).await {
+                error!(error = ?why, "Failed to register the file as bug action");
+            }
+        }
     }
 }
 
@@ -425,19 +2829,36 @@ pub async fn run(quotes_folder: &str) {
 
     // Define the intents for our bot. Intents tell Discord which events our bot wants to receive.
     // For this bot, we need `GUILD_MESSAGES` to receive message server events,
-    // and `MESSAGE_CONTENT` to read the content of the messages.
-    let intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
-    info!("Configured bot intents: GUILD_MESSAGES | MESSAGE_CONTENT");
+    // `DIRECT_MESSAGES` for standup answers sent in DMs,
+    // `GUILD_MESSAGE_REACTIONS` to count poll votes,
+    // `MESSAGE_CONTENT` to read the content of the messages,
+    // and `GUILDS` so the cache knows who owns each guild.
+    let intents = GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::DIRECT_MESSAGES
+        | GatewayIntents::GUILD_MESSAGE_REACTIONS
+        | GatewayIntents::MESSAGE_CONTENT;
+    info!(
+        "Configured bot intents: GUILDS | GUILD_MESSAGES | DIRECT_MESSAGES | GUILD_MESSAGE_REACTIONS | MESSAGE_CONTENT"
+    );
+
+    let config = match BotConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            error!(error = ?e, "Failed to load bot config");
+            panic!("Failed to load bot config");
+        }
+    };
 
     // Init bot state
     info!(
         "Initializing bot state with quotes from folder: {}",
         quotes_folder
     );
-    let bot_state = Arc::new(match BotState::new(quotes_folder).await {
+    let bot_state = Arc::new(match BotState::with_config(quotes_folder, config).await {
         Ok(state) => state,
         Err(e) => {
-            error!(error = ?e, "Failed to load quotes from folder: {}", quotes_folder);
+            error!(error = ?e, "Failed to initialize bot state with quotes from folder: {}", quotes_folder);
             panic!("Failed to initialize bot state");
         }
     });
@@ -445,7 +2866,7 @@ pub async fn run(quotes_folder: &str) {
     // Create a new client instance with the token, intents, and our event handler.
     info!("Creating Discord client");
     let mut client = match Client::builder(&token, intents)
-        .event_handler(Handler::new(bot_state))
+        .event_handler(Handler::new(bot_state.clone()))
         .await
     {
         Ok(client) => {
@@ -458,6 +2879,11 @@ pub async fn run(quotes_folder: &str) {
         }
     };
 
+    start_webhook_listener(&bot_state.config.webhook, client.http.clone()).await;
+    start_reminder_scheduler(bot_state.storage.clone(), client.http.clone());
+    start_standup_scheduler(bot_state.clone(), client.http.clone());
+    start_poll_scheduler(bot_state.storage.clone(), client.http.clone());
+
     // Start the client. This will connect to Discord and start listening for events.
     info!("Starting Discord client connection");
     if let Err(why) = client.start().await {
@@ -469,4 +2895,3 @@ pub async fn run(quotes_folder: &str) {
         info!("Discord client shut down gracefully");
     }
 }
-
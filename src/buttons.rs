@@ -0,0 +1,74 @@
+use serde::Deserialize;
+use serenity::builder::{CreateActionRow, CreateButton};
+use serenity::model::application::ButtonStyle;
+
+/// Commands whose replies get an "Another" button.
+pub const WITH_ANOTHER: &[&str] = &["joke", "oracle", "guysay"];
+
+const ANOTHER_PREFIX: &str = "another:";
+const REVEAL_ID: &str = "reveal";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ButtonsConfig {
+    /// How long buttons stay usable before they are disabled.
+    pub timeout_secs: u64,
+}
+
+impl Default for ButtonsConfig {
+    fn default() -> Self {
+        Self { timeout_secs: 300 }
+    }
+}
+
+/// What pressing a button asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonAction {
+    /// Run the command again.
+    Another(&'static str),
+    /// Show the joke's punchline.
+    Reveal,
+}
+
+impl ButtonAction {
+    pub fn parse(custom_id: &str) -> Option<Self> {
+        if custom_id == REVEAL_ID {
+            return Some(Self::Reveal);
+        }
+        let command = custom_id.strip_prefix(ANOTHER_PREFIX)?;
+        WITH_ANOTHER
+            .iter()
+            .find(|name| **name == command)
+            .map(|name| Self::Another(name))
+    }
+
+    pub fn custom_id(&self) -> String {
+        match self {
+            Self::Another(command) => format!("{}{}", ANOTHER_PREFIX, command),
+            Self::Reveal => REVEAL_ID.to_string(),
+        }
+    }
+}
+
+/// The buttons under a reply to `command`. Jokes get a reveal button until
+/// the punchline is out.
+pub fn buttons(command: &'static str, reveal: bool, disabled: bool) -> Vec<CreateActionRow> {
+    let mut row = Vec::new();
+    if reveal {
+        row.push(
+            CreateButton::new(ButtonAction::Reveal.custom_id())
+                .label("Reveal punchline")
+                .emoji('💡')
+                .style(ButtonStyle::Primary)
+                .disabled(disabled),
+        );
+    }
+    row.push(
+        CreateButton::new(ButtonAction::Another(command).custom_id())
+            .label("Another")
+            .emoji('🔁')
+            .style(ButtonStyle::Secondary)
+            .disabled(disabled),
+    );
+    vec![CreateActionRow::Buttons(row)]
+}
@@ -0,0 +1,260 @@
+use crate::language::Text;
+
+/// The default prefix bot commands start with.
+pub const PREFIX: &str = "!";
+
+/// A command the bot dispatches, as listed by `!help`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Command {
+    pub name: &'static str,
+    /// Other names that run the same command.
+    pub aliases: &'static [&'static str],
+    /// Arguments after the command name, empty when it takes none.
+    pub usage: &'static str,
+    pub description: &'static str,
+    /// Only bot admins can run it, so `!help` hides it from everyone else.
+    pub admin_only: bool,
+}
+
+impl Command {
+    const fn new(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            aliases: &[],
+            usage: "",
+            description,
+            admin_only: false,
+        }
+    }
+
+    const fn usage(mut self, usage: &'static str) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    const fn admin_only(mut self) -> Self {
+        self.admin_only = true;
+        self
+    }
+}
+
+/// Every command the bot dispatches, without the prefix.
+pub const COMMANDS: &[Command] = &[
+    Command::new("help", "List the commands, or explain one of them")
+        .usage("[command|page]")
+        .aliases(&["commands"]),
+    Command::new(
+        "summon",
+        "Ping whoever you mention, `urgent` pings again and DMs them if they don't show up",
+    )
+    .usage("@mention... [reason] [urgent]"),
+    Command::new("oracle", "Ask the oracle, get a random set of emojis").usage("[react|text]"),
+    Command::new("joke", "A random joke from JokeAPI"),
+    Command::new(
+        "health",
+        "Hit a backend's health check endpoint and report the status code",
+    )
+    .usage("<service> <environment>"),
+    Command::new("guysay", "A random fortune message in a cowsay style"),
+    Command::new("gaysay", "Don't make this typo"),
+    Command::new(
+        "sprint",
+        "Track this channel's sprint: goals, items and a burndown",
+    )
+    .usage("[start <name> <end>|goal <text>|add <item>|done <number>|end]"),
+    Command::new("no", "Tell your PM that you won't be doing it"),
+    Command::new("breakfast", "Breakfast cereal with AI? Anyone?"),
+    Command::new("PM", "Long live the PM"),
+    Command::new("QA", "Found a bug? File it right here").usage("[<title> | <description>]"),
+    Command::new("abubakar", "All he wants for Eid").aliases(&["abubakr"]),
+    Command::new("biriyani", "🎭 💡").aliases(&["biryani"]),
+    Command::new("failed", "What to do when the build fails"),
+    Command::new("talha", "Call out whoever hasn't posted an update today"),
+    Command::new("jiggu", "The project manager's confession"),
+    Command::new("ale", "We're not same bro"),
+    Command::new("approve", "Approve a PR with a phrase and some emojis")
+        .usage("[short|enthusiastic|sarcastic]"),
+    Command::new("remindme", "Remind yourself of something later")
+        .usage("in 2h|at 17:00|tomorrow [at 10:00] <text> [--dm]"),
+    Command::new("remind", "Remind someone else of something later")
+        .usage("@user in 2h|at 17:00|tomorrow [at 10:00] <text> [--dm]"),
+    Command::new("reminders", "List your reminders, or cancel one").usage("[cancel <id>]"),
+    Command::new("timezone", "Show or set the timezone your reminders use")
+        .usage("[Asia/Dhaka|UTC|...]"),
+    Command::new("standup", "Answer the standup questions in one go")
+        .usage("[team] <yesterday> | <today> | <blockers>"),
+    Command::new("poll", "Let the channel vote on something")
+        .usage("\"question\" option1 option2 ... [--duration 1h] [--multi]"),
+    Command::new("karma", "Show someone's karma, or the leaderboard").usage("[@user|thing|top]"),
+    Command::new("update", "Post a status update").usage("<text>"),
+    Command::new("updates", "List status updates, or export them as Markdown")
+        .usage("[export] [@user] [today|yesterday|week|7d|2024-01-31]"),
+    Command::new("unfurl", "Turn ticket summaries on or off in this channel")
+        .usage("[on|off]")
+        .admin_only(),
+    Command::new("perm", "Change who can run which command where")
+        .usage("allow|deny|reset|show <command|group> [mentions...]")
+        .admin_only(),
+    Command::new("config", "Change this guild's settings")
+        .usage("show | set prefix|language|quotes|alert|emojis|embeds <value> | enable|disable <command> | reset")
+        .admin_only(),
+    Command::new("stats", "Who uses which command the most")
+        .usage("[day|week|all] | me [day|week|all] | <command>"),
+];
+
+/// `text` with the default prefix of every `` `!command` `` in it swapped for
+/// `prefix`, so usage lines match what the guild actually types.
+pub fn with_prefix(text: &str, prefix: &str) -> String {
+    text.replace(&format!("`{}", PREFIX), &format!("`{}", prefix))
+}
+
+/// Look a command up by its name or one of its aliases.
+pub fn find(name: &str) -> Option<&'static Command> {
+    COMMANDS
+        .iter()
+        .find(|command| command.name == name || command.aliases.contains(&name))
+}
+
+/// Commands that can't be disabled, otherwise a guild could lock itself out.
+pub const ALWAYS_ENABLED: &[&str] = &["help", "perm", "config"];
+
+/// A command that just answers with a fixed line.
+pub struct SimpleReply {
+    pub name: &'static str,
+    pub text: Text,
+    /// Logged when sending the reply fails.
+    pub error_note: &'static str,
+}
+
+// just single string return blocks
+pub const SIMPLE_REPLIES: &[SimpleReply] = &[
+    // Disclaimer: this command isn't used to demean people of any community or class
+    // :)
+    // but this is a typo that people willfully are going to make given the name of the bot
+    // besides if you're homophobic, get some help!
+    SimpleReply {
+        name: "gaysay",
+        text: Text::bangla(
+            "ব্রো, এসো তোমাকে ব্লেম দেই <3 ",
+            "Bro, come here and let me blame you <3 ",
+        ),
+        error_note: "Cheeky!",
+    },
+    SimpleReply {
+        name: "sprint",
+        text: Text::english(
+            "Sprint in the AI world means, really fast.",
+            "এআই এর দুনিয়ায় স্প্রিন্ট মানে, আসলেই অনেক দ্রুত।",
+        ),
+        error_note: "The sprint failed perhaps?",
+    },
+    SimpleReply {
+        name: "no",
+        text: Text::english(
+            "The no word has deep philosophical meaning to me. It tells me that I can tell anyone, no. Nobody can stop me.",
+            "না শব্দটার আমার কাছে গভীর দার্শনিক মানে আছে। এটা আমাকে বলে যে আমি যে কাউকে না বলতে পারি। কেউ আমাকে থামাতে পারবে না।",
+        ),
+        error_note: "No!",
+    },
+    SimpleReply {
+        name: "breakfast",
+        text: Text::english(
+            "I had granola and corn flakes this breakfast, but decided to add AI on top of it anyway.",
+            "আজকে নাস্তায় গ্রানোলা আর কর্ন ফ্লেক্স খাইসি, তাও উপরে একটু এআই দিয়ে দিলাম।",
+        ),
+        error_note: "breakfast",
+    },
+    SimpleReply {
+        name: "PM",
+        text: Text::english("LONG LIVE THE PM!", "পিএম দীর্ঘজীবী হোক!"),
+        error_note: "pm",
+    },
+    SimpleReply {
+        name: "QA",
+        text: Text::bangla(
+            "বাগ পাইসেন? আচ্ছা লিনিয়ারে টিকেট দেন। দেখতেসি বিষয়টা।",
+            "Found a bug? Alright, file a ticket in Linear. I'm looking into it.",
+        ),
+        error_note: "qa",
+    },
+    SimpleReply {
+        name: "abubakar",
+        text: Text::english("All I want for Eid is chunks!", "ঈদে আমার শুধু চাঙ্কস চাই!"),
+        error_note: "abubakar",
+    },
+    SimpleReply {
+        name: "biriyani",
+        text: Text::english(
+            "🎭 💡 https://www.youtube.com/watch?v=xvFZjo5PgG0\n",
+            "🎭 💡 https://www.youtube.com/watch?v=xvFZjo5PgG0\n",
+        ),
+        error_note: "biriyani",
+    },
+    SimpleReply {
+        name: "failed",
+        text: Text::english(
+            "Don't fix it just revert!",
+            "ঠিক করার দরকার নাই, রিভার্ট করে দেন!",
+        ),
+        error_note: "failed",
+    },
+    SimpleReply {
+        name: "talha",
+        text: Text::bangla(
+            "আপডেট ছাড়া আরেকবার ডাকলে বেতন 10% মাইনাস",
+            "Call me one more time without an update and it's 10% off your salary",
+        ),
+        error_note: "talha",
+    },
+    SimpleReply {
+        name: "jiggu",
+        text: Text::bangla(
+            "লোকে বলে আমি প্রোজেক্ট ম্যানেজার কিন্তু আমি আসলে আস্ত অপদার্থ, মুনিয়ার মা, প্লেটে আরো থ্যাপলা দাও, খাই।",
+            "People say I'm a project manager but I'm actually a complete good-for-nothing. Munia's mom, put more thapla on my plate, I'll eat.",
+        ),
+        error_note: "jiggu",
+    },
+    SimpleReply {
+        name: "ale",
+        text: Text::english(
+            "You drink the ale, I EAT THE ALE. We're not same bro!",
+            "তুমি এল খাও, আমি এল চিবাই। আমরা এক না ব্রো!",
+        ),
+        error_note: "ale",
+    },
+];
+
+/// The fixed reply for a command, if it is one of the simple ones.
+pub fn simple_reply(name: &str) -> Option<&'static SimpleReply> {
+    SIMPLE_REPLIES.iter().find(|reply| reply.name == name)
+}
+
+/// A command found in a message: its name and whatever followed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Invocation<'a> {
+    /// The command's own name, even when it was called by an alias.
+    pub name: &'static str,
+    pub args: &'a str,
+}
+
+/// Parse a message into a known command invocation.
+///
+/// Only the first word is considered and it has to match a command or alias
+/// exactly, so `!nope` is not mistaken for `!no`.
+pub fn parse<'a>(content: &'a str, prefix: &str) -> Option<Invocation<'a>> {
+    let rest = content.trim_start().strip_prefix(prefix)?;
+    let (name, args) = match rest.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (rest, ""),
+    };
+
+    find(name).map(|command| Invocation {
+        name: command.name,
+        args,
+    })
+}
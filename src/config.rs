@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serenity::model::id::{RoleId, UserId};
+use thiserror::Error;
+use tracing::info;
+
+use crate::approve::ApproveConfig;
+use crate::buttons::ButtonsConfig;
+use crate::github::GitHubConfig;
+use crate::guysay::GuysayConfig;
+use crate::jokes::JokeConfig;
+use crate::karma::KarmaConfig;
+use crate::output::OutputConfig;
+use crate::permissions::PermissionRule;
+use crate::pr_links::PrReactionConfig;
+use crate::standup::StandupConfig;
+use crate::tracker::TrackerConfig;
+use crate::unfurl::UnfurlConfig;
+use crate::updates::UpdatesConfig;
+use crate::webhook::WebhookConfig;
+
+/// Environment variable pointing at the bot's TOML config file.
+pub const CONFIG_ENV_VAR: &str = "BOT_CONFIG";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse config file: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+/// Top level bot configuration. Every section is optional and falls back to
+/// its defaults, so an empty file (or no file at all) is a valid config.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct BotConfig {
+    pub rate_limit: RateLimitConfig,
+    pub permissions: PermissionConfig,
+    pub storage: StorageConfig,
+    pub summon: SummonConfig,
+    pub pr_reactions: PrReactionConfig,
+    pub approve: ApproveConfig,
+    pub github: GitHubConfig,
+    pub webhook: WebhookConfig,
+    pub standup: StandupConfig,
+    pub karma: KarmaConfig,
+    pub updates: UpdatesConfig,
+    pub tracker: TrackerConfig,
+    pub unfurl: UnfurlConfig,
+    pub buttons: ButtonsConfig,
+    pub joke: JokeConfig,
+    pub guysay: GuysayConfig,
+    pub output: OutputConfig,
+}
+
+impl BotConfig {
+    /// Parse a config from a TOML string.
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Load a config from a TOML file on disk.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// Load the config from the file named by `BOT_CONFIG`, or use the defaults
+    /// when the variable is not set.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        match env::var(CONFIG_ENV_VAR) {
+            Ok(path) => {
+                info!("Loading bot config from: {}", path);
+                Self::load(path)
+            }
+            Err(_) => {
+                info!("{} not set, using default bot config", CONFIG_ENV_VAR);
+                Ok(Self::default())
+            }
+        }
+    }
+}
+
+/// Cooldowns for a single command, in seconds. A value of `0` disables that scope.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct CooldownConfig {
+    /// How long a single user has to wait between two uses of the command.
+    pub user_secs: u64,
+    /// How long a channel has to wait between two uses of the command.
+    pub channel_secs: u64,
+    /// How long everyone has to wait between two uses of the command.
+    pub global_secs: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Cooldown applied to commands without an entry in `commands`.
+    pub default: CooldownConfig,
+    /// Per command cooldowns, keyed by command name without the prefix.
+    pub commands: HashMap<String, CooldownConfig>,
+    /// Members with any of these roles are never throttled.
+    pub exempt_roles: Vec<RoleId>,
+    /// How often a throttled user is told off, so the warning can't be spammed either.
+    pub notice_cooldown_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let mut commands = HashMap::new();
+        // the joke API is the only thing we call on someone else's dime
+        commands.insert(
+            "joke".to_string(),
+            CooldownConfig {
+                user_secs: 15,
+                channel_secs: 5,
+                global_secs: 1,
+            },
+        );
+        commands.insert(
+            "oracle".to_string(),
+            CooldownConfig {
+                user_secs: 5,
+                channel_secs: 2,
+                global_secs: 0,
+            },
+        );
+
+        Self {
+            default: CooldownConfig {
+                user_secs: 3,
+                channel_secs: 0,
+                global_secs: 0,
+            },
+            commands,
+            exempt_roles: Vec::new(),
+            notice_cooldown_secs: 30,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct PermissionConfig {
+    /// Users that may change permissions at runtime.
+    pub admin_users: Vec<UserId>,
+    /// Roles that may change permissions at runtime.
+    pub admin_roles: Vec<RoleId>,
+    /// Named sets of commands, so one rule can cover e.g. all the fun commands.
+    pub groups: HashMap<String, Vec<String>>,
+    /// Rules keyed by command or group name.
+    pub rules: HashMap<String, PermissionRule>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// SQLite database everything persistent is kept in. `:memory:` keeps it
+    /// in memory, which loses everything on restart.
+    pub path: PathBuf,
+    /// JSON file older versions kept the guild settings in. When it exists
+    /// its settings are imported into the database on startup.
+    pub guild_settings_path: Option<PathBuf>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("the-bot.db"),
+            guild_settings_path: Some(PathBuf::from("guild_settings.json")),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SummonConfig {
+    /// How long `!summon ... urgent` waits for an answer before pinging again
+    /// and sending DMs.
+    pub escalation_delay_secs: u64,
+}
+
+impl Default for SummonConfig {
+    fn default() -> Self {
+        Self {
+            escalation_delay_secs: 5 * 60,
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use thiserror::Error;
+use tracing::info;
+
+use crate::pr_links::{Forge, PullRequestLink};
+
+/// Environment variable holding a GitHub token.
+pub const TOKEN_ENV_VAR: &str = "GITHUB_TOKEN";
+/// Environment variable pointing at a file that holds a GitHub token.
+pub const TOKEN_FILE_ENV_VAR: &str = "GITHUB_TOKEN_FILE";
+
+const USER_AGENT: &str = "the-bot";
+
+#[derive(Error, Debug)]
+pub enum GitHubError {
+    #[error("No GitHub token, set {TOKEN_ENV_VAR} or {TOKEN_FILE_ENV_VAR}")]
+    MissingToken,
+    #[error("Failed to read GitHub token file: {0}")]
+    TokenFile(#[from] std::io::Error),
+    #[error("Not a GitHub pull request link: {0}")]
+    NotAPullRequest(String),
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("GitHub answered {status}: {body}")]
+    Api { status: u16, body: String },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GitHubConfig {
+    /// REST API root, change it for GitHub Enterprise (`https://host/api/v3`).
+    pub api_url: String,
+    /// File to read the token from when `GITHUB_TOKEN` isn't set.
+    pub token_file: Option<PathBuf>,
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            api_url: "https://api.github.com".to_string(),
+            token_file: None,
+        }
+    }
+}
+
+impl GitHubConfig {
+    /// The host pull requests are linked on when `api_url` points at a GitHub
+    /// Enterprise server, `None` for github.com.
+    pub fn enterprise_host(&self) -> Option<String> {
+        let rest = self
+            .api_url
+            .strip_prefix("https://")
+            .or_else(|| self.api_url.strip_prefix("http://"))
+            .unwrap_or(&self.api_url);
+        let host = rest.split('/').next().unwrap_or_default();
+        let host = host.strip_prefix("api.").unwrap_or(host);
+        (!host.is_empty() && !host.eq_ignore_ascii_case("github.com")).then(|| host.to_string())
+    }
+
+    /// The token from `GITHUB_TOKEN`, or else from the token file given by
+    /// `GITHUB_TOKEN_FILE` or the config.
+    pub fn token(&self) -> Result<String, GitHubError> {
+        if let Ok(token) = env::var(TOKEN_ENV_VAR)
+            && !token.trim().is_empty()
+        {
+            return Ok(token.trim().to_string());
+        }
+
+        let path = env::var(TOKEN_FILE_ENV_VAR)
+            .ok()
+            .map(PathBuf::from)
+            .or_else(|| self.token_file.clone())
+            .ok_or(GitHubError::MissingToken)?;
+        let token = std::fs::read_to_string(path)?.trim().to_string();
+        if token.is_empty() {
+            return Err(GitHubError::MissingToken);
+        }
+        Ok(token)
+    }
+}
+
+/// How the approval ends up on the pull request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReviewKind {
+    /// An approving review.
+    #[default]
+    Approve,
+    /// A plain comment, for when you can't (or shouldn't) approve.
+    Comment,
+}
+
+/// A request to the GitHub API, built up front so `--dry-run` can show it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiRequest {
+    pub url: String,
+    pub body: Value,
+}
+
+impl fmt::Display for ApiRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let body = serde_json::to_string_pretty(&self.body).map_err(|_| fmt::Error)?;
+        write!(f, "POST {}\n{}", self.url, body)
+    }
+}
+
+/// Build the request that posts `body` on a pull request.
+pub fn review_request(
+    api_url: &str,
+    link: &PullRequestLink,
+    body: &str,
+    kind: ReviewKind,
+) -> Result<ApiRequest, GitHubError> {
+    if link.forge != Forge::GitHub {
+        return Err(GitHubError::NotAPullRequest(link.repo.clone()));
+    }
+
+    let api_url = api_url.trim_end_matches('/');
+    Ok(match kind {
+        ReviewKind::Approve => ApiRequest {
+            url: format!(
+                "{}/repos/{}/pulls/{}/reviews",
+                api_url, link.repo, link.number
+            ),
+            body: json!({ "body": body, "event": "APPROVE" }),
+        },
+        // pull requests are issues as far as comments go
+        ReviewKind::Comment => ApiRequest {
+            url: format!(
+                "{}/repos/{}/issues/{}/comments",
+                api_url, link.repo, link.number
+            ),
+            body: json!({ "body": body }),
+        },
+    })
+}
+
+/// Send a request and return the link to what it created, when GitHub gives one.
+pub async fn submit(request: &ApiRequest, token: &str) -> Result<Option<String>, GitHubError> {
+    let response = reqwest::Client::new()
+        .post(&request.url)
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", USER_AGENT)
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .json(&request.body)
+        .send()
+        .await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(GitHubError::Api {
+            status: status.as_u16(),
+            body,
+        });
+    }
+
+    let created: Value = response.json().await?;
+    info!(url = %request.url, "Posted to GitHub");
+    Ok(created
+        .get("html_url")
+        .and_then(Value::as_str)
+        .map(str::to_string))
+}
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, GuildId};
+use tracing::{debug, info};
+
+use crate::commands::{self, ALWAYS_ENABLED, PREFIX};
+use crate::language::Language;
+use crate::permissions::{GuildRules, Target};
+use crate::reactions::EmojiMode;
+use crate::storage::{Storage, StorageError};
+
+/// Longest prefix a guild may pick.
+const MAX_PREFIX_LEN: usize = 5;
+
+const CONFIG_USAGE: &str = "Usage: `!config show`, `!config set prefix|language|quotes|alert|emojis|embeds <value>`, `!config enable|disable <command>`, `!config reset`";
+
+/// Settings a guild can change for itself with `!config`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct GuildSettings {
+    pub prefix: String,
+    pub disabled_commands: Vec<String>,
+    /// `None` keeps every reply in the language it was written in.
+    pub language: Option<Language>,
+    /// Only quote from this fortune file (by file name without extension).
+    pub quote_category: Option<String>,
+    /// Where alerts for this guild are posted, like `!health` finding a
+    /// service down.
+    pub alert_channel: Option<ChannelId>,
+    /// Whether `!oracle` and `!summon` post their emojis or react with them.
+    pub emoji_mode: EmojiMode,
+    /// Whether `!health`, `!joke` and `!stats` answer with embeds or plain text.
+    pub embeds: bool,
+    /// Permission overrides set with `!perm`.
+    pub permissions: GuildRules,
+    /// Channels where ticket references get a summary, set with `!unfurl`.
+    pub unfurl_channels: Vec<ChannelId>,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        Self {
+            prefix: PREFIX.to_string(),
+            disabled_commands: Vec::new(),
+            language: None,
+            quote_category: None,
+            alert_channel: None,
+            emoji_mode: EmojiMode::default(),
+            embeds: true,
+            permissions: GuildRules::new(),
+            unfurl_channels: Vec::new(),
+        }
+    }
+}
+
+impl GuildSettings {
+    pub fn is_enabled(&self, command: &str) -> bool {
+        !self.disabled_commands.iter().any(|c| c == command)
+    }
+
+    /// A summary of the settings, for `!config show`.
+    pub fn describe(&self) -> String {
+        let disabled = if self.disabled_commands.is_empty() {
+            "none".to_string()
+        } else {
+            self.disabled_commands.join(", ")
+        };
+
+        let unfurl = if self.unfurl_channels.is_empty() {
+            "none".to_string()
+        } else {
+            self.unfurl_channels
+                .iter()
+                .map(|c| format!("<#{}>", c))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        format!(
+            "prefix: `{}`\nlanguage: {}\nquotes: {}\nalert channel: {}\nemojis: {}\nembeds: {}\nunfurl channels: {}\ndisabled commands: {}",
+            self.prefix,
+            self.language.map(|l| l.code()).unwrap_or("default"),
+            self.quote_category.as_deref().unwrap_or("all"),
+            self.alert_channel
+                .map(|c| format!("<#{}>", c))
+                .unwrap_or_else(|| "none".to_string()),
+            self.emoji_mode.name(),
+            if self.embeds { "on" } else { "off" },
+            unfurl,
+            disabled
+        )
+    }
+
+    /// Apply an admin `!unfurl` command run in `channel` and return the reply.
+    pub fn apply_unfurl(&mut self, channel: ChannelId, args: &str) -> String {
+        let enabled = self.unfurl_channels.contains(&channel);
+        match args.trim().to_lowercase().as_str() {
+            "" => {
+                if enabled {
+                    "Ticket summaries are on here.".to_string()
+                } else {
+                    let hint = "Ticket summaries are off here, `!unfurl on` turns them on.";
+                    commands::with_prefix(hint, &self.prefix)
+                }
+            }
+            "on" => {
+                if !enabled {
+                    self.unfurl_channels.push(channel);
+                }
+                format!("Ticket references in <#{}> get a summary now.", channel)
+            }
+            "off" => {
+                self.unfurl_channels.retain(|c| *c != channel);
+                format!("No more ticket summaries in <#{}>.", channel)
+            }
+            _ => commands::with_prefix("Usage: `!unfurl [on|off]`", &self.prefix),
+        }
+    }
+
+    /// Apply an admin `!config` command and return the reply.
+    ///
+    /// `quote_categories` are the fortune files the bot has loaded, so a guild
+    /// can't pick a category that doesn't exist.
+    pub fn apply_command(&mut self, args: &str, quote_categories: &[String]) -> String {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+
+        match parts.as_slice() {
+            [] | ["show"] => self.describe(),
+            ["set", "prefix", prefix] => {
+                if prefix.chars().count() > MAX_PREFIX_LEN {
+                    return format!("Prefix বেশি লম্বা, {} অক্ষরের মধ্যে রাখেন।", MAX_PREFIX_LEN);
+                }
+                self.prefix = prefix.to_string();
+                format!("Prefix is now `{}`. Try `{}config show`.", prefix, prefix)
+            }
+            ["set", "language", "default"] => {
+                self.language = None;
+                "Language is back to the default mix.".to_string()
+            }
+            ["set", "language", language] => match Language::parse(language) {
+                Some(language) => {
+                    self.language = Some(language);
+                    format!("Language is now `{}`.", language.code())
+                }
+                None => "Language হইলো `bn`, `en` অথবা `default`।".to_string(),
+            },
+            ["set", "quotes", "all"] => {
+                self.quote_category = None;
+                "Quoting from every file again.".to_string()
+            }
+            ["set", "quotes", category] => {
+                if quote_categories.iter().any(|c| c == category) {
+                    self.quote_category = Some(category.to_string());
+                    format!("Quoting only from `{}` now.", category)
+                } else {
+                    format!(
+                        "`{}` নামে কোন কোটের ফাইল নাই। আছে: {}",
+                        category,
+                        quote_categories.join(", ")
+                    )
+                }
+            }
+            ["set", "alert", "none"] => {
+                self.alert_channel = None;
+                "Alert channel removed.".to_string()
+            }
+            ["set", "alert", channel] => match Target::parse(channel) {
+                Some(Target::Channel(channel)) => {
+                    self.alert_channel = Some(channel);
+                    format!("Alerts go to <#{}> now.", channel)
+                }
+                _ => "Alert channel হিসেবে একটা #channel দেন।".to_string(),
+            },
+            ["set", "embeds", "on"] => {
+                self.embeds = true;
+                "Health, joke and stats replies come as embeds now.".to_string()
+            }
+            ["set", "embeds", "off"] => {
+                self.embeds = false;
+                "Health, joke and stats replies come as plain text now.".to_string()
+            }
+            ["set", "embeds", _] => "Embeds হইলো `on` অথবা `off`।".to_string(),
+            ["set", "emojis", mode] => match EmojiMode::parse(mode) {
+                Some(mode) => {
+                    self.emoji_mode = mode;
+                    format!("Emojis are now sent as {}.", mode.name())
+                }
+                None => "Emoji mode হইলো `text` অথবা `reactions`।".to_string(),
+            },
+            [action @ ("enable" | "disable"), command] => {
+                let Some(command) = commands::find(command).map(|c| &c.name) else {
+                    return format!("`{}` নামে কোন কমান্ড নাই। 🤔", command);
+                };
+                if *action == "enable" {
+                    self.disabled_commands.retain(|c| c != command);
+                    format!("`{}` is enabled.", command)
+                } else if ALWAYS_ENABLED.contains(command) {
+                    format!("`{}` can't be disabled.", command)
+                } else {
+                    if self.is_enabled(command) {
+                        self.disabled_commands.push(command.to_string());
+                    }
+                    format!("`{}` is disabled.", command)
+                }
+            }
+            ["reset"] => {
+                // permission overrides have their own `!perm reset`
+                let permissions = std::mem::take(&mut self.permissions);
+                *self = Self {
+                    permissions,
+                    ..Self::default()
+                };
+                "Settings are back to the defaults.".to_string()
+            }
+            _ => commands::with_prefix(CONFIG_USAGE, &self.prefix),
+        }
+    }
+}
+
+/// Move the settings older versions kept in a JSON file into storage and
+/// return how many guilds were imported. Guilds that already have stored
+/// settings keep them, and the file is renamed to `*.imported` afterwards so
+/// it is only read once. A missing file imports nothing.
+pub fn import_json(storage: &Storage, path: &Path) -> Result<usize, StorageError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let guilds: HashMap<GuildId, GuildSettings> = serde_json::from_str(&content)?;
+    let stored = storage.all_guild_settings()?;
+
+    let mut imported = 0;
+    for (guild, settings) in guilds {
+        if !stored.contains_key(&guild) {
+            storage.save_guild_settings(guild, &settings)?;
+            imported += 1;
+        }
+    }
+    std::fs::rename(path, path.with_extension("json.imported"))?;
+    info!(path = %path.display(), guild_count = imported, "Imported guild settings from JSON");
+    Ok(imported)
+}
+
+/// Per guild settings, cached in memory and written through to storage on every change.
+pub struct GuildSettingsStore {
+    storage: Arc<Storage>,
+    guilds: HashMap<GuildId, GuildSettings>,
+}
+
+impl GuildSettingsStore {
+    /// Load every guild's settings from storage.
+    pub fn load(storage: Arc<Storage>) -> Result<Self, StorageError> {
+        let guilds = storage.all_guild_settings()?;
+        info!(guild_count = guilds.len(), "Loaded guild settings");
+        Ok(Self { storage, guilds })
+    }
+
+    /// The settings of a guild, or the defaults for unknown guilds and DMs.
+    pub fn get(&self, guild: Option<GuildId>) -> GuildSettings {
+        guild
+            .and_then(|g| self.guilds.get(&g))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Change a guild's settings and persist them. The cached settings only
+    /// change once they are saved.
+    pub async fn update<R>(
+        &mut self,
+        guild: GuildId,
+        update: impl FnOnce(&mut GuildSettings) -> R,
+    ) -> Result<R, StorageError> {
+        let mut settings = self.get(Some(guild));
+        let result = update(&mut settings);
+        let saved = settings.clone();
+        self.storage
+            .run(move |s| s.save_guild_settings(guild, &saved))
+            .await?;
+        debug!(guild_id = %guild, "Saved guild settings");
+        self.guilds.insert(guild, settings);
+        Ok(result)
+    }
+}
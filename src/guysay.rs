@@ -1,9 +1,34 @@
 use fcowsay::animalsay;
+use rand::seq::SliceRandom;
 use rfortune::utils::random_quote;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct GuysayConfig {
+    /// Only pick quotes that fit in one message, instead of splitting the
+    /// long ones or sending them as a file.
+    pub skip_long: bool,
+}
 
 pub fn say(quotes: &[String], bash_format: bool) -> String {
     let output = random_quote(quotes);
-    let cow_say = animalsay(output, "cow");
+    render(output, bash_format)
+}
+
+/// Like [`say`], but only picks quotes that come out at most `limit`
+/// characters long. `None` when none of them do.
+pub fn say_within(quotes: &[String], bash_format: bool, limit: usize) -> Option<String> {
+    let mut order: Vec<&String> = quotes.iter().collect();
+    order.shuffle(&mut rand::rng());
+    order
+        .into_iter()
+        .map(|quote| render(quote, bash_format))
+        .find(|said| said.chars().count() <= limit)
+}
+
+fn render(quote: &str, bash_format: bool) -> String {
+    let cow_say = animalsay(quote, "cow");
     
     if bash_format {
         format!("```bash\n{}\n```", cow_say)
@@ -1,9 +1,14 @@
+use chrono::Utc;
 use rand::Rng;
 use reqwest::Error;
 use serde::Deserialize;
+use serenity::builder::CreateMessage;
 use std::env;
+use std::time::{Duration, Instant};
 use tracing::info;
 
+use crate::output::{COLOR_ERROR, COLOR_OK, Reply};
+
 #[derive(Deserialize, Debug)]
 struct HealthResponse {
     status: String,
@@ -15,6 +20,38 @@ struct ParsedData {
     environment: String,
 }
 
+/// Services `!health` knows about.
+pub const SERVICES: &[&str] = &["backend", "frontend"];
+/// Environments every service is deployed to.
+pub const ENVIRONMENTS: &[&str] = &["dev", "staging", "prod", "qa"];
+
+/// What `!health` answers: the text, and for an actual health report an embed
+/// to send instead when the guild has embeds on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReply {
+    pub text: String,
+    pub embed: Option<Reply>,
+    /// The service answered, but not with a healthy status.
+    pub down: bool,
+}
+
+impl HealthReply {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            embed: None,
+            down: false,
+        }
+    }
+
+    pub fn to_message(&self, embeds: bool) -> CreateMessage {
+        match &self.embed {
+            Some(embed) if embeds => CreateMessage::new().embed(embed.to_embed()),
+            _ => CreateMessage::new().content(&self.text),
+        }
+    }
+}
+
 fn format_response(service: &str, environment: &str, status: &str) -> String {
     let (emoji, status_text) = if status.to_lowercase() == "ok" {
         ("🚀", "Service Healthy")
@@ -42,6 +79,38 @@ fn format_response(service: &str, environment: &str, status: &str) -> String {
     )
 }
 
+fn health_embed(service: &str, environment: &str, status: &str, latency: Duration) -> Reply {
+    let healthy = status.to_lowercase() == "ok";
+    let (emoji, status_text, check, color) = if healthy {
+        ("🚀", "Service Healthy", "✅ Healthy", COLOR_OK)
+    } else {
+        ("⚠️", "Service Down", "❌ Unhealthy", COLOR_ERROR)
+    };
+
+    Reply::default()
+        .title(format!("{} | {}", emoji, status_text))
+        .description(format!("{} has been checked", service))
+        .color(color)
+        .field("Environment", environment, true)
+        .field("Status", status.to_uppercase(), true)
+        .field("Latency", format!("{} ms", latency.as_millis()), true)
+        .field("Health Check", check, false)
+        .footer(format!(
+            "{}_{}_URL",
+            service.to_uppercase(),
+            environment.to_uppercase()
+        ))
+        .timestamp(Utc::now().timestamp())
+}
+
+fn health_reply(service: &str, environment: &str, status: &str, latency: Duration) -> HealthReply {
+    HealthReply {
+        text: format_response(service, environment, status),
+        embed: Some(health_embed(service, environment, status, latency)),
+        down: status.to_lowercase() != "ok",
+    }
+}
+
 pub fn send_funny() -> String {
     // long replies
     let lmaos: Vec<&str> = [
@@ -67,9 +136,9 @@ pub fn send_funny() -> String {
 
 fn parse_message(message: &str) -> Result<ParsedData, String> {
     // allowed values
-    let allowed_services = ["backend", "frontend"];
-    let allowed_environments = ["dev", "staging", "prod", "qa"];
-    
+    let allowed_services = SERVICES;
+    let allowed_environments = ENVIRONMENTS;
+
     // Split the message into parts
     let parts: Vec<&str> = message.split_whitespace().collect();
 
@@ -97,12 +166,19 @@ fn parse_message(message: &str) -> Result<ParsedData, String> {
     })
 }
 
+/// Run `!health <service> <environment>` and render the outcome as plain text.
 pub async fn check_health(message: String) -> Result<String, Error> {
+    Ok(check(&message).await?.text)
+}
+
+/// Run `!health <service> <environment>`. Anything that isn't a health
+/// report, like a usage complaint, comes back as text only.
+pub async fn check(message: &str) -> Result<HealthReply, Error> {
     info!("Received health check request: {}", message);
     // parse the message
-    let parsed = match parse_message(&message) {
+    let parsed = match parse_message(message) {
         Ok(data) => data,
-        Err(e) => return Ok(e),
+        Err(e) => return Ok(HealthReply::text(e)),
     };
 
     // find the url var from env
@@ -114,34 +190,32 @@ pub async fn check_health(message: String) -> Result<String, Error> {
     let url = match env::var(&url_var) {
         Ok(url) => url,
         Err(_) => {
-            return Ok("এই জিনিসের কোন হদিস পাইলাম না! 😅".to_string());
+            return Ok(HealthReply::text("এই জিনিসের কোন হদিস পাইলাম না! 😅"));
         }
     };
 
     // Make a GET request to the backend URL
+    let started = Instant::now();
     let resp = reqwest::get(&url).await?;
+    let latency = started.elapsed();
 
     // Check if the response status is successful and then return the health status
     if resp.status().is_success() {
-        return if let Ok(data) = resp.json::<HealthResponse>().await {
-            if data.status.to_lowercase() == "ok" {
-                let msg = format_response(&parsed.service, &parsed.environment, &data.status);
-                Ok(msg.into())
-            } else {
-                Ok(format_response(
-                    &parsed.service,
-                    &parsed.environment,
-                    &data.status,
-                ))
-            }
-        } else {
-            Ok("Invalid JSON response from the endpoint".into())
+        return match resp.json::<HealthResponse>().await {
+            Ok(data) => Ok(health_reply(
+                &parsed.service,
+                &parsed.environment,
+                &data.status,
+                latency,
+            )),
+            Err(_) => Ok(HealthReply::text("Invalid JSON response from the endpoint")),
         };
     }
 
-    Ok(format_response(
+    Ok(health_reply(
         &parsed.service,
         &parsed.environment,
         &resp.status().to_string(),
+        latency,
     ))
 }
@@ -0,0 +1,98 @@
+use crate::commands::Command;
+
+/// Longest message Discord accepts.
+pub const MESSAGE_LIMIT: usize = 2000;
+
+/// Room kept free on every page for the page footer.
+const FOOTER_ROOM: usize = 100;
+
+/// What `!help` was asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpQuery<'a> {
+    /// A page of the command list, starting at 1.
+    Page(usize),
+    /// Everything about one command.
+    Command(&'a str),
+}
+
+impl<'a> HelpQuery<'a> {
+    /// Parse the arguments of `!help`; a command may be given with or without the prefix.
+    pub fn parse(args: &'a str, prefix: &str) -> Self {
+        let args = args.trim();
+        if args.is_empty() {
+            return Self::Page(1);
+        }
+        match args.parse::<usize>() {
+            Ok(page) => Self::Page(page.max(1)),
+            Err(_) => Self::Command(args.strip_prefix(prefix).unwrap_or(args)),
+        }
+    }
+}
+
+/// One line of the command list.
+fn summary_line(command: &Command, prefix: &str) -> String {
+    if command.usage.is_empty() {
+        format!("`{}{}`: {}", prefix, command.name, command.description)
+    } else {
+        format!(
+            "`{}{} {}`: {}",
+            prefix, command.name, command.usage, command.description
+        )
+    }
+}
+
+/// The full help of a single command, for `!help <command>`.
+pub fn command_help(command: &Command, prefix: &str) -> String {
+    let usage = if command.usage.is_empty() {
+        format!("Usage: `{}{}`", prefix, command.name)
+    } else {
+        format!("Usage: `{}{} {}`", prefix, command.name, command.usage)
+    };
+    let mut lines = vec![
+        format!("**{}{}**", prefix, command.name),
+        command.description.to_string(),
+        usage,
+    ];
+    if !command.aliases.is_empty() {
+        let aliases: Vec<String> = command
+            .aliases
+            .iter()
+            .map(|alias| format!("`{}{}`", prefix, alias))
+            .collect();
+        lines.push(format!("Aliases: {}", aliases.join(", ")));
+    }
+    if command.admin_only {
+        lines.push("Admins only.".to_string());
+    }
+    lines.join("\n")
+}
+
+/// The command list split into pages that each fit in one message.
+pub fn pages(commands: &[&Command], prefix: &str) -> Vec<String> {
+    let limit = MESSAGE_LIMIT - FOOTER_ROOM;
+    let mut pages = Vec::new();
+    let mut page = String::from("**Commands**");
+
+    for command in commands {
+        let line = summary_line(command, prefix);
+        if page.chars().count() + 1 + line.chars().count() > limit {
+            pages.push(std::mem::take(&mut page));
+        }
+        if !page.is_empty() {
+            page.push('\n');
+        }
+        page.push_str(&line);
+    }
+    pages.push(page);
+
+    let total = pages.len();
+    if total > 1 {
+        for (index, page) in pages.iter_mut().enumerate() {
+            page.push_str(&format!("\n_Page {}/{}_", index + 1, total));
+            if index + 1 < total {
+                page.push_str(&format!(", `{}help {}` for more", prefix, index + 2));
+            }
+        }
+    }
+    pages
+}
@@ -1,6 +1,29 @@
+use std::collections::HashMap;
+
 use serde::Deserialize;
+use serenity::model::id::MessageId;
 use thiserror::Error;
 
+use crate::output::{COLOR_FUN, Reply};
+
+/// Reacting to a setup with this posts the punchline right away.
+pub const THINKING_EMOJI: &str = "🤔";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct JokeConfig {
+    /// How long after the setup the punchline is posted.
+    pub punchline_delay_secs: u64,
+}
+
+impl Default for JokeConfig {
+    fn default() -> Self {
+        Self {
+            punchline_delay_secs: 5,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[allow(dead_code)]
 pub struct Joke {
@@ -16,6 +39,43 @@ pub enum JokeError {
     Request(#[from] reqwest::Error),
 }
 
+pub fn setup_reply(setup: &str) -> Reply {
+    Reply::default()
+        .description(format!("🎭 **{}**", setup))
+        .color(COLOR_FUN)
+}
+
+pub fn punchline_reply(punchline: &str) -> Reply {
+    Reply::default()
+        .description(format!("💡 _{}_", punchline))
+        .color(COLOR_FUN)
+}
+
+/// Punchlines of posted setups, until they are delivered or the setup is deleted.
+#[derive(Debug, Default)]
+pub struct Punchlines {
+    pending: HashMap<MessageId, String>,
+}
+
+impl Punchlines {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn hold(&mut self, setup: MessageId, punchline: &str) {
+        self.pending.insert(setup, punchline.to_string());
+    }
+
+    pub fn is_pending(&self, setup: MessageId) -> bool {
+        self.pending.contains_key(&setup)
+    }
+
+    /// The punchline for a setup, handed out once.
+    pub fn take(&mut self, setup: MessageId) -> Option<String> {
+        self.pending.remove(&setup)
+    }
+}
+
 pub async fn fetch_joke() -> Result<Vec<Joke>, JokeError> {
     let url = "https://official-joke-api.appspot.com/jokes/programming/random";
     let response = reqwest::get(url).await?;
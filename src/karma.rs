@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serenity::model::id::UserId;
+
+/// At most this many targets get karma from a single message.
+pub const MAX_CHANGES_PER_MESSAGE: usize = 5;
+/// How many entries `!karma top` shows.
+pub const TOP_LIMIT: usize = 10;
+
+pub const SELF_KARMA_MESSAGE: &str = "নিজের ঢোল নিজে পেটানো যাবে না। 🥁";
+pub const USAGE: &str = "Usage: `!karma`, `!karma @user`, `!karma <thing>`, `!karma top`";
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct KarmaConfig {
+    /// How long before the same person can change the same target's karma again.
+    pub cooldown_secs: u64,
+}
+
+impl Default for KarmaConfig {
+    fn default() -> Self {
+        Self { cooldown_secs: 60 }
+    }
+}
+
+/// What karma is given to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KarmaTarget {
+    User(UserId),
+    /// Anything else, lower cased: `biriyani++`.
+    Thing(String),
+}
+
+impl KarmaTarget {
+    /// How the target is stored.
+    pub fn key(&self) -> String {
+        match self {
+            Self::User(user) => format!("user:{}", user),
+            Self::Thing(thing) => format!("thing:{}", thing),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key.split_once(':')? {
+            ("user", id) => id
+                .parse::<u64>()
+                .ok()
+                .filter(|id| *id != 0)
+                .map(|id| Self::User(UserId::new(id))),
+            ("thing", thing) => Some(Self::Thing(thing.to_string())),
+            _ => None,
+        }
+    }
+
+    /// How the target is shown in a reply.
+    pub fn label(&self) -> String {
+        match self {
+            Self::User(user) => format!("<@{}>", user),
+            Self::Thing(thing) => format!("**{}**", thing),
+        }
+    }
+
+    /// Parse `<@id>`, `<@!id>` or a thing name.
+    pub fn parse(input: &str) -> Option<Self> {
+        if let Some(id) = input.strip_prefix("<@").and_then(|id| id.strip_suffix('>')) {
+            return id
+                .trim_start_matches('!')
+                .parse::<u64>()
+                .ok()
+                .filter(|id| *id != 0)
+                .map(|id| Self::User(UserId::new(id)));
+        }
+        thing(input).map(Self::Thing)
+    }
+}
+
+/// A thing name, or `None` for words that are better left alone: single
+/// letters (`c++`, `i--`), other mentions and links.
+fn thing(word: &str) -> Option<String> {
+    let word = word.trim_start_matches('@').to_lowercase();
+    let valid = word.chars().count() >= 2
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && word.chars().any(char::is_alphanumeric);
+    valid.then_some(word)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KarmaChange {
+    pub target: KarmaTarget,
+    pub delta: i64,
+}
+
+/// Drop everything between backticks, code is full of `i++`.
+fn strip_code(content: &str) -> String {
+    content.split('`').step_by(2).collect::<Vec<_>>().join(" ")
+}
+
+/// Karma changes in a message: `@user ++`, `@user--`, `thing++`, `thing--`.
+/// Each target counts once.
+pub fn find_changes(content: &str) -> Vec<KarmaChange> {
+    let content = strip_code(content);
+    let words: Vec<&str> = content.split_whitespace().collect();
+    let mut changes: Vec<KarmaChange> = Vec::new();
+
+    for (index, word) in words.iter().enumerate() {
+        let (base, delta) = match *word {
+            // a detached `++` only counts after a mention, `this -- that` is just a dash
+            "++" | "--" => match index.checked_sub(1).map(|i| words[i]) {
+                Some(previous) if previous.starts_with("<@") => {
+                    (previous, if *word == "++" { 1 } else { -1 })
+                }
+                _ => continue,
+            },
+            word => {
+                if let Some(base) = word.strip_suffix("++") {
+                    (base, 1)
+                } else if let Some(base) = word.strip_suffix("--") {
+                    (base, -1)
+                } else {
+                    continue;
+                }
+            }
+        };
+
+        let Some(target) = KarmaTarget::parse(base) else {
+            continue;
+        };
+        if changes.iter().any(|change| change.target == target) {
+            continue;
+        }
+        changes.push(KarmaChange { target, delta });
+        if changes.len() == MAX_CHANGES_PER_MESSAGE {
+            break;
+        }
+    }
+    changes
+}
+
+/// Remembers who changed whose karma when, so it can't be farmed.
+pub struct KarmaCooldown {
+    cooldown: Duration,
+    last: HashMap<(UserId, KarmaTarget), Instant>,
+}
+
+impl KarmaCooldown {
+    pub fn new(config: &KarmaConfig) -> Self {
+        Self {
+            cooldown: Duration::from_secs(config.cooldown_secs),
+            last: HashMap::new(),
+        }
+    }
+
+    /// Whether `giver` may change `target`'s karma now; if so, the change is remembered.
+    pub fn allow(&mut self, giver: UserId, target: &KarmaTarget, now: Instant) -> bool {
+        let cooldown = self.cooldown;
+        self.last
+            .retain(|_, at| now.saturating_duration_since(*at) < cooldown);
+
+        let key = (giver, target.clone());
+        if self.last.contains_key(&key) {
+            return false;
+        }
+        self.last.insert(key, now);
+        true
+    }
+}
+
+/// The reply line for a change that went through.
+pub fn score_message(target: &KarmaTarget, delta: i64, score: i64, emoji: &str) -> String {
+    let (arrow, verb) = if delta > 0 {
+        ("⬆️", "up")
+    } else {
+        ("⬇️", "down")
+    };
+    format!(
+        "{} {} karma {}, now at **{}** {}",
+        arrow,
+        target.label(),
+        verb,
+        score,
+        emoji
+    )
+}
+
+/// The `!karma top` list.
+pub fn leaderboard(entries: &[(KarmaTarget, i64)]) -> String {
+    if entries.is_empty() {
+        return "এখনও কেউ কোন karma পায়নি।".to_string();
+    }
+    let mut lines = vec!["**Karma leaderboard**".to_string()];
+    for (rank, (target, score)) in entries.iter().enumerate() {
+        lines.push(format!("{}. {} {}", rank + 1, target.label(), score));
+    }
+    lines.join("\n")
+}
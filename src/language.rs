@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// The languages the bot can answer in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Bangla,
+    English,
+}
+
+impl Language {
+    /// Parse a language name or code as typed in chat.
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "bn" | "bangla" | "bengali" | "বাংলা" => Some(Self::Bangla),
+            "en" | "english" => Some(Self::English),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Bangla => "bn",
+            Self::English => "en",
+        }
+    }
+}
+
+/// A response available in both languages.
+///
+/// `native` is the language the line was written in; it is used when a guild
+/// hasn't picked a language, which keeps the bot's usual Banglish mix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Text {
+    pub native: Language,
+    pub bn: &'static str,
+    pub en: &'static str,
+}
+
+impl Text {
+    /// A line originally written in Bangla, with its English translation.
+    pub const fn bangla(bn: &'static str, en: &'static str) -> Self {
+        Self {
+            native: Language::Bangla,
+            bn,
+            en,
+        }
+    }
+
+    /// A line originally written in English, with its Bangla translation.
+    pub const fn english(en: &'static str, bn: &'static str) -> Self {
+        Self {
+            native: Language::English,
+            bn,
+            en,
+        }
+    }
+
+    pub fn get(&self, language: Option<Language>) -> &'static str {
+        match language.unwrap_or(self.native) {
+            Language::Bangla => self.bn,
+            Language::English => self.en,
+        }
+    }
+}
@@ -1,7 +1,33 @@
+pub mod approve;
 pub mod bot;
+pub mod buttons;
 pub mod clipboard;
+pub mod commands;
+pub mod config;
 pub mod emoji_generator;
+pub mod github;
+pub mod guild_settings;
 pub mod guysay;
 pub mod health_checker;
+pub mod help;
 pub mod jokes;
+pub mod karma;
+pub mod language;
+pub mod notify;
+pub mod output;
+pub mod permissions;
+pub mod poll;
+pub mod pr_links;
 pub mod quote_loader;
+pub mod rate_limit;
+pub mod reactions;
+pub mod reminders;
+pub mod sprint;
+pub mod standup;
+pub mod stats;
+pub mod storage;
+pub mod summon;
+pub mod tracker;
+pub mod unfurl;
+pub mod updates;
+pub mod webhook;
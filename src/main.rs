@@ -1,10 +1,8 @@
-mod bot;
-mod clipboard;
-mod emoji_generator;
-mod guysay;
-mod health_checker;
-mod jokes;
-mod quote_loader;
+use the_bot::approve::{self, Style};
+use the_bot::config::BotConfig;
+use the_bot::github::{self, GitHubConfig, ReviewKind};
+use the_bot::pr_links::PullRequestLink;
+use the_bot::{bot, clipboard, emoji_generator, guysay, quote_loader};
 
 use std::env;
 use tracing::{error, info};
@@ -47,6 +45,49 @@ async fn main() {
             Ok(_) => info!("Emojis copied to clipboard successfully"),
             Err(e) => error!(error = %e, "Failed to copy emojis to clipboard"),
         }
+    } else if args.len() > 1 && args[1] == "approve" {
+        info!("Starting approve mode");
+        let mut style = Style::default();
+        let mut link = None;
+        let mut kind = ReviewKind::Approve;
+        let mut dry_run = false;
+        let config = match BotConfig::from_env() {
+            Ok(config) => config,
+            Err(e) => {
+                error!(error = %e, "Failed to load bot config");
+                return;
+            }
+        };
+        let github_host = config.github.enterprise_host();
+        for arg in &args[2..] {
+            if arg == "--dry-run" {
+                dry_run = true;
+            } else if arg == "--comment" {
+                kind = ReviewKind::Comment;
+            } else if let Some(parsed) = Style::parse(arg) {
+                style = parsed;
+            } else if let Some(parsed) = PullRequestLink::parse_on(arg, github_host.as_deref()) {
+                link = Some(parsed);
+            } else {
+                error!(
+                    "Unknown argument: {}, expected a pull request link, a style (short, enthusiastic, sarcastic), --comment or --dry-run",
+                    arg
+                );
+                return;
+            }
+        }
+        let message = approve::approval(&config.approve, style);
+
+        match link {
+            Some(link) => post_approval(&config.github, &link, &message, kind, dry_run).await,
+            None => {
+                println!("{}", message);
+                match clipboard::copy_to_clipboard(&message) {
+                    Ok(_) => info!("Approval copied to clipboard successfully"),
+                    Err(e) => error!(error = %e, "Failed to copy approval to clipboard"),
+                }
+            }
+        }
     } else if args.len() > 2 && args[1] == "guysay" {
         // get files dir from args[2]
         info!("Starting guysay mode");
@@ -74,3 +115,40 @@ async fn main() {
         error!("Invalid arguments supplied");
     }
 }
+
+/// Post an approval on a GitHub pull request, or only print the request on a dry run.
+async fn post_approval(
+    config: &GitHubConfig,
+    link: &PullRequestLink,
+    message: &str,
+    kind: ReviewKind,
+    dry_run: bool,
+) {
+    let request = match github::review_request(&config.api_url, link, message, kind) {
+        Ok(request) => request,
+        Err(e) => {
+            error!(error = %e, "Can't approve this pull request");
+            return;
+        }
+    };
+
+    if dry_run {
+        info!("Dry run, not sending anything");
+        println!("{}", request);
+        return;
+    }
+
+    let token = match config.token() {
+        Ok(token) => token,
+        Err(e) => {
+            error!(error = %e, "Failed to get GitHub token");
+            return;
+        }
+    };
+
+    match github::submit(&request, &token).await {
+        Ok(Some(url)) => println!("{}", url),
+        Ok(None) => println!("Posted to {}#{}", link.repo, link.number),
+        Err(e) => error!(error = %e, "Failed to post approval to GitHub"),
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serenity::model::id::ChannelId;
+use sha2::Sha256;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{error, info, warn};
+
+use crate::health_checker::{ENVIRONMENTS, SERVICES, check_health};
+use crate::webhook::Post;
+
+/// Environment variable holding the `/notify` token, it wins over the config.
+pub const TOKEN_ENV_VAR: &str = "NOTIFY_TOKEN";
+/// Where deploy scripts post their notifications.
+pub const NOTIFY_PATH: &str = "/notify";
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Bearer token callers have to send.
+    pub token: Option<String>,
+    /// Channel per service, e.g. `backend = 1234`.
+    pub channels: HashMap<String, ChannelId>,
+    /// Channel for services without their own entry.
+    pub default_channel: Option<ChannelId>,
+    /// Run `!health` for the service this many seconds after a successful deploy.
+    pub health_check_delay_secs: Option<u64>,
+}
+
+impl NotifyConfig {
+    /// The token from `NOTIFY_TOKEN`, or else from the config.
+    pub fn token(&self) -> Option<String> {
+        env::var(TOKEN_ENV_VAR)
+            .ok()
+            .or_else(|| self.token.clone())
+            .filter(|token| !token.is_empty())
+    }
+
+    pub fn channel_for(&self, service: &str) -> Option<ChannelId> {
+        self.channels.get(service).copied().or(self.default_channel)
+    }
+}
+
+/// What a deploy script sends.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub service: String,
+    pub environment: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// `started`, `success` or `failure`; anything else is shown as is.
+    pub status: String,
+    #[serde(default)]
+    pub link: Option<String>,
+}
+
+impl Notification {
+    /// Check the service and environment against the ones `!health` knows.
+    pub fn validate(&self) -> Result<(), String> {
+        if !SERVICES.contains(&self.service.as_str()) {
+            return Err(format!(
+                "unknown service `{}`, expected one of {}",
+                self.service,
+                SERVICES.join(", ")
+            ));
+        }
+        if !ENVIRONMENTS.contains(&self.environment.as_str()) {
+            return Err(format!(
+                "unknown environment `{}`, expected one of {}",
+                self.environment,
+                ENVIRONMENTS.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn succeeded(&self) -> bool {
+        matches!(
+            self.status.to_lowercase().as_str(),
+            "success" | "succeeded" | "deployed" | "ok"
+        )
+    }
+
+    /// The message posted in Discord.
+    pub fn message(&self) -> String {
+        let (emoji, what) = match self.status.to_lowercase().as_str() {
+            _ if self.succeeded() => ("🚀", "deployed to".to_string()),
+            "started" | "in_progress" | "deploying" => ("⏳", "is deploying to".to_string()),
+            "failure" | "failed" | "error" => ("💥", "failed to deploy to".to_string()),
+            _ => ("ℹ️", format!("is `{}` on", self.status)),
+        };
+
+        let mut message = format!("{} **{}**", emoji, self.service);
+        if let Some(version) = &self.version {
+            message.push_str(&format!(" `{}`", version));
+        }
+        message.push_str(&format!(" {} **{}**", what, self.environment));
+        if let Some(link) = &self.link {
+            message.push_str(&format!("\n{}", link));
+        }
+        message
+    }
+}
+
+#[derive(Clone)]
+struct NotifyState {
+    config: Arc<NotifyConfig>,
+    token: Arc<String>,
+    posts: UnboundedSender<Post>,
+}
+
+/// The `/notify` route. Notifications are handed to `posts`.
+pub fn router(config: NotifyConfig, token: String, posts: UnboundedSender<Post>) -> Router {
+    let state = NotifyState {
+        config: Arc::new(config),
+        token: Arc::new(token),
+        posts,
+    };
+    Router::new()
+        .route(NOTIFY_PATH, post(notify))
+        .with_state(state)
+}
+
+/// Compare HMACs of both tokens in constant time, so neither the token nor
+/// its length can be guessed from response times.
+fn same_token(given: &str, expected: &str) -> bool {
+    let digest = |token: &str| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(expected.as_bytes())
+            .expect("HMAC takes keys of any size");
+        mac.update(token.as_bytes());
+        mac
+    };
+    let expected_digest = digest(expected).finalize().into_bytes();
+    digest(given).verify_slice(&expected_digest).is_ok()
+}
+
+async fn notify(
+    State(state): State<NotifyState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, String) {
+    let token = headers
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    if !same_token(token, &state.token) {
+        warn!("Rejected notification with a bad token");
+        return (StatusCode::UNAUTHORIZED, "bad token".to_string());
+    }
+
+    let notification: Notification = match serde_json::from_slice(&body) {
+        Ok(notification) => notification,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    if let Err(reason) = notification.validate() {
+        return (StatusCode::UNPROCESSABLE_ENTITY, reason);
+    }
+
+    let Some(channel) = state.config.channel_for(&notification.service) else {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("no channel for `{}`", notification.service),
+        );
+    };
+
+    info!(
+        service = %notification.service,
+        environment = %notification.environment,
+        status = %notification.status,
+        channel_id = %channel,
+        "Posting deploy notification"
+    );
+    let post = Post {
+        channel,
+        content: notification.message(),
+    };
+    if state.posts.send(post).is_err() {
+        return (StatusCode::SERVICE_UNAVAILABLE, "not connected".to_string());
+    }
+
+    if let Some(delay) = state.config.health_check_delay_secs
+        && notification.succeeded()
+    {
+        tokio::spawn(confirm_deploy(
+            notification,
+            channel,
+            Duration::from_secs(delay),
+            state.posts.clone(),
+        ));
+    }
+
+    (StatusCode::ACCEPTED, "ok".to_string())
+}
+
+/// Check a freshly deployed service once it had time to come up.
+async fn confirm_deploy(
+    notification: Notification,
+    channel: ChannelId,
+    delay: Duration,
+    posts: UnboundedSender<Post>,
+) {
+    tokio::time::sleep(delay).await;
+
+    let command = format!(
+        "!health {} {}",
+        notification.service, notification.environment
+    );
+    let content = match check_health(command).await {
+        Ok(status) => status,
+        Err(e) => {
+            error!(
+                error = ?e,
+                service = %notification.service,
+                environment = %notification.environment,
+                "Health check after deploy failed"
+            );
+            format!(
+                "⚠️ **{}** doesn't answer on **{}** after the deploy",
+                notification.service, notification.environment
+            )
+        }
+    };
+
+    if posts.send(Post { channel, content }).is_err() {
+        warn!("Nobody is posting notifications anymore");
+    }
+}
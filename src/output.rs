@@ -0,0 +1,281 @@
+use serde::Deserialize;
+use serenity::builder::{CreateAttachment, CreateEmbed, CreateEmbedFooter, CreateMessage};
+use serenity::model::Colour;
+use serenity::model::Timestamp;
+
+use crate::help::MESSAGE_LIMIT;
+
+const FENCE: &str = "```";
+
+/// Sent along with a reply that went out as a file.
+pub const ATTACHED_NOTE: &str = "লেখা অনেক লম্বা, ফাইলে দিলাম। 📎";
+
+/// Embed colors, by what the reply says.
+pub const COLOR_OK: u32 = 0x2ECC71;
+pub const COLOR_ERROR: u32 = 0xE74C3C;
+pub const COLOR_INFO: u32 = 0x5865F2;
+pub const COLOR_FUN: u32 = 0xF1C40F;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+    /// Shown next to other inline fields in an embed, as `name: value` in text.
+    pub inline: bool,
+}
+
+/// A reply that can go out as a Discord embed or as plain text, for channels
+/// where embeds are off and for the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reply {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<u32>,
+    pub fields: Vec<Field>,
+    pub footer: Option<String>,
+    /// Unix timestamp in seconds, shown by Discord in the reader's timezone.
+    pub timestamp: Option<i64>,
+}
+
+impl Reply {
+    /// A reply that is just text, sent as a plain message either way.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::default().description(text)
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn color(mut self, color: u32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn field(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+        inline: bool,
+    ) -> Self {
+        self.fields.push(Field {
+            name: name.into(),
+            value: value.into(),
+            inline,
+        });
+        self
+    }
+
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Whether there's anything an embed would add over plain text.
+    fn is_plain(&self) -> bool {
+        self.title.is_none()
+            && self.color.is_none()
+            && self.fields.is_empty()
+            && self.footer.is_none()
+    }
+
+    /// The plain text rendering.
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(title) = &self.title {
+            lines.push(format!("**{}**", title));
+        }
+        if let Some(description) = &self.description {
+            lines.push(description.clone());
+        }
+        for field in &self.fields {
+            if field.inline {
+                lines.push(format!("{}: {}", field.name, field.value));
+            } else {
+                lines.push(format!("**{}**\n{}", field.name, field.value));
+            }
+        }
+        if let Some(footer) = &self.footer {
+            lines.push(format!("_{}_", footer));
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new();
+        if let Some(title) = &self.title {
+            embed = embed.title(title);
+        }
+        if let Some(description) = &self.description {
+            embed = embed.description(description);
+        }
+        if let Some(color) = self.color {
+            embed = embed.colour(Colour::new(color));
+        }
+        for field in &self.fields {
+            embed = embed.field(&field.name, &field.value, field.inline);
+        }
+        if let Some(footer) = &self.footer {
+            embed = embed.footer(CreateEmbedFooter::new(footer));
+        }
+        if let Some(at) = self
+            .timestamp
+            .and_then(|at| Timestamp::from_unix_timestamp(at).ok())
+        {
+            embed = embed.timestamp(at);
+        }
+        embed
+    }
+
+    /// The message to send, an embed unless `embeds` is off or the reply is plain.
+    pub fn to_message(&self, embeds: bool) -> CreateMessage {
+        if embeds && !self.is_plain() {
+            CreateMessage::new().embed(self.to_embed())
+        } else {
+            CreateMessage::new().content(self.to_text())
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct OutputConfig {
+    /// A reply too long for one message is split into at most this many
+    /// messages, and attached as a `.txt` file when it needs more. 0 always
+    /// attaches.
+    pub max_messages: usize,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self { max_messages: 3 }
+    }
+}
+
+/// How a reply goes out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fitted {
+    /// One message each, in order.
+    Messages(Vec<String>),
+    /// Attached as a file, the text without a code fence around all of it.
+    Attachment(String),
+}
+
+/// Fit `content` into messages of at most `limit` characters.
+pub fn fit(content: &str, limit: usize, config: &OutputConfig) -> Fitted {
+    if content.chars().count() <= limit {
+        return Fitted::Messages(vec![content.to_string()]);
+    }
+    let chunks = split_message(content, limit);
+    if chunks.len() <= config.max_messages {
+        Fitted::Messages(chunks)
+    } else {
+        Fitted::Attachment(unfenced(content).to_string())
+    }
+}
+
+/// The messages to send for `content`, attached as `file_name` when it
+/// doesn't fit in a few.
+pub fn fit_messages(content: &str, config: &OutputConfig, file_name: &str) -> Vec<CreateMessage> {
+    match fit(content, MESSAGE_LIMIT, config) {
+        Fitted::Messages(chunks) => chunks
+            .into_iter()
+            .map(|chunk| CreateMessage::new().content(chunk))
+            .collect(),
+        Fitted::Attachment(text) => vec![
+            CreateMessage::new()
+                .content(ATTACHED_NOTE)
+                .add_file(CreateAttachment::bytes(text.into_bytes(), file_name)),
+        ],
+    }
+}
+
+/// Split `content` into chunks of at most `limit` characters, on line
+/// boundaries where possible. A code block that spans chunks is closed at the
+/// end of one and opened again, with the same language, in the next.
+pub fn split_message(content: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    let mut length = 0;
+    // Whether the chunk holds nothing but the reopened fence
+    let mut fresh = true;
+    // The line that opened the code block we are in
+    let mut fence: Option<&str> = None;
+
+    for line in content.lines() {
+        let after = match (line.trim_start().starts_with(FENCE), fence) {
+            (true, Some(_)) => None,
+            (true, None) => Some(line),
+            (false, open) => open,
+        };
+        let closing = if after.is_some() { FENCE.len() + 1 } else { 0 };
+        let mut rest = line;
+
+        loop {
+            let separator = usize::from(!chunk.is_empty());
+            let rest_length = rest.chars().count();
+            if length + separator + rest_length + closing <= limit {
+                if separator == 1 {
+                    chunk.push('\n');
+                }
+                chunk.push_str(rest);
+                length += separator + rest_length;
+                fresh = false;
+                break;
+            }
+            if fresh {
+                // A single line longer than a message, cut it where it has to be
+                let room = limit
+                    .saturating_sub(length + separator + fence.map_or(0, |_| FENCE.len() + 1))
+                    .max(1);
+                let cut = rest
+                    .char_indices()
+                    .nth(room)
+                    .map_or(rest.len(), |(index, _)| index);
+                if separator == 1 {
+                    chunk.push('\n');
+                }
+                chunk.push_str(&rest[..cut]);
+                rest = &rest[cut..];
+            }
+            if fence.is_some() {
+                chunk.push('\n');
+                chunk.push_str(FENCE);
+            }
+            chunks.push(std::mem::take(&mut chunk));
+            if let Some(open) = fence {
+                chunk.push_str(open);
+            }
+            length = chunk.chars().count();
+            fresh = true;
+        }
+        fence = after;
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// `content` without the code fence around all of it, if there is one.
+fn unfenced(content: &str) -> &str {
+    let trimmed = content.trim();
+    if !trimmed.starts_with(FENCE) || !trimmed.ends_with(FENCE) || trimmed.len() < 2 * FENCE.len() {
+        return content;
+    }
+    match (trimmed.find('\n'), trimmed.rfind('\n')) {
+        (Some(first), Some(last)) if first < last => &trimmed[first + 1..last],
+        _ => content,
+    }
+}
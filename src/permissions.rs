@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, RoleId, UserId};
+
+use crate::commands;
+use crate::config::PermissionConfig;
+use crate::language::{Language, Text};
+
+/// Who is allowed to run a command, and where.
+///
+/// Deny lists always win. When an allow list is non-empty the caller has to
+/// match it: `allow_channels` restricts where the command runs, `allow_users`
+/// and `allow_roles` together restrict who can run it.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct PermissionRule {
+    pub allow_users: Vec<UserId>,
+    pub allow_roles: Vec<RoleId>,
+    pub allow_channels: Vec<ChannelId>,
+    pub deny_users: Vec<UserId>,
+    pub deny_roles: Vec<RoleId>,
+    pub deny_channels: Vec<ChannelId>,
+}
+
+/// The person running a command and where they are running it.
+#[derive(Debug, Clone, Copy)]
+pub struct Caller<'a> {
+    pub user: UserId,
+    pub roles: &'a [RoleId],
+    pub channel: ChannelId,
+}
+
+/// Why a command was refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Denied {
+    /// The command or group whose rule refused the command.
+    pub rule: String,
+    pub reason: DenyReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenyReason {
+    Channel,
+    Caller,
+}
+
+/// Something a rule can point at, as written in a Discord message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    User(UserId),
+    Role(RoleId),
+    Channel(ChannelId),
+}
+
+impl Target {
+    /// Parse a user (`<@id>`, `<@!id>`), role (`<@&id>`) or channel (`<#id>`) mention.
+    pub fn parse(mention: &str) -> Option<Self> {
+        let inner = mention.strip_prefix('<')?.strip_suffix('>')?;
+        let parse_id = |s: &str| s.parse::<u64>().ok().filter(|id| *id != 0);
+
+        if let Some(id) = inner.strip_prefix("@&") {
+            Some(Self::Role(RoleId::new(parse_id(id)?)))
+        } else if let Some(id) = inner.strip_prefix("@!").or_else(|| inner.strip_prefix('@')) {
+            Some(Self::User(UserId::new(parse_id(id)?)))
+        } else if let Some(id) = inner.strip_prefix('#') {
+            Some(Self::Channel(ChannelId::new(parse_id(id)?)))
+        } else {
+            None
+        }
+    }
+}
+
+impl PermissionRule {
+    fn check(&self, caller: &Caller) -> Result<(), DenyReason> {
+        if self.deny_channels.contains(&caller.channel) {
+            return Err(DenyReason::Channel);
+        }
+        if self.deny_users.contains(&caller.user)
+            || caller.roles.iter().any(|r| self.deny_roles.contains(r))
+        {
+            return Err(DenyReason::Caller);
+        }
+        if !self.allow_channels.is_empty() && !self.allow_channels.contains(&caller.channel) {
+            return Err(DenyReason::Channel);
+        }
+
+        let restricts_callers = !self.allow_users.is_empty() || !self.allow_roles.is_empty();
+        let allowed_caller = self.allow_users.contains(&caller.user)
+            || caller.roles.iter().any(|r| self.allow_roles.contains(r));
+        if restricts_callers && !allowed_caller {
+            return Err(DenyReason::Caller);
+        }
+
+        Ok(())
+    }
+
+    /// Add a target to the matching allow list, removing it from the deny list.
+    pub fn allow(&mut self, target: Target) {
+        match target {
+            Target::User(id) => move_between(&mut self.deny_users, &mut self.allow_users, id),
+            Target::Role(id) => move_between(&mut self.deny_roles, &mut self.allow_roles, id),
+            Target::Channel(id) => {
+                move_between(&mut self.deny_channels, &mut self.allow_channels, id)
+            }
+        }
+    }
+
+    /// Add a target to the matching deny list, removing it from the allow list.
+    pub fn deny(&mut self, target: Target) {
+        match target {
+            Target::User(id) => move_between(&mut self.allow_users, &mut self.deny_users, id),
+            Target::Role(id) => move_between(&mut self.allow_roles, &mut self.deny_roles, id),
+            Target::Channel(id) => {
+                move_between(&mut self.allow_channels, &mut self.deny_channels, id)
+            }
+        }
+    }
+
+    /// A short human readable description of the rule.
+    pub fn describe(&self) -> String {
+        fn list<T: std::fmt::Display>(label: &str, items: &[T], fmt: &str) -> Option<String> {
+            (!items.is_empty()).then(|| {
+                let mentions: Vec<String> = items
+                    .iter()
+                    .map(|id| fmt.replace("{}", &id.to_string()))
+                    .collect();
+                format!("{}: {}", label, mentions.join(" "))
+            })
+        }
+
+        let lines: Vec<String> = [
+            list("allowed channels", &self.allow_channels, "<#{}>"),
+            list("allowed roles", &self.allow_roles, "<@&{}>"),
+            list("allowed users", &self.allow_users, "<@{}>"),
+            list("denied channels", &self.deny_channels, "<#{}>"),
+            list("denied roles", &self.deny_roles, "<@&{}>"),
+            list("denied users", &self.deny_users, "<@{}>"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if lines.is_empty() {
+            "everyone, everywhere".to_string()
+        } else {
+            lines.join("\n")
+        }
+    }
+}
+
+fn move_between<T: PartialEq + Copy>(from: &mut Vec<T>, to: &mut Vec<T>, id: T) {
+    from.retain(|x| *x != id);
+    if !to.contains(&id) {
+        to.push(id);
+    }
+}
+
+/// Per guild rule overrides, keyed by command or group name. They are stored
+/// with the rest of the guild settings.
+pub type GuildRules = HashMap<String, PermissionRule>;
+
+/// Commands admins can always run, whatever the rules say, so no rule can
+/// lock them out of undoing it.
+const ADMIN_COMMANDS: &[&str] = &["perm", "config"];
+
+/// The permission rules from the config file. A guild override replaces the
+/// file rule of the same name for that guild.
+pub struct Permissions {
+    config: PermissionConfig,
+}
+
+impl Permissions {
+    pub fn new(config: PermissionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether the caller may change permissions and settings at runtime.
+    pub fn is_admin(&self, user: UserId, roles: &[RoleId]) -> bool {
+        self.config.admin_users.contains(&user)
+            || roles.iter().any(|r| self.config.admin_roles.contains(r))
+    }
+
+    /// Whether `name` is a group defined in the config.
+    pub fn is_group(&self, name: &str) -> bool {
+        self.config.groups.contains_key(name)
+    }
+
+    /// The rule in effect for a command or group name given a guild's overrides, if any.
+    pub fn rule_for<'a>(
+        &'a self,
+        overrides: &'a GuildRules,
+        name: &str,
+    ) -> Option<&'a PermissionRule> {
+        overrides.get(name).or_else(|| self.config.rules.get(name))
+    }
+
+    /// Check every rule that applies to `command`: its own, and those of the groups it is in.
+    /// Admins skip the rules for `perm` and `config`.
+    pub fn check(
+        &self,
+        overrides: &GuildRules,
+        command: &str,
+        caller: &Caller,
+    ) -> Result<(), Denied> {
+        if is_admin_command(command) && self.is_admin(caller.user, caller.roles) {
+            return Ok(());
+        }
+
+        let groups = self
+            .config
+            .groups
+            .iter()
+            .filter(|(_, commands)| commands.iter().any(|c| c == command))
+            .map(|(name, _)| name.as_str());
+
+        for name in std::iter::once(command).chain(groups) {
+            if let Some(rule) = self.rule_for(overrides, name) {
+                rule.check(caller).map_err(|reason| Denied {
+                    rule: name.to_string(),
+                    reason,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run an admin `!perm` command against a guild's overrides and return the reply.
+    ///
+    /// Supported forms are `allow <name> <mentions...>`, `deny <name> <mentions...>`,
+    /// `reset <name>` and `show <name>`, where `<name>` is a command or a group.
+    /// Overrides start from the rule currently in effect.
+    pub fn apply_command(&self, overrides: &mut GuildRules, args: &str) -> String {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let (action, name, rest) = match parts.as_slice() {
+            [action, name, rest @ ..] => (*action, *name, rest),
+            _ => return PERM_USAGE.to_string(),
+        };
+
+        // rules are kept under the command's own name, not an alias
+        let name = match commands::find(name) {
+            Some(command) => command.name,
+            None if self.is_group(name) => name,
+            None => return format!("`{}` নামে কোন কমান্ড বা গ্রুপ নাই। 🤔", name),
+        };
+
+        match action {
+            "allow" | "deny" => {
+                let targets: Option<Vec<Target>> = rest.iter().map(|m| Target::parse(m)).collect();
+                let targets = match targets {
+                    Some(targets) if !targets.is_empty() => targets,
+                    _ => return PERM_USAGE.to_string(),
+                };
+
+                let current = self.rule_for(overrides, name).cloned().unwrap_or_default();
+                let rule = overrides.entry(name.to_string()).or_insert(current);
+                for target in targets {
+                    if action == "allow" {
+                        rule.allow(target);
+                    } else {
+                        rule.deny(target);
+                    }
+                }
+                format!("`{}` updated:\n{}", name, rule.describe())
+            }
+            "reset" if rest.is_empty() => {
+                if overrides.remove(name).is_some() {
+                    format!("`{}` is back to the defaults.", name)
+                } else {
+                    format!("`{}` had no overrides here.", name)
+                }
+            }
+            "show" if rest.is_empty() => {
+                let rule = self
+                    .rule_for(overrides, name)
+                    .map(PermissionRule::describe)
+                    .unwrap_or_else(|| "everyone, everywhere".to_string());
+                format!("`{}`:\n{}", name, rule)
+            }
+            _ => PERM_USAGE.to_string(),
+        }
+    }
+}
+
+/// Whether admins run `command` regardless of the rules.
+pub fn is_admin_command(command: &str) -> bool {
+    ADMIN_COMMANDS.contains(&command)
+}
+
+const PERM_USAGE: &str = "Usage: `!perm allow|deny <command|group> <#channel|@role|@user>...`, `!perm reset <command|group>`, `!perm show <command|group>`";
+
+/// The reply sent when a command is refused.
+pub fn denied_message(denied: &Denied, language: Option<Language>) -> &'static str {
+    let text = match denied.reason {
+        DenyReason::Channel => Text::bangla(
+            "এই কমান্ড এই চ্যানেলে চলবে না ভাই। 🚫",
+            "This command doesn't run in this channel, bro. 🚫",
+        ),
+        DenyReason::Caller => Text::bangla(
+            "আপনার এই কমান্ড চালানোর পারমিশন নাই। 🙅",
+            "You don't have permission to run this command. 🙅",
+        ),
+    };
+    text.get(language)
+}
@@ -0,0 +1,188 @@
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+use thiserror::Error;
+
+use crate::reminders;
+
+/// Reactions voters pick an option with, one per option.
+pub const NUMBER_EMOJIS: [&str; 10] = ["1️⃣", "2️⃣", "3️⃣", "4️⃣", "5️⃣", "6️⃣", "7️⃣", "8️⃣", "9️⃣", "🔟"];
+pub const MIN_OPTIONS: usize = 2;
+pub const MAX_OPTIONS: usize = NUMBER_EMOJIS.len();
+pub const DEFAULT_DURATION_SECS: i64 = 60 * 60;
+pub const MAX_DURATION_SECS: i64 = 7 * 24 * 60 * 60;
+
+pub const USAGE: &str = "Usage: `!poll \"question\" option1 option2 ... [--duration 1h] [--multi]`";
+
+const DURATION_FLAG: &str = "--duration";
+const MULTI_FLAG: &str = "--multi";
+/// Width of a full bar in the results.
+const BAR_WIDTH: usize = 20;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum PollError {
+    #[error("প্রশ্নটা কি?")]
+    MissingQuestion,
+    #[error("কমপক্ষে {MIN_OPTIONS} টা অপশন লাগবে।")]
+    TooFewOptions,
+    #[error("{MAX_OPTIONS} টার বেশি অপশন নেয়া যাবে না।")]
+    TooManyOptions,
+    #[error("`{0}` সময়টা বুঝলাম না, `30m`, `2h` বা `1d` এর মত দেন (সর্বোচ্চ ৭ দিন)।")]
+    BadDuration(String),
+}
+
+/// What `!poll` asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedPoll {
+    pub question: String,
+    pub options: Vec<String>,
+    pub duration_secs: i64,
+    /// Whether a user may vote for more than one option.
+    pub multi: bool,
+}
+
+/// A running (or closed) poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poll {
+    /// Assigned by storage, ignored when adding.
+    pub id: i64,
+    pub guild: Option<GuildId>,
+    pub channel: ChannelId,
+    /// The message votes are cast on.
+    pub message: MessageId,
+    pub author: UserId,
+    pub question: String,
+    pub options: Vec<String>,
+    pub multi: bool,
+    /// Unix timestamp in seconds.
+    pub closes_at: i64,
+}
+
+/// Split arguments on whitespace, keeping `"quoted parts"` together. Phones
+/// like to turn quotes into curly ones, so those work too.
+fn tokenize(args: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in args.chars() {
+        match c {
+            '"' | '“' | '”' => {
+                if quoted || !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                quoted = !quoted;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens.retain(|token| !token.trim().is_empty());
+    tokens
+}
+
+/// Parse `"question" option1 option2 ... [--duration 1h] [--multi]`.
+pub fn parse(args: &str) -> Result<ParsedPoll, PollError> {
+    let mut duration_secs = DEFAULT_DURATION_SECS;
+    let mut multi = false;
+    let mut words = Vec::new();
+
+    let mut tokens = tokenize(args).into_iter();
+    while let Some(token) = tokens.next() {
+        match token.as_str() {
+            MULTI_FLAG => multi = true,
+            DURATION_FLAG => {
+                let value = tokens.next().unwrap_or_default();
+                duration_secs = reminders::parse_compact(&value)
+                    .map(|duration| duration.num_seconds())
+                    .filter(|secs| (60..=MAX_DURATION_SECS).contains(secs))
+                    .ok_or(PollError::BadDuration(value))?;
+            }
+            _ => words.push(token),
+        }
+    }
+
+    let mut words = words.into_iter();
+    let question = words.next().ok_or(PollError::MissingQuestion)?;
+    let options: Vec<String> = words.collect();
+    if options.len() < MIN_OPTIONS {
+        return Err(PollError::TooFewOptions);
+    }
+    if options.len() > MAX_OPTIONS {
+        return Err(PollError::TooManyOptions);
+    }
+
+    Ok(ParsedPoll {
+        question,
+        options,
+        duration_secs,
+        multi,
+    })
+}
+
+/// Which option a reaction votes for.
+pub fn option_for(emoji: &str) -> Option<usize> {
+    NUMBER_EMOJIS.iter().position(|number| *number == emoji)
+}
+
+/// The message people vote on.
+pub fn poll_message(poll: &ParsedPoll, closes_at: i64) -> String {
+    let mut lines = vec![format!("📊 **{}**", poll.question)];
+    for (emoji, option) in NUMBER_EMOJIS.iter().zip(&poll.options) {
+        lines.push(format!("{} {}", emoji, option));
+    }
+    let how = if poll.multi {
+        "Vote for as many as you like"
+    } else {
+        "One vote each"
+    };
+    // Discord shows `<t:..:R>` as "in 2 hours" in every reader's own timezone
+    lines.push(format!("_{}, closes <t:{}:R>_", how, closes_at));
+    lines.join("\n")
+}
+
+/// Count votes per option. `votes` holds one entry per user and option voted for.
+pub fn tally(option_count: usize, votes: &[(UserId, usize)]) -> Vec<usize> {
+    let mut counts = vec![0; option_count];
+    for (_, option) in votes {
+        if let Some(count) = counts.get_mut(*option) {
+            *count += 1;
+        }
+    }
+    counts
+}
+
+/// The results, with a bar per option.
+pub fn results_message(poll: &Poll, counts: &[usize]) -> String {
+    let total: usize = counts.iter().sum();
+    let max = counts.iter().copied().max().unwrap_or(0);
+
+    let mut lines = vec![format!("📊 **{}** is closed", poll.question)];
+    for (index, option) in poll.options.iter().enumerate() {
+        let count = counts.get(index).copied().unwrap_or(0);
+        let (bar, percent) = match (max, total) {
+            (0, _) | (_, 0) => (0, 0),
+            _ => (count * BAR_WIDTH / max, count * 100 / total),
+        };
+        let winner = if count == max && max > 0 { " 🏆" } else { "" };
+        lines.push(format!(
+            "{} {}\n`{}{}` {} ({}%){}",
+            NUMBER_EMOJIS[index],
+            option,
+            "█".repeat(bar),
+            "░".repeat(BAR_WIDTH - bar),
+            count,
+            percent,
+            winner
+        ));
+    }
+    if total == 0 {
+        lines.push("Nobody voted. 🦗".to_string());
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,153 @@
+use std::collections::{HashSet, VecDeque};
+
+use serde::Deserialize;
+use serenity::model::id::{ChannelId, MessageId};
+
+/// How many reacted messages are remembered for deduplication.
+const SEEN_CAPACITY: usize = 1024;
+
+/// Where a pull request lives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+}
+
+/// A pull (or merge) request linked in a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequestLink {
+    pub forge: Forge,
+    /// `owner/repo` on GitHub, the full project path on GitLab.
+    pub repo: String,
+    pub number: u64,
+}
+
+impl PullRequestLink {
+    /// Parse a single URL, e.g. `https://github.com/owner/repo/pull/12` or
+    /// `https://gitlab.com/group/project/-/merge_requests/34`.
+    pub fn parse(url: &str) -> Option<Self> {
+        Self::parse_on(url, None)
+    }
+
+    /// Like [`PullRequestLink::parse`], also taking GitHub links on
+    /// `github_host`, a GitHub Enterprise server.
+    pub fn parse_on(url: &str, github_host: Option<&str>) -> Option<Self> {
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))?;
+        let (host, path) = rest.split_once('/')?;
+        let path = path.split(['?', '#']).next()?.trim_end_matches('/');
+
+        if host == "github.com"
+            || host == "www.github.com"
+            || github_host.is_some_and(|github| host.eq_ignore_ascii_case(github))
+        {
+            let parts: Vec<&str> = path.split('/').collect();
+            match parts.as_slice() {
+                [owner, repo, "pull", number, ..] => Some(Self {
+                    forge: Forge::GitHub,
+                    repo: format!("{}/{}", owner, repo),
+                    number: number.parse().ok()?,
+                }),
+                _ => None,
+            }
+        } else if host.contains("gitlab") {
+            let (project, rest) = path.split_once("/-/merge_requests/")?;
+            let number = rest.split('/').next()?.parse().ok()?;
+            Some(Self {
+                forge: Forge::GitLab,
+                repo: project.to_string(),
+                number,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Every pull request linked anywhere in a message.
+pub fn find_pull_requests(content: &str) -> Vec<PullRequestLink> {
+    content
+        .split_whitespace()
+        // Discord users wrap links in <> to suppress the embed
+        .map(|word| word.trim_matches(|c| matches!(c, '<' | '>' | '(' | ')')))
+        .filter_map(PullRequestLink::parse)
+        .collect()
+}
+
+/// Which messages with pull request links get approving reactions.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PrReactionConfig {
+    /// Channels to watch. Empty turns the feature off.
+    pub channels: Vec<ChannelId>,
+    /// Only react to these repos (`owner/repo`, or the GitLab project path).
+    /// Empty allows every repo.
+    pub repos: Vec<String>,
+    /// Only react when the message also contains this word, e.g. `review`.
+    pub keyword: Option<String>,
+    /// How many emojis to react with.
+    pub emoji_count: usize,
+}
+
+impl Default for PrReactionConfig {
+    fn default() -> Self {
+        Self {
+            channels: Vec::new(),
+            repos: Vec::new(),
+            keyword: None,
+            emoji_count: 5,
+        }
+    }
+}
+
+impl PrReactionConfig {
+    /// Whether a message in `channel` should get approving reactions.
+    pub fn matches(&self, channel: ChannelId, content: &str) -> bool {
+        if !self.channels.contains(&channel) {
+            return false;
+        }
+
+        if let Some(keyword) = &self.keyword
+            && !content.to_lowercase().contains(&keyword.to_lowercase())
+        {
+            return false;
+        }
+
+        find_pull_requests(content).iter().any(|link| {
+            self.repos.is_empty()
+                || self
+                    .repos
+                    .iter()
+                    .any(|repo| repo.eq_ignore_ascii_case(&link.repo))
+        })
+    }
+}
+
+/// Remembers the most recent messages that were reacted to, so editing a
+/// message doesn't get it a second round of reactions.
+#[derive(Debug, Default)]
+pub struct SeenMessages {
+    order: VecDeque<MessageId>,
+    seen: HashSet<MessageId>,
+}
+
+impl SeenMessages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark a message as seen. Returns `false` when it was already seen.
+    pub fn insert(&mut self, message: MessageId) -> bool {
+        if !self.seen.insert(message) {
+            return false;
+        }
+        self.order.push_back(message);
+        if self.order.len() > SEEN_CAPACITY
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        true
+    }
+}
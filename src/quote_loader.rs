@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use rfortune::loader;
 
 pub fn load_from_file(file_path: &str) -> Result<Vec<String>, std::io::Error> {
@@ -37,3 +39,37 @@ pub fn load_from_folder(folder_path: &str) -> Result<Vec<String>, std::io::Error
         ))
     }
 }
+
+/// Load every fortune file in a folder, keyed by the file name without extension,
+/// so quotes can be picked from a single category.
+pub fn load_categories_from_folder(
+    folder_path: &str,
+) -> Result<HashMap<String, Vec<String>>, std::io::Error> {
+    let mut categories = HashMap::new();
+    let path = std::path::Path::new(folder_path);
+
+    if !path.is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Folder not found: {:?}", path),
+        ));
+    }
+
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let file_path = entry.path();
+        if file_path.is_file() {
+            let category = file_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let quotes = load_from_file(file_path.to_str().unwrap())?;
+            categories
+                .entry(category)
+                .or_insert_with(Vec::new)
+                .extend(quotes);
+        }
+    }
+
+    Ok(categories)
+}
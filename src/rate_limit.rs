@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serenity::model::id::{ChannelId, RoleId, UserId};
+
+use crate::config::{CooldownConfig, RateLimitConfig};
+use crate::language::{Language, Text};
+
+/// The limiter only prunes expired entries once a map grows past this size.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Which cooldown stopped a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    User,
+    Channel,
+    Global,
+}
+
+/// Returned when a command is still cooling down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throttled {
+    pub scope: Scope,
+    pub retry_after: Duration,
+}
+
+/// Tracks when each command was last used per user, per channel and globally.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    by_user: HashMap<(String, UserId), Instant>,
+    by_channel: HashMap<(String, ChannelId), Instant>,
+    global: HashMap<String, Instant>,
+    notices: HashMap<UserId, Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            by_user: HashMap::new(),
+            by_channel: HashMap::new(),
+            global: HashMap::new(),
+            notices: HashMap::new(),
+        }
+    }
+
+    /// Cooldowns that apply to the given command.
+    pub fn cooldown_for(&self, command: &str) -> CooldownConfig {
+        self.config
+            .commands
+            .get(command)
+            .copied()
+            .unwrap_or(self.config.default)
+    }
+
+    /// Whether any of the given roles is exempt from rate limiting.
+    pub fn is_exempt(&self, roles: &[RoleId]) -> bool {
+        roles.iter().any(|r| self.config.exempt_roles.contains(r))
+    }
+
+    /// Check whether `command` may run right now. On success the use is recorded,
+    /// otherwise the most restrictive cooldown is returned and nothing is recorded.
+    pub fn check(
+        &mut self,
+        command: &str,
+        user: UserId,
+        channel: ChannelId,
+        now: Instant,
+    ) -> Result<(), Throttled> {
+        let cooldown = self.cooldown_for(command);
+        let key = command.to_string();
+
+        let remaining = [
+            (
+                Scope::Global,
+                remaining(self.global.get(&key), cooldown.global_secs, now),
+            ),
+            (
+                Scope::Channel,
+                remaining(
+                    self.by_channel.get(&(key.clone(), channel)),
+                    cooldown.channel_secs,
+                    now,
+                ),
+            ),
+            (
+                Scope::User,
+                remaining(
+                    self.by_user.get(&(key.clone(), user)),
+                    cooldown.user_secs,
+                    now,
+                ),
+            ),
+        ];
+
+        if let Some((scope, retry_after)) = remaining
+            .into_iter()
+            .filter_map(|(scope, left)| left.map(|l| (scope, l)))
+            .max_by_key(|(_, left)| *left)
+        {
+            return Err(Throttled { scope, retry_after });
+        }
+
+        self.prune(now);
+        self.global.insert(key.clone(), now);
+        self.by_channel.insert((key.clone(), channel), now);
+        self.by_user.insert((key, user), now);
+        Ok(())
+    }
+
+    /// Whether a throttled (or refused) user should be told about it. The notice
+    /// has its own cooldown so that spamming a throttled command doesn't spam
+    /// the warning.
+    pub fn should_notify(&mut self, user: UserId, now: Instant) -> bool {
+        let window = Duration::from_secs(self.config.notice_cooldown_secs);
+        match self.notices.get(&user) {
+            Some(last) if now.duration_since(*last) < window => false,
+            _ => {
+                self.notices.insert(user, now);
+                true
+            }
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        let longest = self
+            .config
+            .commands
+            .values()
+            .chain(std::iter::once(&self.config.default))
+            .map(|c| c.user_secs.max(c.channel_secs).max(c.global_secs))
+            .max()
+            .unwrap_or(0)
+            .max(self.config.notice_cooldown_secs);
+        let keep = |last: &Instant| now.duration_since(*last) < Duration::from_secs(longest);
+
+        if self.by_user.len() > PRUNE_THRESHOLD {
+            self.by_user.retain(|_, last| keep(last));
+        }
+        if self.by_channel.len() > PRUNE_THRESHOLD {
+            self.by_channel.retain(|_, last| keep(last));
+        }
+        if self.notices.len() > PRUNE_THRESHOLD {
+            self.notices.retain(|_, last| keep(last));
+        }
+    }
+}
+
+fn remaining(last: Option<&Instant>, secs: u64, now: Instant) -> Option<Duration> {
+    let window = Duration::from_secs(secs);
+    let elapsed = now.duration_since(*last?);
+    (elapsed < window).then(|| window - elapsed)
+}
+
+/// The reply sent to someone who got throttled.
+pub fn throttle_message(throttled: &Throttled, language: Option<Language>) -> String {
+    // round up so we never tell someone to come back in 0 seconds
+    let secs =
+        throttled.retry_after.as_secs() + u64::from(throttled.retry_after.subsec_nanos() > 0);
+
+    let lines: &[Text] = match throttled.scope {
+        Scope::User => &[
+            Text::bangla(
+                "আস্তে ভাই, আস্তে! {} সেকেন্ড পরে আবার ট্রাই করেন। 🐢",
+                "Easy bro, easy! Try again in {} seconds. 🐢",
+            ),
+            Text::bangla(
+                "এত তাড়া কিসের? {} সেকেন্ড চা খান, তারপর আসেন। ☕",
+                "What's the rush? Have some tea for {} seconds, then come back. ☕",
+            ),
+            Text::bangla(
+                "Cooldown চলতেছে বস, {}s wait করেন। 😤",
+                "Cooldown in progress boss, wait {}s. 😤",
+            ),
+        ],
+        Scope::Channel => &[
+            Text::bangla(
+                "এই চ্যানেলে অনেক হইসে, {} সেকেন্ড ব্রেক নেন সবাই। 🥱",
+                "That's enough for this channel, everyone take a {} second break. 🥱",
+            ),
+            Text::bangla(
+                "চ্যানেল গরম হয়ে গেছে, {}s ঠান্ডা হইতে দেন। 🧊",
+                "The channel is overheating, let it cool down for {}s. 🧊",
+            ),
+        ],
+        Scope::Global => &[
+            Text::bangla(
+                "সার্ভার হাঁপাইতেছে, {} সেকেন্ড পরে আসেন। 🥵",
+                "The server is out of breath, come back in {} seconds. 🥵",
+            ),
+            Text::bangla(
+                "সবাই একসাথে ডাকলে আমি কার কথা শুনবো? {}s পরে। 🫠",
+                "If everyone calls me at once, who do I listen to? In {}s. 🫠",
+            ),
+        ],
+    };
+
+    let index = rand::rng().random_range(0..lines.len());
+    lines[index].get(language).replace("{}", &secs.to_string())
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Most distinct reactions Discord allows on one message.
+pub const MAX_REACTIONS: usize = 20;
+
+/// How `!oracle` and `!summon` hand out their emojis.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmojiMode {
+    /// Post the emojis as a message.
+    #[default]
+    Text,
+    /// React to the message with them.
+    Reactions,
+}
+
+impl EmojiMode {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "react" | "reactions" => Some(Self::Reactions),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Reactions => "reactions",
+        }
+    }
+}
+
+/// Whether Discord takes the emoji as a reaction. Sequences glued together
+/// with a zero width joiner, flags and skin tones get rejected, so they are
+/// left out up front instead of wasting a request on them.
+pub fn is_reactable(emoji: &str) -> bool {
+    !emoji.is_empty()
+        && !emoji.chars().any(|c| {
+            c == '\u{200D}'
+                || ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+                || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+        })
+}
+
+/// The emojis to react with, given how many reactions the message already has.
+pub fn pick_reactions(emojis: &[String], existing: usize) -> Vec<&str> {
+    let mut picked: Vec<&str> = Vec::new();
+    for emoji in emojis {
+        if is_reactable(emoji) && !picked.contains(&emoji.as_str()) {
+            picked.push(emoji);
+        }
+    }
+    picked.truncate(MAX_REACTIONS.saturating_sub(existing));
+    picked
+}
@@ -0,0 +1,261 @@
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use thiserror::Error;
+
+/// Timezone for users that haven't picked one with `!timezone`.
+pub const DEFAULT_TIMEZONE: Tz = Tz::UTC;
+/// How many pending reminders a single user may set.
+pub const MAX_PER_USER: usize = 25;
+/// Flag that sends the reminder as a DM instead of in the channel.
+pub const DM_FLAG: &str = "--dm";
+
+/// When `tomorrow` doesn't come with a time.
+const DEFAULT_HOUR: u32 = 9;
+/// Furthest ahead a reminder can be set, a year.
+const MAX_AHEAD_SECS: i64 = 366 * 24 * 60 * 60;
+
+pub const REMINDME_USAGE: &str = "Usage: `!remindme in 2h check staging`, `!remindme at 17:00 standup`, `!remindme tomorrow at 10:00 deploy` (add `--dm` to get it as a DM)";
+pub const REMIND_USAGE: &str =
+    "Usage: `!remind @user in 30m review my PR`, `!remind @user tomorrow standup notes`";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ReminderError {
+    #[error("কবে মনে করাবো? `in 2h`, `at 17:00` অথবা `tomorrow` দিয়ে শুরু করেন।")]
+    MissingTime,
+    #[error("`{0}` সময়টা বুঝলাম না।")]
+    BadTime(String),
+    #[error("কি মনে করাবো সেটাও তো লিখবেন।")]
+    MissingText,
+}
+
+/// A scheduled reminder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reminder {
+    /// Assigned by storage, ignored when adding.
+    pub id: i64,
+    pub guild: Option<GuildId>,
+    pub channel: ChannelId,
+    /// Who set the reminder.
+    pub author: UserId,
+    /// Who gets reminded, the author for `!remindme`.
+    pub target: UserId,
+    pub text: String,
+    /// Unix timestamp in seconds.
+    pub due_at: i64,
+    /// Deliver as a DM instead of in `channel`.
+    pub dm: bool,
+}
+
+/// The parts of a `!remindme`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedReminder {
+    pub due_at: DateTime<Utc>,
+    pub text: String,
+    pub dm: bool,
+}
+
+/// Parse `in 2h ...`, `at 17:00 ...` or `tomorrow [at 10:00] ...`, with times
+/// read in the user's timezone.
+pub fn parse(args: &str, now: DateTime<Utc>, tz: Tz) -> Result<ParsedReminder, ReminderError> {
+    let dm = args.split_whitespace().any(|word| word == DM_FLAG);
+    let words: Vec<&str> = args
+        .split_whitespace()
+        .filter(|word| *word != DM_FLAG)
+        .collect();
+
+    let (due_at, rest) = match words.as_slice() {
+        ["in", rest @ ..] => {
+            let (duration, used) = parse_duration(rest)?;
+            let due_at = now
+                .checked_add_signed(duration)
+                .ok_or_else(|| ReminderError::BadTime(rest[..used].join(" ")))?;
+            (due_at, &rest[used..])
+        }
+        ["at", time, rest @ ..] => (next_time(now, tz, time, 0)?, rest),
+        ["tomorrow", "at", time, rest @ ..] => (next_time(now, tz, time, 1)?, rest),
+        ["tomorrow", rest @ ..] => {
+            let time = format!("{}:00", DEFAULT_HOUR);
+            (next_time(now, tz, &time, 1)?, rest)
+        }
+        _ => return Err(ReminderError::MissingTime),
+    };
+
+    if rest.is_empty() {
+        return Err(ReminderError::MissingText);
+    }
+
+    Ok(ParsedReminder {
+        due_at,
+        text: rest.join(" "),
+        dm,
+    })
+}
+
+/// Read a duration from the start of `words`, e.g. `2h`, `1h30m` or
+/// `2 hours 10 minutes`, at most a year. Returns it with the number of words used.
+fn parse_duration(words: &[&str]) -> Result<(Duration, usize), ReminderError> {
+    let mut total = Some(Duration::zero());
+    let mut used = 0;
+
+    while used < words.len() {
+        if let Some(duration) = parse_compact(words[used]) {
+            total = total.and_then(|total| total.checked_add(&duration));
+            used += 1;
+        } else if let (Ok(amount), Some(unit)) = (words[used].parse::<i64>(), words.get(used + 1))
+            && let Some(duration) = unit_duration(unit, amount)
+        {
+            total = total.and_then(|total| total.checked_add(&duration));
+            used += 2;
+        } else {
+            break;
+        }
+    }
+
+    match (used, words.first(), total) {
+        (0, Some(word), _) => Err(ReminderError::BadTime(word.to_string())),
+        (0, None, _) => Err(ReminderError::MissingTime),
+        (_, _, Some(total))
+            if total > Duration::zero() && total.num_seconds() <= MAX_AHEAD_SECS =>
+        {
+            Ok((total, used))
+        }
+        _ => Err(ReminderError::BadTime(words[..used].join(" "))),
+    }
+}
+
+/// A single word duration: `2h`, `90m`, `1h30m`, `3d`. `None` as well when
+/// it's too long to represent.
+pub fn parse_compact(word: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut number = String::new();
+
+    for c in word.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+        } else {
+            let amount: i64 = number.parse().ok()?;
+            total = total.checked_add(&unit_duration(&c.to_string(), amount)?)?;
+            number.clear();
+        }
+    }
+
+    (number.is_empty() && total > Duration::zero()).then_some(total)
+}
+
+fn unit_duration(unit: &str, amount: i64) -> Option<Duration> {
+    match unit.to_lowercase().trim_end_matches(',') {
+        "s" | "sec" | "secs" | "second" | "seconds" => Duration::try_seconds(amount),
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(amount),
+        "d" | "day" | "days" => Duration::try_days(amount),
+        "w" | "week" | "weeks" => Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
+/// `17:00`, `17`, `5pm` or `5:30pm`.
+fn parse_clock(input: &str) -> Option<NaiveTime> {
+    let lower = input.to_lowercase();
+    let (clock, offset) = if let Some(clock) = lower.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = lower.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (lower.as_str(), None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+
+    let hour = match offset {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(offset) => hour % 12 + offset,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// The next time the clock shows `time` in `tz`, at least `days` days from now.
+fn next_time(
+    now: DateTime<Utc>,
+    tz: Tz,
+    time: &str,
+    days: i64,
+) -> Result<DateTime<Utc>, ReminderError> {
+    let clock = parse_clock(time).ok_or_else(|| ReminderError::BadTime(time.to_string()))?;
+    let today = now.with_timezone(&tz).date_naive();
+
+    for offset in days..days + 2 {
+        let date = today + Duration::days(offset);
+        // `earliest` skips times that don't exist on DST days
+        if let Some(local) = tz.from_local_datetime(&date.and_time(clock)).earliest() {
+            let due = local.with_timezone(&Utc);
+            if due > now {
+                return Ok(due);
+            }
+        }
+    }
+    Err(ReminderError::BadTime(time.to_string()))
+}
+
+/// Parse a timezone name like `Asia/Dhaka` or `UTC`.
+pub fn parse_timezone(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// When a reminder is due, in the reader's timezone.
+pub fn format_due(due_at: i64, tz: Tz) -> String {
+    match Utc.timestamp_opt(due_at, 0).single() {
+        Some(due) => due
+            .with_timezone(&tz)
+            .format("%a %d %b %H:%M %Z")
+            .to_string(),
+        None => "?".to_string(),
+    }
+}
+
+/// The message that goes out when a reminder is due.
+pub fn delivery_message(reminder: &Reminder) -> String {
+    if reminder.author == reminder.target {
+        format!("⏰ <@{}> মনে আছে? {}", reminder.target, reminder.text)
+    } else {
+        format!(
+            "⏰ <@{}>, <@{}> asked me to remind you: {}",
+            reminder.target, reminder.author, reminder.text
+        )
+    }
+}
+
+/// The `!reminders` list.
+pub fn list_message(reminders: &[Reminder], user: UserId, tz: Tz, prefix: &str) -> String {
+    if reminders.is_empty() {
+        return "কোন রিমাইন্ডার নাই। 🎉".to_string();
+    }
+
+    let mut lines = vec![format!("**Your reminders** ({})", tz.name())];
+    for reminder in reminders {
+        let mut line = format!(
+            "`#{}` {}: {}",
+            reminder.id,
+            format_due(reminder.due_at, tz),
+            reminder.text
+        );
+        if reminder.target != user {
+            line.push_str(&format!(" (for <@{}>)", reminder.target));
+        } else if reminder.author != user {
+            line.push_str(&format!(" (from <@{}>)", reminder.author));
+        }
+        if reminder.dm {
+            line.push_str(" 📩");
+        }
+        lines.push(line);
+    }
+    lines.push(format!(
+        "Cancel one with `{}reminders cancel <id>`.",
+        prefix
+    ));
+    lines.join("\n")
+}
@@ -0,0 +1,263 @@
+use chrono::{Duration, NaiveDate};
+use rand::Rng;
+use serenity::model::id::ChannelId;
+use thiserror::Error;
+
+use crate::reminders;
+
+pub const USAGE: &str = "Usage: `!sprint`, `!sprint start <name> <end: 2024-01-31|2w>`, `!sprint goal <text>`, `!sprint add <item>`, `!sprint done <number>`, `!sprint end`";
+
+/// Longest sprint a length like `2w` may ask for.
+const MAX_LENGTH_DAYS: i64 = 366;
+
+/// Width of the longest burndown bar.
+const BAR_WIDTH: usize = 20;
+
+/// Lines that may close the status, next to the original `!sprint` joke.
+const FLAVOR: &[&str] = &[
+    "কাল থেকে সিরিয়াস, প্রমিস।",
+    "The burndown is more of a burn-up if you squint.",
+    "Story points are made up and the points don't matter.",
+];
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SprintError {
+    #[error("Sprint এর নাম আর শেষ হওয়ার তারিখ দেন: `!sprint start <name> <2024-01-31|2w>`")]
+    MissingName,
+    #[error("`{0}` তারিখটা বুঝলাম না, `2024-01-31` বা `2w` এর মত দেন।")]
+    BadDate(String),
+    #[error("Sprint শুরুর আগেই শেষ? 🤔")]
+    EndsBeforeStart,
+    #[error("কি লিখবো?")]
+    MissingText,
+    #[error("`{0}` কোন item নাম্বার না।")]
+    BadItem(String),
+    #[error("`{0}` দিয়ে কি করবো?")]
+    UnknownAction(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SprintCommand {
+    Status,
+    Start { name: String, ends_on: NaiveDate },
+    Goal(String),
+    Add(String),
+    Done(u32),
+    End,
+}
+
+impl SprintCommand {
+    /// Parse `!sprint` arguments. `today` is where relative end dates count from.
+    pub fn parse(args: &str, today: NaiveDate) -> Result<Self, SprintError> {
+        let args = args.trim();
+        let (action, rest) = args.split_once(' ').unwrap_or((args, ""));
+        let rest = rest.trim();
+
+        match action.to_lowercase().as_str() {
+            "" | "status" => Ok(Self::Status),
+            "start" => {
+                let (name, end) = rest.rsplit_once(' ').ok_or(SprintError::MissingName)?;
+                let ends_on = parse_end(end, today)?;
+                if ends_on < today {
+                    return Err(SprintError::EndsBeforeStart);
+                }
+                Ok(Self::Start {
+                    name: name.trim().to_string(),
+                    ends_on,
+                })
+            }
+            "goal" | "add" if rest.is_empty() => Err(SprintError::MissingText),
+            "goal" => Ok(Self::Goal(rest.to_string())),
+            "add" => Ok(Self::Add(rest.to_string())),
+            "done" => rest
+                .trim_start_matches('#')
+                .parse()
+                .map(Self::Done)
+                .map_err(|_| SprintError::BadItem(rest.to_string())),
+            "end" => Ok(Self::End),
+            _ => Err(SprintError::UnknownAction(action.to_string())),
+        }
+    }
+}
+
+/// `2024-01-31`, or a length like `2w` or `10d` from today.
+fn parse_end(input: &str, today: NaiveDate) -> Result<NaiveDate, SprintError> {
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    reminders::parse_compact(input)
+        .map(|length| length.num_days())
+        .filter(|days| (1..=MAX_LENGTH_DAYS).contains(days))
+        .and_then(|days| today.checked_add_signed(Duration::days(days)))
+        .ok_or_else(|| SprintError::BadDate(input.to_string()))
+}
+
+/// A channel's sprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sprint {
+    /// Assigned by storage, ignored when starting.
+    pub id: i64,
+    pub channel: ChannelId,
+    pub name: String,
+    pub started_on: NaiveDate,
+    pub ends_on: NaiveDate,
+    pub goals: Vec<String>,
+}
+
+/// Something to get done in a sprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SprintItem {
+    /// Numbered per sprint, starting at 1.
+    pub number: u32,
+    pub title: String,
+    pub added_on: NaiveDate,
+    pub done_on: Option<NaiveDate>,
+}
+
+/// A random line for the end of the status, the original joke included.
+pub fn flavor(joke: &str) -> String {
+    let index = rand::rng().random_range(0..=FLAVOR.len());
+    FLAVOR.get(index).copied().unwrap_or(joke).to_string()
+}
+
+fn days_left(sprint: &Sprint, today: NaiveDate) -> String {
+    let days = (sprint.ends_on - today).num_days();
+    match days {
+        0 => "ends today".to_string(),
+        1 => "1 day left".to_string(),
+        days if days > 1 => format!("{} days left", days),
+        -1 => "1 day overdue".to_string(),
+        days => format!("{} days overdue", -days),
+    }
+}
+
+/// Items still open at the end of every day of the sprint so far.
+pub fn burndown(
+    sprint: &Sprint,
+    items: &[SprintItem],
+    today: NaiveDate,
+) -> Vec<(NaiveDate, usize)> {
+    let last = today.min(sprint.ends_on);
+    sprint
+        .started_on
+        .iter_days()
+        .take_while(|day| *day <= last)
+        .map(|day| {
+            let open = items
+                .iter()
+                .filter(|item| item.added_on <= day && item.done_on.is_none_or(|done| done > day))
+                .count();
+            (day, open)
+        })
+        .collect()
+}
+
+fn burndown_chart(points: &[(NaiveDate, usize)]) -> Vec<String> {
+    let max = points.iter().map(|(_, open)| *open).max().unwrap_or(0);
+    if max == 0 {
+        return Vec::new();
+    }
+    points
+        .iter()
+        .map(|(day, open)| {
+            format!(
+                "`{} {:<width$} {}`",
+                day.format("%a %d"),
+                "█".repeat(open * BAR_WIDTH / max),
+                open,
+                width = BAR_WIDTH
+            )
+        })
+        .collect()
+}
+
+fn item_lines(items: &[SprintItem]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| {
+            let check = if item.done_on.is_some() { "☑" } else { "☐" };
+            format!("{} `#{}` {}", check, item.number, item.title)
+        })
+        .collect()
+}
+
+/// What `!sprint` shows.
+pub fn status_message(
+    sprint: &Sprint,
+    items: &[SprintItem],
+    today: NaiveDate,
+    flavor: &str,
+    prefix: &str,
+) -> String {
+    let done = items.iter().filter(|item| item.done_on.is_some()).count();
+    let mut lines = vec![format!(
+        "🏃 **{}**, {} to {}, {} ({}/{} done)",
+        sprint.name,
+        sprint.started_on.format("%d %b"),
+        sprint.ends_on.format("%d %b"),
+        days_left(sprint, today),
+        done,
+        items.len()
+    )];
+
+    if !sprint.goals.is_empty() {
+        lines.push("\n**Goals**".to_string());
+        lines.extend(sprint.goals.iter().map(|goal| format!("🎯 {}", goal)));
+    }
+    if items.is_empty() {
+        lines.push(format!(
+            "\nNo items yet, add some with `{}sprint add <item>`.",
+            prefix
+        ));
+    } else {
+        lines.push("\n**Items**".to_string());
+        lines.extend(item_lines(items));
+    }
+
+    let chart = burndown_chart(&burndown(sprint, items, today));
+    if !chart.is_empty() {
+        lines.push("\n**Burndown**".to_string());
+        lines.extend(chart);
+    }
+    lines.push(format!("\n_{}_", flavor));
+    lines.join("\n")
+}
+
+/// What `!sprint end` posts.
+pub fn retrospective(sprint: &Sprint, items: &[SprintItem], today: NaiveDate) -> String {
+    let (done, open): (Vec<&SprintItem>, Vec<&SprintItem>) =
+        items.iter().partition(|item| item.done_on.is_some());
+    let days = (today - sprint.started_on).num_days() + 1;
+
+    let mut lines = vec![format!(
+        "🏁 **{}** is over: {} of {} items done in {} day(s)",
+        sprint.name,
+        done.len(),
+        items.len(),
+        days
+    )];
+    let late = (today - sprint.ends_on).num_days();
+    if late > 0 {
+        lines.push(format!("It ran {} day(s) past its end date.", late));
+    }
+    if !sprint.goals.is_empty() {
+        lines.push("\n**Goals**".to_string());
+        lines.extend(sprint.goals.iter().map(|goal| format!("🎯 {}", goal)));
+    }
+    if !done.is_empty() {
+        lines.push("\n**Done** ✅".to_string());
+        lines.extend(
+            done.iter()
+                .map(|item| format!("`#{}` {}", item.number, item.title)),
+        );
+    }
+    if !open.is_empty() {
+        lines.push("\n**Carried over** 📦".to_string());
+        lines.extend(
+            open.iter()
+                .map(|item| format!("`#{}` {}", item.number, item.title)),
+        );
+    }
+    lines.push("\nWhat went well? What didn't? What do we change? 🤔".to_string());
+    lines.join("\n")
+}
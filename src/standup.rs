@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use serenity::model::id::{ChannelId, UserId};
+
+use crate::commands;
+
+pub const USAGE: &str = "Usage: `!standup [team] <yesterday> | <today> | <blockers>`";
+
+/// Separates the answers in `!standup`.
+const ANSWER_SEPARATOR: char = '|';
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct StandupConfig {
+    pub teams: Vec<TeamConfig>,
+}
+
+impl StandupConfig {
+    pub fn team(&self, name: &str) -> Option<&TeamConfig> {
+        self.teams
+            .iter()
+            .find(|team| team.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// How a team is asked for its standup.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Delivery {
+    /// Every member gets the questions one by one in their DMs and answers there.
+    #[default]
+    Dm,
+    /// The members are pinged in the team channel and answer with `!standup`.
+    Channel,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TeamConfig {
+    #[serde(default)]
+    pub name: String,
+    /// Where the questions (for `channel` delivery) and the summary are posted.
+    pub channel: ChannelId,
+    #[serde(default)]
+    pub members: Vec<UserId>,
+    #[serde(default = "default_questions")]
+    pub questions: Vec<String>,
+    /// When the standup starts, `HH:MM` in `timezone`.
+    #[serde(default = "default_time")]
+    pub time: String,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Days with a standup, `mon` to `sun`.
+    #[serde(default = "default_days")]
+    pub days: Vec<String>,
+    /// How long answers are collected before the summary goes out.
+    #[serde(default = "default_window_mins")]
+    pub window_mins: u64,
+    #[serde(default)]
+    pub delivery: Delivery,
+}
+
+fn strings(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_questions() -> Vec<String> {
+    strings(&[
+        "What did you do yesterday?",
+        "What are you doing today?",
+        "Anything blocking you?",
+    ])
+}
+
+fn default_time() -> String {
+    "09:30".to_string()
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+fn default_days() -> Vec<String> {
+    strings(&["mon", "tue", "wed", "thu", "fri"])
+}
+
+fn default_window_mins() -> u64 {
+    120
+}
+
+impl TeamConfig {
+    pub fn tz(&self) -> Option<Tz> {
+        self.timezone.parse().ok()
+    }
+
+    pub fn start_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(&self.time, "%H:%M").ok()
+    }
+
+    /// Why this team can't be scheduled, if it can't.
+    pub fn problem(&self) -> Option<String> {
+        if self.tz().is_none() {
+            return Some(format!("unknown timezone `{}`", self.timezone));
+        }
+        if self.start_time().is_none() {
+            return Some(format!("`{}` isn't an HH:MM time", self.time));
+        }
+        if self.questions.is_empty() {
+            return Some("no questions".to_string());
+        }
+        None
+    }
+
+    pub fn is_member(&self, user: UserId) -> bool {
+        self.members.contains(&user)
+    }
+
+    fn runs_on(&self, weekday: Weekday) -> bool {
+        self.days
+            .iter()
+            .any(|day| day.parse::<Weekday>().is_ok_and(|day| day == weekday))
+    }
+
+    /// When the standup of `day` starts.
+    pub fn starts_at(&self, day: NaiveDate) -> Option<DateTime<Utc>> {
+        let local = self
+            .tz()?
+            .from_local_datetime(&day.and_time(self.start_time()?))
+            .earliest()?;
+        Some(local.with_timezone(&Utc))
+    }
+
+    /// When answers for the standup of `day` stop being collected.
+    pub fn closes_at(&self, day: NaiveDate) -> Option<DateTime<Utc>> {
+        Some(self.starts_at(day)? + Duration::minutes(self.window_mins as i64))
+    }
+
+    /// The day whose standup should be running at `now`, if any. A bot that
+    /// was down at the start still opens the round while it is collecting.
+    /// Teams with a [`problem`](Self::problem) never have one.
+    pub fn due_round(&self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        if self.problem().is_some() {
+            return None;
+        }
+        let today = now.with_timezone(&self.tz()?).date_naive();
+        (self.runs_on(today.weekday())
+            && self.starts_at(today)? <= now
+            && now < self.closes_at(today)?)
+        .then_some(today)
+    }
+}
+
+/// Answers to one round, per member, in question order.
+pub type Answers = HashMap<UserId, Vec<String>>;
+
+/// Split `!standup` arguments into answers.
+pub fn parse_answers(args: &str) -> Vec<String> {
+    args.split(ANSWER_SEPARATOR)
+        .map(str::trim)
+        .filter(|answer| !answer.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A DM asking question `index`.
+pub fn question_message(team: &TeamConfig, index: usize) -> String {
+    let question = &team.questions[index];
+    if index == 0 {
+        format!(
+            "☀️ Standup time for **{}**! ({}/{})\n{}",
+            team.name,
+            index + 1,
+            team.questions.len(),
+            question
+        )
+    } else {
+        format!("({}/{}) {}", index + 1, team.questions.len(), question)
+    }
+}
+
+/// The in-channel call for `channel` delivery.
+pub fn channel_message(team: &TeamConfig, prefix: &str) -> String {
+    let mentions: Vec<String> = team.members.iter().map(|m| format!("<@{}>", m)).collect();
+    let questions: Vec<String> = team
+        .questions
+        .iter()
+        .enumerate()
+        .map(|(i, q)| format!("{}. {}", i + 1, q))
+        .collect();
+    format!(
+        "☀️ Standup time for **{}**! {}\n{}\nAnswer with `{}standup {}` and separate the answers with `{}`.",
+        team.name,
+        mentions.join(" "),
+        questions.join("\n"),
+        prefix,
+        team.name,
+        ANSWER_SEPARATOR
+    )
+}
+
+pub fn thanks_message(team: &TeamConfig) -> String {
+    format!("ধন্যবাদ! Your **{}** standup is in. ✅", team.name)
+}
+
+/// The compiled standup posted once the round closes.
+pub fn summary(team: &TeamConfig, day: NaiveDate, answers: &Answers) -> String {
+    let mut lines = vec![format!(
+        "📋 **{} standup**, {}",
+        team.name,
+        day.format("%a %d %b")
+    )];
+
+    for member in &team.members {
+        let Some(given) = answers.get(member).filter(|given| !given.is_empty()) else {
+            continue;
+        };
+        lines.push(format!("\n**<@{}>**", member));
+        for (question, answer) in team.questions.iter().zip(given) {
+            lines.push(format!("> _{}_\n{}", question, answer));
+        }
+    }
+
+    let missing: Vec<String> = team
+        .members
+        .iter()
+        .filter(|member| answers.get(member).is_none_or(|given| given.is_empty()))
+        .map(|member| format!("<@{}>", member))
+        .collect();
+    if missing.len() == team.members.len() {
+        lines.push("\nNobody answered. 🦗".to_string());
+    } else if !missing.is_empty() {
+        let warning = commands::simple_reply("talha")
+            .map(|reply| format!(" _{}_", reply.text.bn))
+            .unwrap_or_default();
+        lines.push(format!(
+            "\n🙈 No update from {}.{}",
+            missing.join(", "),
+            warning
+        ));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,174 @@
+use serenity::model::id::{ChannelId, GuildId, UserId};
+
+use crate::output::{COLOR_INFO, Reply};
+use crate::storage::{Storage, StorageError};
+
+/// How many entries the leaderboards show.
+const LEADERBOARD_SIZE: usize = 5;
+
+/// One dispatched command, as recorded for `!stats`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandUsage {
+    pub command: String,
+    pub user: UserId,
+    pub guild: Option<GuildId>,
+    pub channel: ChannelId,
+    /// Unix timestamp in seconds.
+    pub used_at: i64,
+    pub success: bool,
+}
+
+/// The time window stats are counted over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    AllTime,
+}
+
+impl Period {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "day" | "today" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "all" | "alltime" | "all-time" => Some(Self::AllTime),
+            _ => None,
+        }
+    }
+
+    /// The first timestamp included in the period, relative to `now`.
+    pub fn since(&self, now: i64) -> i64 {
+        match self {
+            Self::Day => now - 24 * 60 * 60,
+            Self::Week => now - 7 * 24 * 60 * 60,
+            Self::AllTime => 0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Day => "last 24 hours",
+            Self::Week => "last 7 days",
+            Self::AllTime => "all time",
+        }
+    }
+}
+
+/// What `!stats` was asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsQuery {
+    /// `!stats [day|week|all]`: top commands and users.
+    Leaderboard(Period),
+    /// `!stats me [day|week|all]`: the caller's own usage.
+    Me(Period),
+    /// `!stats <command>`: one command over every period.
+    Command(String),
+}
+
+impl StatsQuery {
+    /// Parse the arguments of `!stats`. `command_name` resolves a word to the
+    /// command it names, which tells command names apart from typos.
+    pub fn parse(args: &str, command_name: impl Fn(&str) -> Option<&'static str>) -> Option<Self> {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        match parts.as_slice() {
+            [] => Some(Self::Leaderboard(Period::AllTime)),
+            ["me"] => Some(Self::Me(Period::AllTime)),
+            ["me", period] => Period::parse(period).map(Self::Me),
+            [word] => Period::parse(word)
+                .map(Self::Leaderboard)
+                .or_else(|| command_name(word).map(|name| Self::Command(name.to_string()))),
+            _ => None,
+        }
+    }
+}
+
+/// Build the `!stats` reply for a guild (or DMs when `guild` is `None`).
+pub fn report(
+    storage: &Storage,
+    guild: Option<GuildId>,
+    caller: UserId,
+    query: &StatsQuery,
+    now: i64,
+) -> Result<Reply, StorageError> {
+    match query {
+        StatsQuery::Leaderboard(period) => {
+            let since = period.since(now);
+            let commands = storage.command_counts(guild, since, None, LEADERBOARD_SIZE)?;
+            let users = storage.user_counts(guild, since, LEADERBOARD_SIZE)?;
+
+            if commands.is_empty() {
+                return Ok(Reply::text(format!(
+                    "📊 No commands used ({}). Boring. 🥱",
+                    period.label()
+                )));
+            }
+
+            Ok(Reply::default()
+                .title(format!("📊 Command stats ({})", period.label()))
+                .color(COLOR_INFO)
+                .field(
+                    "Top commands",
+                    ranked(commands.iter().map(|(c, n)| (format!("`{}`", c), *n))),
+                    false,
+                )
+                .field(
+                    "Top users",
+                    ranked(users.iter().map(|(u, n)| (format!("<@{}>", u), *n))),
+                    false,
+                )
+                .timestamp(now))
+        }
+        StatsQuery::Me(period) => {
+            let since = period.since(now);
+            let commands = storage.command_counts(guild, since, Some(caller), usize::MAX)?;
+
+            if commands.is_empty() {
+                return Ok(Reply::text(format!(
+                    "📊 You haven't used any commands ({}). Shy? 🙈",
+                    period.label()
+                )));
+            }
+
+            let total: u64 = commands.iter().map(|(_, n)| n).sum();
+            Ok(Reply::default()
+                .title(format!("📊 Your stats ({})", period.label()))
+                .description(format!(
+                    "{} commands\n{}",
+                    total,
+                    ranked(commands.iter().map(|(c, n)| (format!("`{}`", c), *n)))
+                ))
+                .color(COLOR_INFO)
+                .timestamp(now))
+        }
+        StatsQuery::Command(command) => {
+            let mut reply = Reply::default()
+                .title(format!("📊 `{}`", command))
+                .color(COLOR_INFO)
+                .timestamp(now);
+            for period in [Period::Day, Period::Week, Period::AllTime] {
+                let usage = storage.command_usage_count(guild, period.since(now), command)?;
+                reply = reply.field(
+                    period.label(),
+                    format!("{} ({} failed)", usage.total, usage.failed),
+                    true,
+                );
+            }
+            Ok(reply)
+        }
+    }
+}
+
+/// How often a single command was used, and how many of those failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageCount {
+    pub total: u64,
+    pub failed: u64,
+}
+
+fn ranked(entries: impl Iterator<Item = (String, u64)>) -> String {
+    entries
+        .enumerate()
+        .map(|(i, (name, count))| format!("{}. {} — {}", i + 1, name, count))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
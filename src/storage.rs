@@ -0,0 +1,925 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, params};
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+use thiserror::Error;
+use tracing::info;
+
+use crate::guild_settings::GuildSettings;
+use crate::karma::KarmaTarget;
+use crate::poll::Poll;
+use crate::reminders::Reminder;
+use crate::sprint::{Sprint, SprintItem};
+use crate::standup::Answers;
+use crate::stats::{CommandUsage, UsageCount};
+use crate::updates::StatusUpdate;
+
+/// Path that opens a throwaway in-memory database instead of a file.
+pub const IN_MEMORY: &str = ":memory:";
+
+/// Schema migrations, applied in order. The database's `user_version` records
+/// how many have run, so only append to this list and never edit an entry.
+const MIGRATIONS: &[&str] = &[
+    // 1: per guild settings, stored as JSON so new settings don't need a migration
+    "CREATE TABLE guild_settings (
+        guild_id INTEGER PRIMARY KEY,
+        settings TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );",
+    // 2: every dispatched command, for !stats
+    "CREATE TABLE command_usage (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        command TEXT NOT NULL,
+        user_id INTEGER NOT NULL,
+        guild_id INTEGER,
+        channel_id INTEGER NOT NULL,
+        used_at INTEGER NOT NULL,
+        success INTEGER NOT NULL
+    );
+    CREATE INDEX command_usage_guild_time ON command_usage (guild_id, used_at);",
+    // 3: reminders and the timezone each user reads them in
+    "CREATE TABLE reminders (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        guild_id INTEGER,
+        channel_id INTEGER NOT NULL,
+        author_id INTEGER NOT NULL,
+        target_id INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        due_at INTEGER NOT NULL,
+        dm INTEGER NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX reminders_due ON reminders (due_at);
+    CREATE TABLE user_timezones (
+        user_id INTEGER PRIMARY KEY,
+        timezone TEXT NOT NULL
+    );",
+    // 4: standup rounds and the answers collected for them
+    "CREATE TABLE standup_rounds (
+        team TEXT NOT NULL,
+        day TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        closed INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (team, day)
+    );
+    CREATE TABLE standup_answers (
+        team TEXT NOT NULL,
+        day TEXT NOT NULL,
+        user_id INTEGER NOT NULL,
+        question INTEGER NOT NULL,
+        answer TEXT NOT NULL,
+        answered_at INTEGER NOT NULL,
+        PRIMARY KEY (team, day, user_id, question)
+    );",
+    // 5: polls and their votes
+    "CREATE TABLE polls (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        guild_id INTEGER,
+        channel_id INTEGER NOT NULL,
+        message_id INTEGER NOT NULL UNIQUE,
+        author_id INTEGER NOT NULL,
+        question TEXT NOT NULL,
+        options TEXT NOT NULL,
+        multi INTEGER NOT NULL,
+        closes_at INTEGER NOT NULL,
+        closed INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX polls_closes_at ON polls (closed, closes_at);
+    CREATE TABLE poll_votes (
+        poll_id INTEGER NOT NULL REFERENCES polls (id),
+        user_id INTEGER NOT NULL,
+        option INTEGER NOT NULL,
+        voted_at INTEGER NOT NULL,
+        PRIMARY KEY (poll_id, user_id, option)
+    );",
+    // 6: karma per guild, keyed by `user:<id>` or `thing:<name>`
+    "CREATE TABLE karma (
+        guild_id INTEGER NOT NULL,
+        target TEXT NOT NULL,
+        score INTEGER NOT NULL,
+        PRIMARY KEY (guild_id, target)
+    );",
+    // 7: status updates posted with !update
+    "CREATE TABLE status_updates (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        guild_id INTEGER,
+        user_id INTEGER NOT NULL,
+        user_name TEXT NOT NULL,
+        text TEXT NOT NULL,
+        posted_at INTEGER NOT NULL
+    );
+    CREATE INDEX status_updates_guild_time ON status_updates (guild_id, posted_at);",
+    // 8: sprints, at most one running per channel, and their items
+    "CREATE TABLE sprints (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        channel_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        started_on TEXT NOT NULL,
+        ends_on TEXT NOT NULL,
+        goals TEXT NOT NULL,
+        ended_at INTEGER
+    );
+    CREATE UNIQUE INDEX sprints_running ON sprints (channel_id) WHERE ended_at IS NULL;
+    CREATE TABLE sprint_items (
+        sprint_id INTEGER NOT NULL REFERENCES sprints (id),
+        number INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        added_on TEXT NOT NULL,
+        done_on TEXT,
+        PRIMARY KEY (sprint_id, number)
+    );",
+];
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Failed to (de)serialize stored data: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Stored date `{0}` isn't YYYY-MM-DD")]
+    BadDate(String),
+    #[error("Storage task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+    #[error("Failed to import old data: {0}")]
+    Import(#[from] std::io::Error),
+}
+
+/// SQLite backed storage for everything the bot needs to remember across restarts.
+pub struct Storage {
+    conn: Mutex<Connection>,
+}
+
+impl Storage {
+    /// Open (or create) the database at `path` and bring its schema up to date.
+    /// A path of `:memory:` gives an in-memory database.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = path.as_ref();
+        let conn = if path == Path::new(IN_MEMORY) {
+            Connection::open_in_memory()?
+        } else {
+            Connection::open(path)?
+        };
+        info!(path = %path.display(), "Opened database");
+        Self::from_connection(conn)
+    }
+
+    /// A fresh in-memory database, handy for tests.
+    pub fn in_memory() -> Result<Self, StorageError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, StorageError> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Run `query` on a blocking thread. SQLite calls block, so async code
+    /// goes through here instead of stalling the runtime's workers.
+    pub async fn run<T, F>(self: &Arc<Self>, query: F) -> Result<T, StorageError>
+    where
+        F: FnOnce(&Storage) -> Result<T, StorageError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let storage = Arc::clone(self);
+        tokio::task::spawn_blocking(move || query(&storage)).await?
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        // a panic while holding the lock can't leave SQLite in a bad state,
+        // so a poisoned lock is still safe to use
+        self.conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The schema version the database is at.
+    pub fn schema_version(&self) -> Result<usize, StorageError> {
+        let version: i64 = self
+            .conn()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        Ok(version as usize)
+    }
+
+    pub fn guild_settings(&self, guild: GuildId) -> Result<Option<GuildSettings>, StorageError> {
+        let json: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT settings FROM guild_settings WHERE guild_id = ?1",
+                params![guild.get() as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(json.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    pub fn all_guild_settings(&self) -> Result<HashMap<GuildId, GuildSettings>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT guild_id, settings FROM guild_settings")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut all = HashMap::new();
+        for row in rows {
+            let (guild_id, json) = row?;
+            all.insert(GuildId::new(guild_id as u64), serde_json::from_str(&json)?);
+        }
+        Ok(all)
+    }
+
+    pub fn save_guild_settings(
+        &self,
+        guild: GuildId,
+        settings: &GuildSettings,
+    ) -> Result<(), StorageError> {
+        let json = serde_json::to_string(settings)?;
+        self.conn().execute(
+            "INSERT INTO guild_settings (guild_id, settings, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(guild_id) DO UPDATE SET settings = excluded.settings, updated_at = excluded.updated_at",
+            params![guild.get() as i64, json, unix_now()],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_command_usage(&self, usage: &CommandUsage) -> Result<(), StorageError> {
+        self.conn().execute(
+            "INSERT INTO command_usage (command, user_id, guild_id, channel_id, used_at, success)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                usage.command,
+                usage.user.get() as i64,
+                usage.guild.map(|g| g.get() as i64),
+                usage.channel.get() as i64,
+                usage.used_at,
+                usage.success
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most used commands in a guild since `since`, optionally for a single user.
+    pub fn command_counts(
+        &self,
+        guild: Option<GuildId>,
+        since: i64,
+        user: Option<UserId>,
+        limit: usize,
+    ) -> Result<Vec<(String, u64)>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT command, COUNT(*) AS uses FROM command_usage
+             WHERE guild_id IS ?1 AND used_at >= ?2 AND (?3 IS NULL OR user_id = ?3)
+             GROUP BY command ORDER BY uses DESC, command ASC LIMIT ?4",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                guild.map(|g| g.get() as i64),
+                since,
+                user.map(|u| u.get() as i64),
+                limit.min(i64::MAX as usize) as i64
+            ],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64)),
+        )?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Users that ran the most commands in a guild since `since`.
+    pub fn user_counts(
+        &self,
+        guild: Option<GuildId>,
+        since: i64,
+        limit: usize,
+    ) -> Result<Vec<(UserId, u64)>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT user_id, COUNT(*) AS uses FROM command_usage
+             WHERE guild_id IS ?1 AND used_at >= ?2
+             GROUP BY user_id ORDER BY uses DESC, user_id ASC LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![guild.map(|g| g.get() as i64), since, limit as i64],
+            |row| {
+                Ok((
+                    UserId::new(row.get::<_, i64>(0)? as u64),
+                    row.get::<_, i64>(1)? as u64,
+                ))
+            },
+        )?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// How often one command was used in a guild since `since`.
+    pub fn command_usage_count(
+        &self,
+        guild: Option<GuildId>,
+        since: i64,
+        command: &str,
+    ) -> Result<UsageCount, StorageError> {
+        let (total, failed): (i64, i64) = self.conn().query_row(
+            "SELECT COUNT(*), COALESCE(SUM(success = 0), 0) FROM command_usage
+             WHERE guild_id IS ?1 AND used_at >= ?2 AND command = ?3",
+            params![guild.map(|g| g.get() as i64), since, command],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(UsageCount {
+            total: total as u64,
+            failed: failed as u64,
+        })
+    }
+
+    /// Store a reminder and return its id.
+    pub fn add_reminder(&self, reminder: &Reminder) -> Result<i64, StorageError> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO reminders (guild_id, channel_id, author_id, target_id, text, due_at, dm, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                reminder.guild.map(|g| g.get() as i64),
+                reminder.channel.get() as i64,
+                reminder.author.get() as i64,
+                reminder.target.get() as i64,
+                reminder.text,
+                reminder.due_at,
+                reminder.dm,
+                unix_now()
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Pending reminders a user set or is the target of, soonest first.
+    pub fn reminders_for(&self, user: UserId) -> Result<Vec<Reminder>, StorageError> {
+        self.query_reminders(
+            "WHERE author_id = ?1 OR target_id = ?1 ORDER BY due_at",
+            user.get() as i64,
+        )
+    }
+
+    /// Reminders due at or before `now`.
+    pub fn due_reminders(&self, now: i64) -> Result<Vec<Reminder>, StorageError> {
+        self.query_reminders("WHERE due_at <= ?1 ORDER BY due_at", now)
+    }
+
+    fn query_reminders(&self, filter: &str, param: i64) -> Result<Vec<Reminder>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, guild_id, channel_id, author_id, target_id, text, due_at, dm FROM reminders {}",
+            filter
+        ))?;
+        let rows = stmt.query_map(params![param], |row| {
+            Ok(Reminder {
+                id: row.get(0)?,
+                guild: row
+                    .get::<_, Option<i64>>(1)?
+                    .map(|g| GuildId::new(g as u64)),
+                channel: ChannelId::new(row.get::<_, i64>(2)? as u64),
+                author: UserId::new(row.get::<_, i64>(3)? as u64),
+                target: UserId::new(row.get::<_, i64>(4)? as u64),
+                text: row.get(5)?,
+                due_at: row.get(6)?,
+                dm: row.get(7)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Remove a reminder once it went out.
+    pub fn delete_reminder(&self, id: i64) -> Result<(), StorageError> {
+        self.conn()
+            .execute("DELETE FROM reminders WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Cancel a reminder the user set or is the target of. Returns whether
+    /// there was one to cancel.
+    pub fn cancel_reminder(&self, id: i64, user: UserId) -> Result<bool, StorageError> {
+        let deleted = self.conn().execute(
+            "DELETE FROM reminders WHERE id = ?1 AND (author_id = ?2 OR target_id = ?2)",
+            params![id, user.get() as i64],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// How many pending reminders a user has set.
+    pub fn reminder_count(&self, author: UserId) -> Result<usize, StorageError> {
+        let count: i64 = self.conn().query_row(
+            "SELECT COUNT(*) FROM reminders WHERE author_id = ?1",
+            params![author.get() as i64],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    pub fn user_timezone(&self, user: UserId) -> Result<Option<String>, StorageError> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT timezone FROM user_timezones WHERE user_id = ?1",
+                params![user.get() as i64],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_user_timezone(&self, user: UserId, timezone: &str) -> Result<(), StorageError> {
+        self.conn().execute(
+            "INSERT INTO user_timezones (user_id, timezone) VALUES (?1, ?2)
+             ON CONFLICT(user_id) DO UPDATE SET timezone = excluded.timezone",
+            params![user.get() as i64, timezone],
+        )?;
+        Ok(())
+    }
+
+    /// Open the standup round of `team` for `day`. Returns false when it was
+    /// opened before, so a round is only started once.
+    pub fn start_standup(&self, team: &str, day: NaiveDate) -> Result<bool, StorageError> {
+        let inserted = self.conn().execute(
+            "INSERT OR IGNORE INTO standup_rounds (team, day, started_at) VALUES (?1, ?2, ?3)",
+            params![team, day.to_string(), unix_now()],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Rounds still collecting answers, newest first.
+    pub fn open_standups(&self) -> Result<Vec<(String, NaiveDate)>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT team, day FROM standup_rounds WHERE closed = 0 ORDER BY started_at DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut rounds = Vec::new();
+        for row in rows {
+            let (team, day) = row?;
+            if let Ok(day) = day.parse() {
+                rounds.push((team, day));
+            }
+        }
+        Ok(rounds)
+    }
+
+    pub fn close_standup(&self, team: &str, day: NaiveDate) -> Result<(), StorageError> {
+        self.conn().execute(
+            "UPDATE standup_rounds SET closed = 1 WHERE team = ?1 AND day = ?2",
+            params![team, day.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Store the answer to question `question`, replacing an earlier one.
+    pub fn add_standup_answer(
+        &self,
+        team: &str,
+        day: NaiveDate,
+        user: UserId,
+        question: usize,
+        answer: &str,
+    ) -> Result<(), StorageError> {
+        self.conn().execute(
+            "INSERT INTO standup_answers (team, day, user_id, question, answer, answered_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(team, day, user_id, question) DO UPDATE
+             SET answer = excluded.answer, answered_at = excluded.answered_at",
+            params![
+                team,
+                day.to_string(),
+                user.get() as i64,
+                question as i64,
+                answer,
+                unix_now()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn standup_answers(&self, team: &str, day: NaiveDate) -> Result<Answers, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT user_id, answer FROM standup_answers
+             WHERE team = ?1 AND day = ?2 ORDER BY user_id, question",
+        )?;
+        let rows = stmt.query_map(params![team, day.to_string()], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut answers = Answers::new();
+        for row in rows {
+            let (user, answer) = row?;
+            answers
+                .entry(UserId::new(user as u64))
+                .or_default()
+                .push(answer);
+        }
+        Ok(answers)
+    }
+
+    /// Store a poll and return its id.
+    pub fn add_poll(&self, poll: &Poll) -> Result<i64, StorageError> {
+        let options = serde_json::to_string(&poll.options)?;
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO polls (guild_id, channel_id, message_id, author_id, question, options, multi, closes_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                poll.guild.map(|g| g.get() as i64),
+                poll.channel.get() as i64,
+                poll.message.get() as i64,
+                poll.author.get() as i64,
+                poll.question,
+                options,
+                poll.multi,
+                poll.closes_at
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// The open poll voted on with reactions to `message`.
+    pub fn open_poll(&self, message: MessageId) -> Result<Option<Poll>, StorageError> {
+        Ok(self
+            .query_polls("WHERE message_id = ?1 AND closed = 0", message.get() as i64)?
+            .pop())
+    }
+
+    /// Open polls that should close at or before `now`.
+    pub fn due_polls(&self, now: i64) -> Result<Vec<Poll>, StorageError> {
+        self.query_polls(
+            "WHERE closed = 0 AND closes_at <= ?1 ORDER BY closes_at",
+            now,
+        )
+    }
+
+    fn query_polls(&self, filter: &str, param: i64) -> Result<Vec<Poll>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, guild_id, channel_id, message_id, author_id, question, options, multi, closes_at
+             FROM polls {}",
+            filter
+        ))?;
+        let rows = stmt.query_map(params![param], |row| {
+            Ok((
+                Poll {
+                    id: row.get(0)?,
+                    guild: row
+                        .get::<_, Option<i64>>(1)?
+                        .map(|g| GuildId::new(g as u64)),
+                    channel: ChannelId::new(row.get::<_, i64>(2)? as u64),
+                    message: MessageId::new(row.get::<_, i64>(3)? as u64),
+                    author: UserId::new(row.get::<_, i64>(4)? as u64),
+                    question: row.get(5)?,
+                    options: Vec::new(),
+                    multi: row.get(7)?,
+                    closes_at: row.get(8)?,
+                },
+                row.get::<_, String>(6)?,
+            ))
+        })?;
+
+        let mut polls = Vec::new();
+        for row in rows {
+            let (mut poll, options) = row?;
+            poll.options = serde_json::from_str(&options)?;
+            polls.push(poll);
+        }
+        Ok(polls)
+    }
+
+    pub fn close_poll(&self, id: i64) -> Result<(), StorageError> {
+        self.conn()
+            .execute("UPDATE polls SET closed = 1 WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Record a vote. Unless `multi`, it replaces the user's earlier vote,
+    /// whose options are returned.
+    pub fn record_vote(
+        &self,
+        poll_id: i64,
+        user: UserId,
+        option: usize,
+        multi: bool,
+    ) -> Result<Vec<usize>, StorageError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let mut replaced = Vec::new();
+        if !multi {
+            let mut stmt = tx.prepare(
+                "SELECT option FROM poll_votes WHERE poll_id = ?1 AND user_id = ?2 AND option != ?3",
+            )?;
+            let rows = stmt
+                .query_map(params![poll_id, user.get() as i64, option as i64], |row| {
+                    row.get::<_, i64>(0)
+                })?;
+            for row in rows {
+                replaced.push(row? as usize);
+            }
+            drop(stmt);
+            tx.execute(
+                "DELETE FROM poll_votes WHERE poll_id = ?1 AND user_id = ?2",
+                params![poll_id, user.get() as i64],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR IGNORE INTO poll_votes (poll_id, user_id, option, voted_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![poll_id, user.get() as i64, option as i64, unix_now()],
+        )?;
+        tx.commit()?;
+        Ok(replaced)
+    }
+
+    pub fn remove_vote(
+        &self,
+        poll_id: i64,
+        user: UserId,
+        option: usize,
+    ) -> Result<(), StorageError> {
+        self.conn().execute(
+            "DELETE FROM poll_votes WHERE poll_id = ?1 AND user_id = ?2 AND option = ?3",
+            params![poll_id, user.get() as i64, option as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Every vote of a poll, one entry per user and option.
+    pub fn poll_votes(&self, poll_id: i64) -> Result<Vec<(UserId, usize)>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare("SELECT user_id, option FROM poll_votes WHERE poll_id = ?1")?;
+        let rows = stmt.query_map(params![poll_id], |row| {
+            Ok((
+                UserId::new(row.get::<_, i64>(0)? as u64),
+                row.get::<_, i64>(1)? as usize,
+            ))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Change a target's karma and return the new score.
+    pub fn add_karma(
+        &self,
+        guild: GuildId,
+        target: &KarmaTarget,
+        delta: i64,
+    ) -> Result<i64, StorageError> {
+        Ok(self.conn().query_row(
+            "INSERT INTO karma (guild_id, target, score) VALUES (?1, ?2, ?3)
+             ON CONFLICT(guild_id, target) DO UPDATE SET score = score + excluded.score
+             RETURNING score",
+            params![guild.get() as i64, target.key(), delta],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn karma(&self, guild: GuildId, target: &KarmaTarget) -> Result<i64, StorageError> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT score FROM karma WHERE guild_id = ?1 AND target = ?2",
+                params![guild.get() as i64, target.key()],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or_default())
+    }
+
+    /// The highest scores in a guild.
+    pub fn karma_top(
+        &self,
+        guild: GuildId,
+        limit: usize,
+    ) -> Result<Vec<(KarmaTarget, i64)>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT target, score FROM karma WHERE guild_id = ?1 ORDER BY score DESC, target LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![guild.get() as i64, limit as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        let mut top = Vec::new();
+        for row in rows {
+            let (key, score) = row?;
+            if let Some(target) = KarmaTarget::from_key(&key) {
+                top.push((target, score));
+            }
+        }
+        Ok(top)
+    }
+
+    /// Store a status update and return its id.
+    pub fn add_update(&self, update: &StatusUpdate) -> Result<i64, StorageError> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO status_updates (guild_id, user_id, user_name, text, posted_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                update.guild.map(|g| g.get() as i64),
+                update.user.get() as i64,
+                update.user_name,
+                update.text,
+                update.posted_at
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Status updates in a guild since `since`, optionally of a single user, oldest first.
+    pub fn updates(
+        &self,
+        guild: Option<GuildId>,
+        since: i64,
+        user: Option<UserId>,
+    ) -> Result<Vec<StatusUpdate>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, guild_id, user_id, user_name, text, posted_at FROM status_updates
+             WHERE guild_id IS ?1 AND posted_at >= ?2 AND (?3 IS NULL OR user_id = ?3)
+             ORDER BY posted_at, id",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                guild.map(|g| g.get() as i64),
+                since,
+                user.map(|u| u.get() as i64)
+            ],
+            |row| {
+                Ok(StatusUpdate {
+                    id: row.get(0)?,
+                    guild: row
+                        .get::<_, Option<i64>>(1)?
+                        .map(|g| GuildId::new(g as u64)),
+                    user: UserId::new(row.get::<_, i64>(2)? as u64),
+                    user_name: row.get(3)?,
+                    text: row.get(4)?,
+                    posted_at: row.get(5)?,
+                })
+            },
+        )?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Start a sprint. Returns `None` when the channel already has one running.
+    pub fn start_sprint(&self, sprint: &Sprint) -> Result<Option<i64>, StorageError> {
+        let goals = serde_json::to_string(&sprint.goals)?;
+        let conn = self.conn();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO sprints (channel_id, name, started_on, ends_on, goals)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                sprint.channel.get() as i64,
+                sprint.name,
+                sprint.started_on.to_string(),
+                sprint.ends_on.to_string(),
+                goals
+            ],
+        )?;
+        Ok((inserted > 0).then(|| conn.last_insert_rowid()))
+    }
+
+    /// The sprint running in a channel.
+    pub fn active_sprint(&self, channel: ChannelId) -> Result<Option<Sprint>, StorageError> {
+        let row = self
+            .conn()
+            .query_row(
+                "SELECT id, name, started_on, ends_on, goals FROM sprints
+                 WHERE channel_id = ?1 AND ended_at IS NULL",
+                params![channel.get() as i64],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        let Some((id, name, started_on, ends_on, goals)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(Sprint {
+            id,
+            channel,
+            name,
+            started_on: parse_day(&started_on)?,
+            ends_on: parse_day(&ends_on)?,
+            goals: serde_json::from_str(&goals)?,
+        }))
+    }
+
+    pub fn set_sprint_goals(&self, id: i64, goals: &[String]) -> Result<(), StorageError> {
+        self.conn().execute(
+            "UPDATE sprints SET goals = ?2 WHERE id = ?1",
+            params![id, serde_json::to_string(goals)?],
+        )?;
+        Ok(())
+    }
+
+    pub fn end_sprint(&self, id: i64) -> Result<(), StorageError> {
+        self.conn().execute(
+            "UPDATE sprints SET ended_at = ?2 WHERE id = ?1",
+            params![id, unix_now()],
+        )?;
+        Ok(())
+    }
+
+    /// Add an item to a sprint and return its number.
+    pub fn add_sprint_item(
+        &self,
+        sprint_id: i64,
+        title: &str,
+        day: NaiveDate,
+    ) -> Result<u32, StorageError> {
+        Ok(self.conn().query_row(
+            "INSERT INTO sprint_items (sprint_id, number, title, added_on)
+             SELECT ?1, COALESCE(MAX(number), 0) + 1, ?2, ?3 FROM sprint_items WHERE sprint_id = ?1
+             RETURNING number",
+            params![sprint_id, title, day.to_string()],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Mark an open item done. Returns whether there was one to mark.
+    pub fn complete_sprint_item(
+        &self,
+        sprint_id: i64,
+        number: u32,
+        day: NaiveDate,
+    ) -> Result<bool, StorageError> {
+        let updated = self.conn().execute(
+            "UPDATE sprint_items SET done_on = ?3
+             WHERE sprint_id = ?1 AND number = ?2 AND done_on IS NULL",
+            params![sprint_id, number, day.to_string()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    pub fn sprint_items(&self, sprint_id: i64) -> Result<Vec<SprintItem>, StorageError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT number, title, added_on, done_on FROM sprint_items
+             WHERE sprint_id = ?1 ORDER BY number",
+        )?;
+        let rows = stmt.query_map(params![sprint_id], |row| {
+            Ok((
+                row.get::<_, u32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?;
+
+        let mut items = Vec::new();
+        for row in rows {
+            let (number, title, added_on, done_on) = row?;
+            items.push(SprintItem {
+                number,
+                title,
+                added_on: parse_day(&added_on)?,
+                done_on: done_on.as_deref().map(parse_day).transpose()?,
+            });
+        }
+        Ok(items)
+    }
+}
+
+/// Days are stored as `YYYY-MM-DD`.
+fn parse_day(day: &str) -> Result<NaiveDate, StorageError> {
+    day.parse()
+        .map_err(|_| StorageError::BadDate(day.to_string()))
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), StorageError> {
+    let current: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = index + 1;
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", version as i64)?;
+        tx.commit()?;
+        info!(version = version, "Applied database migration");
+    }
+
+    Ok(())
+}
+
+/// Seconds since the unix epoch, the format timestamps are stored in.
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
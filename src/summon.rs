@@ -0,0 +1,100 @@
+use serenity::model::id::{RoleId, UserId};
+
+/// Keyword that turns a summon into an escalating one.
+pub const URGENT: &str = "urgent";
+
+/// What `!summon` was asked to do, apart from who to summon.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SummonRequest {
+    pub reason: Option<String>,
+    pub urgent: bool,
+}
+
+impl SummonRequest {
+    /// Parse the arguments of `!summon`. Mentions are skipped since Discord
+    /// already resolved them, `urgent` anywhere asks for escalation and
+    /// whatever is left is the reason.
+    pub fn parse(args: &str) -> Self {
+        let mut urgent = false;
+        let mut words = Vec::new();
+
+        for word in args.split_whitespace() {
+            if is_mention(word) {
+                continue;
+            }
+            if word.eq_ignore_ascii_case(URGENT) {
+                urgent = true;
+                continue;
+            }
+            words.push(word);
+        }
+
+        Self {
+            reason: (!words.is_empty()).then(|| words.join(" ")),
+            urgent,
+        }
+    }
+}
+
+fn is_mention(word: &str) -> bool {
+    word.starts_with("<@") && word.ends_with('>')
+}
+
+/// Mentions for every summoned user and role, in that order.
+pub fn mentions(users: &[UserId], roles: &[RoleId]) -> String {
+    users
+        .iter()
+        .map(|user| format!("<@{}>", user))
+        .chain(roles.iter().map(|role| format!("<@&{}>", role)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The summon itself: who, the emojis and why.
+pub fn summon_message(
+    users: &[UserId],
+    roles: &[RoleId],
+    emojis: &str,
+    request: &SummonRequest,
+) -> String {
+    let mut message = mentions(users, roles);
+    // empty when the emojis went out as reactions instead
+    if !emojis.is_empty() {
+        message.push(' ');
+        message.push_str(emojis);
+    }
+    if let Some(reason) = &request.reason {
+        message.push_str(&format!("\n📣 {}", reason));
+    }
+    if request.urgent {
+        message.push_str("\n🚨 urgent, উত্তর না দিলে আবার ডাকবো।");
+    }
+    message
+}
+
+/// The second ping, for everyone that still hasn't posted.
+pub fn escalation_message(users: &[UserId], roles: &[RoleId], request: &SummonRequest) -> String {
+    let mut message = format!("🚨 {} এখনো কোন খবর নাই! 👀", mentions(users, roles));
+    if let Some(reason) = &request.reason {
+        message.push_str(&format!("\n📣 {}", reason));
+    }
+    message
+}
+
+/// The DM sent to a summoned user that didn't answer.
+pub fn escalation_dm(summoner: &str, link: &str, request: &SummonRequest) -> String {
+    let mut message = format!("🚨 {} urgently summoned you: {}", summoner, link);
+    if let Some(reason) = &request.reason {
+        message.push_str(&format!("\n📣 {}", reason));
+    }
+    message
+}
+
+/// Summoned users that haven't posted since the summon.
+pub fn unanswered(summoned: &[UserId], posted: &[UserId]) -> Vec<UserId> {
+    summoned
+        .iter()
+        .filter(|user| !posted.contains(user))
+        .copied()
+        .collect()
+}
@@ -0,0 +1,236 @@
+use std::env;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use thiserror::Error;
+use tracing::info;
+
+use crate::github::ApiRequest;
+
+/// Environment variable holding the tracker token, it wins over the config.
+pub const TOKEN_ENV_VAR: &str = "TRACKER_TOKEN";
+/// Name of the message context menu action that files a bug.
+pub const FILE_AS_BUG: &str = "File as bug";
+pub const QA_USAGE: &str = "Usage: `!QA <title> | <description>`";
+
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+/// Longest title taken from a message filed as a bug.
+const MAX_TITLE_CHARS: usize = 80;
+
+const LINEAR_CREATE_ISSUE: &str = "mutation IssueCreate($input: IssueCreateInput!) { \
+    issueCreate(input: $input) { success issue { identifier url } } }";
+
+#[derive(Error, Debug)]
+pub enum TrackerError {
+    #[error("No issue tracker configured")]
+    NotConfigured,
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Tracker answered {status}: {body}")]
+    Api { status: u16, body: String },
+    #[error("Tracker didn't send a link back: {0}")]
+    MissingLink(String),
+}
+
+/// Which API issues are filed through.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerKind {
+    /// Linear's GraphQL API.
+    #[default]
+    Linear,
+    /// Any HTTP API taking JSON, described by `[tracker.create]`.
+    Http,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct TrackerConfig {
+    pub kind: TrackerKind,
+    /// API endpoint, Linear's public API when left out.
+    pub api_url: Option<String>,
+    pub token: Option<String>,
+    /// Linear team new issues go to.
+    pub team_id: Option<String>,
+    /// How to create an issue with `kind = "http"`.
+    pub create: HttpTemplate,
+}
+
+/// A generic create-issue request. Every `{title}` and `{description}` in the
+/// body's strings is replaced, the link is read from the response at `link`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HttpTemplate {
+    pub url: Option<String>,
+    pub body: Value,
+    /// JSON pointer to the new issue's link in the response.
+    pub link: String,
+}
+
+impl Default for HttpTemplate {
+    fn default() -> Self {
+        Self {
+            url: None,
+            body: json!({ "title": "{title}", "description": "{description}" }),
+            link: "/url".to_string(),
+        }
+    }
+}
+
+impl TrackerConfig {
+    /// The token from `TRACKER_TOKEN`, or else from the config.
+    pub fn token(&self) -> Option<String> {
+        env::var(TOKEN_ENV_VAR)
+            .ok()
+            .or_else(|| self.token.clone())
+            .filter(|token| !token.is_empty())
+    }
+
+    /// Whether issues can be filed at all.
+    pub fn is_configured(&self) -> bool {
+        match self.kind {
+            TrackerKind::Linear => self.team_id.is_some() && self.token().is_some(),
+            TrackerKind::Http => self.create.url.is_some(),
+        }
+    }
+}
+
+/// An issue about to be filed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewIssue {
+    pub title: String,
+    pub description: String,
+}
+
+impl NewIssue {
+    /// Parse `<title> | <description>`, the description is optional.
+    pub fn parse(args: &str) -> Option<Self> {
+        let (title, description) = args.split_once('|').unwrap_or((args, ""));
+        let title = title.trim();
+        if title.is_empty() {
+            return None;
+        }
+        Some(Self {
+            title: title.to_string(),
+            description: description.trim().to_string(),
+        })
+    }
+
+    /// An issue from a chat message: its first line is the title, the whole
+    /// message the description.
+    pub fn from_message(content: &str) -> Option<Self> {
+        let first_line = content.lines().find(|line| !line.trim().is_empty())?.trim();
+        let mut title: String = first_line.chars().take(MAX_TITLE_CHARS).collect();
+        if first_line.chars().count() > MAX_TITLE_CHARS {
+            title.push('…');
+        }
+        Some(Self {
+            title,
+            description: format!("> {}", content.trim().replace('\n', "\n> ")),
+        })
+    }
+
+    /// Add where the issue came from to the description.
+    pub fn reported_from(mut self, reporter: &str, message_link: &str) -> Self {
+        if !self.description.is_empty() {
+            self.description.push_str("\n\n");
+        }
+        self.description.push_str(&format!(
+            "Reported by {} from Discord: {}",
+            reporter, message_link
+        ));
+        self
+    }
+}
+
+/// Replace the placeholders in every string of a JSON template.
+fn fill(template: &Value, issue: &NewIssue) -> Value {
+    match template {
+        Value::String(text) => Value::String(
+            text.replace("{title}", &issue.title)
+                .replace("{description}", &issue.description),
+        ),
+        Value::Array(values) => Value::Array(values.iter().map(|v| fill(v, issue)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), fill(value, issue)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Build the request that files `issue`.
+pub fn issue_request(config: &TrackerConfig, issue: &NewIssue) -> Result<ApiRequest, TrackerError> {
+    match config.kind {
+        TrackerKind::Linear => {
+            let team_id = config.team_id.as_ref().ok_or(TrackerError::NotConfigured)?;
+            Ok(ApiRequest {
+                url: config
+                    .api_url
+                    .clone()
+                    .unwrap_or_else(|| LINEAR_API_URL.to_string()),
+                body: json!({
+                    "query": LINEAR_CREATE_ISSUE,
+                    "variables": { "input": {
+                        "teamId": team_id,
+                        "title": issue.title,
+                        "description": issue.description,
+                    }},
+                }),
+            })
+        }
+        TrackerKind::Http => Ok(ApiRequest {
+            url: config
+                .create
+                .url
+                .clone()
+                .ok_or(TrackerError::NotConfigured)?,
+            body: fill(&config.create.body, issue),
+        }),
+    }
+}
+
+/// Send a create-issue request and return the new issue's link.
+pub async fn create_issue(
+    config: &TrackerConfig,
+    request: &ApiRequest,
+) -> Result<String, TrackerError> {
+    let mut builder = reqwest::Client::new()
+        .post(&request.url)
+        .json(&request.body);
+    if let Some(token) = config.token() {
+        builder = match config.kind {
+            // Linear API keys go in as they are, without `Bearer`
+            TrackerKind::Linear => builder.header("Authorization", token),
+            TrackerKind::Http => builder.bearer_auth(token),
+        };
+    }
+    let response = builder.send().await?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(TrackerError::Api {
+            status: status.as_u16(),
+            body,
+        });
+    }
+
+    let created: Value = response.json().await?;
+    let pointer = match config.kind {
+        TrackerKind::Linear => "/data/issueCreate/issue/url",
+        TrackerKind::Http => config.create.link.as_str(),
+    };
+    let link = created
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            // GraphQL reports failures with a 200 and an `errors` list
+            let errors = created.get("errors").unwrap_or(&created);
+            TrackerError::MissingLink(errors.to_string())
+        })?;
+    info!(url = %request.url, link = %link, "Filed issue");
+    Ok(link.to_string())
+}
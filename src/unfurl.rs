@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use thiserror::Error;
+use tracing::debug;
+
+const USER_AGENT: &str = "the-bot";
+
+/// Longest issue number taken from `#123`, so snowflakes never match.
+const MAX_NUMBER_DIGITS: usize = 9;
+
+const LINEAR_ISSUE: &str = "query Issue($id: String!) { \
+    issue(id: $id) { identifier title url state { name } assignee { name } } }";
+
+#[derive(Error, Debug)]
+pub enum UnfurlError {
+    #[error("Request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Tracker answered {status}: {body}")]
+    Api { status: u16, body: String },
+}
+
+/// Which API a ticket is looked up in.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    Linear,
+    Jira,
+    GitHub,
+}
+
+/// A tracker tickets are looked up in, and which references point at it.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TicketSource {
+    pub kind: SourceKind,
+    /// Project keys, e.g. `ENG` for `ENG-123`. Linear and Jira only.
+    #[serde(default)]
+    pub keys: Vec<String>,
+    /// `owner/repo` that `#456` points at. GitHub only.
+    pub repo: Option<String>,
+    /// API root, the public one of `kind` when left out. Jira has none, so
+    /// it's required there (`https://yourorg.atlassian.net`).
+    pub api_url: Option<String>,
+    /// Jira account email, the token is sent with it as basic auth when set.
+    pub user: Option<String>,
+    pub token: Option<String>,
+    /// Environment variable to read the token from, wins over `token`.
+    pub token_env: Option<String>,
+}
+
+impl TicketSource {
+    pub fn token(&self) -> Option<String> {
+        self.token_env
+            .as_ref()
+            .and_then(|name| env::var(name).ok())
+            .or_else(|| self.token.clone())
+            .filter(|token| !token.is_empty())
+    }
+
+    fn api_url(&self) -> String {
+        let default = match self.kind {
+            SourceKind::Linear => "https://api.linear.app/graphql",
+            SourceKind::GitHub => "https://api.github.com",
+            SourceKind::Jira => "",
+        };
+        self.api_url
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Whether references like `reference` are looked up here.
+    pub fn handles(&self, reference: &TicketRef) -> bool {
+        match reference {
+            TicketRef::Key(key) => {
+                self.kind != SourceKind::GitHub
+                    && key.split_once('-').is_some_and(|(project, _)| {
+                        self.keys.iter().any(|k| k.eq_ignore_ascii_case(project))
+                    })
+            }
+            TicketRef::Number(_) => self.kind == SourceKind::GitHub && self.repo.is_some(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct UnfurlConfig {
+    /// How long a looked up ticket (or a miss) is remembered.
+    pub cache_secs: u64,
+    /// At most this many tickets are unfurled per message.
+    pub max_per_message: usize,
+    pub sources: Vec<TicketSource>,
+}
+
+impl Default for UnfurlConfig {
+    fn default() -> Self {
+        Self {
+            cache_secs: 600,
+            max_per_message: 3,
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl UnfurlConfig {
+    /// The first source that handles `reference`.
+    pub fn source_for(&self, reference: &TicketRef) -> Option<&TicketSource> {
+        self.sources.iter().find(|source| source.handles(reference))
+    }
+}
+
+/// A ticket mentioned in chat.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TicketRef {
+    /// `ENG-123`, upper cased.
+    Key(String),
+    /// `#456`.
+    Number(u64),
+}
+
+impl fmt::Display for TicketRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(key) => write!(f, "{}", key),
+            Self::Number(number) => write!(f, "#{}", number),
+        }
+    }
+}
+
+impl TicketRef {
+    /// Parse `ENG-123` or `#456`, surrounding punctuation ignored.
+    pub fn parse(word: &str) -> Option<Self> {
+        // `<#123>` is a channel mention, not an issue
+        if word.starts_with('<') {
+            return None;
+        }
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '#' && c != '-');
+        let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+        if let Some(number) = word.strip_prefix('#') {
+            return (is_number(number) && number.len() <= MAX_NUMBER_DIGITS)
+                .then(|| number.parse().ok().map(Self::Number))
+                .flatten();
+        }
+        let (project, number) = word.split_once('-')?;
+        let valid = project
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic())
+            && project.chars().all(|c| c.is_ascii_alphanumeric())
+            && is_number(number);
+        valid.then(|| Self::Key(format!("{}-{}", project.to_uppercase(), number)))
+    }
+}
+
+/// Ticket references in a message that some source handles, each once.
+/// Code between backticks and links are left alone.
+pub fn find_references(content: &str, config: &UnfurlConfig) -> Vec<TicketRef> {
+    let mut found: Vec<TicketRef> = Vec::new();
+    for (index, part) in content.split('`').enumerate() {
+        if index % 2 == 1 {
+            continue;
+        }
+        for word in part.split_whitespace() {
+            if word.contains("://") {
+                continue;
+            }
+            let Some(reference) = TicketRef::parse(word) else {
+                continue;
+            };
+            if config.source_for(&reference).is_some() && !found.contains(&reference) {
+                found.push(reference);
+            }
+        }
+    }
+    found.truncate(config.max_per_message);
+    found
+}
+
+/// What the reply shows about a ticket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ticket {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub assignee: Option<String>,
+    pub url: String,
+}
+
+fn text(value: &Value, pointer: &str) -> Option<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Look a ticket up, `None` when the tracker doesn't know it.
+pub async fn lookup(
+    source: &TicketSource,
+    reference: &TicketRef,
+) -> Result<Option<Ticket>, UnfurlError> {
+    let client = reqwest::Client::new();
+    let api_url = source.api_url();
+    let token = source.token();
+
+    let request = match (source.kind, reference) {
+        (SourceKind::Linear, TicketRef::Key(key)) => {
+            let request = client.post(&api_url).json(&json!({
+                "query": LINEAR_ISSUE,
+                "variables": { "id": key },
+            }));
+            // Linear API keys go in as they are, without `Bearer`
+            match &token {
+                Some(token) => request.header("Authorization", token),
+                None => request,
+            }
+        }
+        (SourceKind::Jira, TicketRef::Key(key)) => {
+            let request = client.get(format!(
+                "{}/rest/api/2/issue/{}?fields=summary,status,assignee",
+                api_url, key
+            ));
+            match (&source.user, &token) {
+                (Some(user), token) => request.basic_auth(user, token.as_ref()),
+                (None, Some(token)) => request.bearer_auth(token),
+                (None, None) => request,
+            }
+        }
+        (SourceKind::GitHub, TicketRef::Number(number)) => {
+            let Some(repo) = &source.repo else {
+                return Ok(None);
+            };
+            let request = client
+                .get(format!("{}/repos/{}/issues/{}", api_url, repo, number))
+                .header("Accept", "application/vnd.github+json")
+                .header("User-Agent", USER_AGENT);
+            match &token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    let response = request.send().await?;
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(UnfurlError::Api {
+            status: status.as_u16(),
+            body,
+        });
+    }
+    let body: Value = response.json().await?;
+
+    let ticket = match source.kind {
+        SourceKind::Linear => body.pointer("/data/issue").and_then(|issue| {
+            Some(Ticket {
+                id: text(issue, "/identifier")?,
+                title: text(issue, "/title")?,
+                status: text(issue, "/state/name").unwrap_or_default(),
+                assignee: text(issue, "/assignee/name"),
+                url: text(issue, "/url")?,
+            })
+        }),
+        SourceKind::Jira => {
+            let key = text(&body, "/key").unwrap_or_else(|| reference.to_string());
+            text(&body, "/fields/summary").map(|title| Ticket {
+                url: format!("{}/browse/{}", api_url, key),
+                id: key,
+                title,
+                status: text(&body, "/fields/status/name").unwrap_or_default(),
+                assignee: text(&body, "/fields/assignee/displayName"),
+            })
+        }
+        SourceKind::GitHub => text(&body, "/title").and_then(|title| {
+            Some(Ticket {
+                id: reference.to_string(),
+                title,
+                status: text(&body, "/state").unwrap_or_default(),
+                assignee: text(&body, "/assignee/login"),
+                url: text(&body, "/html_url")?,
+            })
+        }),
+    };
+    if ticket.is_none() {
+        // GraphQL reports unknown issues with a 200 and an `errors` list
+        debug!(reference = %reference, response = %body, "Ticket not found");
+    }
+    Ok(ticket)
+}
+
+/// Looked up tickets, misses included, so a busy channel doesn't hammer the tracker.
+pub struct TicketCache {
+    ttl: Duration,
+    entries: HashMap<TicketRef, (Instant, Option<Ticket>)>,
+}
+
+impl TicketCache {
+    pub fn new(config: &UnfurlConfig) -> Self {
+        Self {
+            ttl: Duration::from_secs(config.cache_secs),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The cached lookup, `Some(None)` for a cached miss.
+    pub fn get(&self, reference: &TicketRef, now: Instant) -> Option<Option<Ticket>> {
+        self.entries
+            .get(reference)
+            .filter(|(at, _)| now.saturating_duration_since(*at) < self.ttl)
+            .map(|(_, ticket)| ticket.clone())
+    }
+
+    pub fn insert(&mut self, reference: TicketRef, ticket: Option<Ticket>, now: Instant) {
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, (at, _)| now.saturating_duration_since(*at) < ttl);
+        self.entries.insert(reference, (now, ticket));
+    }
+}
+
+/// One compact line per ticket. Links are wrapped in `<>` so Discord doesn't
+/// add an embed for each.
+pub fn unfurl_message(tickets: &[Ticket]) -> String {
+    tickets
+        .iter()
+        .map(|ticket| {
+            let mut parts = vec![format!(
+                "🎫 [{}](<{}>) **{}**",
+                ticket.id, ticket.url, ticket.title
+            )];
+            if !ticket.status.is_empty() {
+                parts.push(ticket.status.clone());
+            }
+            parts.push(
+                ticket
+                    .assignee
+                    .clone()
+                    .unwrap_or_else(|| "unassigned".to_string()),
+            );
+            parts.join(" · ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,215 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use serenity::model::id::{GuildId, UserId};
+
+use crate::reminders;
+
+pub const UPDATE_USAGE: &str = "Usage: `!update <what you're working on>`";
+pub const UPDATES_USAGE: &str =
+    "Usage: `!updates [@user] [today|yesterday|week|7d|2024-01-31]`, `!updates export [since]`";
+
+/// What `!updates` lists when no `since` is given.
+const DEFAULT_SINCE: Since = Since::Days(7);
+
+/// Furthest back a length like `30d` reaches.
+const MAX_DAYS: i64 = 366;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct UpdatesConfig {
+    /// Who `!talha` expects an update from every day.
+    pub members: Vec<UserId>,
+    /// Where "today" starts and ends.
+    pub timezone: String,
+}
+
+impl Default for UpdatesConfig {
+    fn default() -> Self {
+        Self {
+            members: Vec::new(),
+            timezone: "UTC".to_string(),
+        }
+    }
+}
+
+impl UpdatesConfig {
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+}
+
+/// One `!update`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusUpdate {
+    /// Assigned by storage, ignored when adding.
+    pub id: i64,
+    pub guild: Option<GuildId>,
+    pub user: UserId,
+    /// The name at the time of posting, so exports read well outside Discord.
+    pub user_name: String,
+    pub text: String,
+    /// Unix timestamp in seconds.
+    pub posted_at: i64,
+}
+
+/// How far back `!updates` looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Since {
+    Today,
+    Yesterday,
+    /// The last `n` days, today included.
+    Days(i64),
+    Date(NaiveDate),
+}
+
+impl Since {
+    pub fn parse(input: &str) -> Option<Self> {
+        match input.to_lowercase().as_str() {
+            "today" => Some(Self::Today),
+            "yesterday" => Some(Self::Yesterday),
+            "week" => Some(Self::Days(7)),
+            "month" => Some(Self::Days(30)),
+            other => {
+                if let Ok(date) = NaiveDate::parse_from_str(other, "%Y-%m-%d") {
+                    return Some(Self::Date(date));
+                }
+                let days = reminders::parse_compact(other)?.num_days();
+                (days > 0).then_some(Self::Days(days.min(MAX_DAYS)))
+            }
+        }
+    }
+
+    /// The first day included, in `tz`.
+    fn first_day(&self, now: DateTime<Utc>, tz: Tz) -> NaiveDate {
+        let today = now.with_timezone(&tz).date_naive();
+        match self {
+            Self::Today => today,
+            Self::Yesterday => today - Duration::days(1),
+            Self::Days(days) => Duration::try_days(days - 1)
+                .and_then(|back| today.checked_sub_signed(back))
+                .unwrap_or(NaiveDate::MIN),
+            Self::Date(date) => *date,
+        }
+    }
+
+    /// Unix timestamp the period starts at.
+    pub fn timestamp(&self, now: DateTime<Utc>, tz: Tz) -> i64 {
+        start_of(self.first_day(now, tz), tz)
+    }
+}
+
+/// Unix timestamp of midnight at the start of `day` in `tz`.
+pub fn start_of(day: NaiveDate, tz: Tz) -> i64 {
+    tz.from_local_datetime(&day.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|start| start.timestamp())
+        .unwrap_or_else(|| day.and_time(NaiveTime::MIN).and_utc().timestamp())
+}
+
+/// What `!updates` asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdatesQuery {
+    pub user: Option<UserId>,
+    pub since: Since,
+    /// Send the list as a Markdown file.
+    pub export: bool,
+}
+
+impl UpdatesQuery {
+    /// Parse `[export] [@user] [since]`, in any order.
+    pub fn parse(args: &str) -> Option<Self> {
+        let mut query = Self {
+            user: None,
+            since: DEFAULT_SINCE,
+            export: false,
+        };
+        for word in args.split_whitespace() {
+            if word.eq_ignore_ascii_case("export") {
+                query.export = true;
+            } else if let Some(id) = word.strip_prefix("<@").and_then(|id| id.strip_suffix('>')) {
+                let id = id
+                    .trim_start_matches('!')
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|id| *id != 0)?;
+                query.user = Some(UserId::new(id));
+            } else {
+                query.since = Since::parse(word)?;
+            }
+        }
+        Some(query)
+    }
+}
+
+fn local(posted_at: i64, tz: Tz) -> Option<DateTime<Tz>> {
+    Utc.timestamp_opt(posted_at, 0)
+        .single()
+        .map(|at| at.with_timezone(&tz))
+}
+
+/// The `!updates` list.
+pub fn list_message(updates: &[StatusUpdate], tz: Tz) -> String {
+    if updates.is_empty() {
+        return "কোন আপডেট নাই। 🦗".to_string();
+    }
+    let mut lines = vec![format!("**Updates** ({})", tz.name())];
+    for update in updates {
+        let when = local(update.posted_at, tz)
+            .map(|at| at.format("%a %d %b %H:%M").to_string())
+            .unwrap_or_default();
+        lines.push(format!("`{}` <@{}>: {}", when, update.user, update.text));
+    }
+    lines.join("\n")
+}
+
+/// The updates as a Markdown report, a section per day.
+pub fn markdown(updates: &[StatusUpdate], tz: Tz, title: &str) -> String {
+    let mut report = format!("# {}\n", title);
+    let mut current_day = None;
+    for update in updates {
+        let Some(at) = local(update.posted_at, tz) else {
+            continue;
+        };
+        let day = at.date_naive();
+        if current_day != Some(day) {
+            report.push_str(&format!("\n## {}\n\n", day.format("%A, %Y-%m-%d")));
+            current_day = Some(day);
+        }
+        // keep multi line updates inside their bullet
+        let text = update.text.replace('\n', "\n  ");
+        report.push_str(&format!(
+            "- **{}** ({}): {}\n",
+            update.user_name,
+            at.format("%H:%M"),
+            text
+        ));
+    }
+    if current_day.is_none() {
+        report.push_str("\nNo updates.\n");
+    }
+    report
+}
+
+/// Members that haven't posted any of `updates`, in config order.
+pub fn missing(members: &[UserId], updates: &[StatusUpdate]) -> Vec<UserId> {
+    members
+        .iter()
+        .filter(|member| !updates.iter().any(|update| update.user == **member))
+        .copied()
+        .collect()
+}
+
+/// What `!talha` says, given who hasn't posted today and the classic `!talha` line.
+pub fn talha_message(missing: &[UserId], line: &str, prefix: &str) -> String {
+    if missing.is_empty() {
+        return "আজকে সবার আপডেট আছে। 🎉".to_string();
+    }
+    let mentions: Vec<String> = missing.iter().map(|user| format!("<@{}>", user)).collect();
+    format!(
+        "{} no update today. {}\nPost one with `{}update <text>`.",
+        mentions.join(" "),
+        line,
+        prefix
+    )
+}
@@ -0,0 +1,324 @@
+use std::env;
+use std::sync::Arc;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::Value;
+use serenity::model::id::ChannelId;
+use sha2::Sha256;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, info, warn};
+
+use crate::commands;
+use crate::notify::{self, NotifyConfig};
+
+/// Environment variable holding the webhook secret, it wins over the config.
+pub const SECRET_ENV_VAR: &str = "GITHUB_WEBHOOK_SECRET";
+/// Where GitHub deliveries are received.
+pub const GITHUB_PATH: &str = "/github";
+
+const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+const EVENT_HEADER: &str = "x-github-event";
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct WebhookConfig {
+    /// Address to listen on, e.g. `0.0.0.0:8080`. Nothing is started without it.
+    pub bind: Option<String>,
+    /// Secret GitHub signs its deliveries with.
+    pub secret: Option<String>,
+    /// Which channel each repo's events go to; every matching route gets a post.
+    pub routes: Vec<WebhookRoute>,
+    /// The `/notify` endpoint for deploy scripts.
+    pub notify: NotifyConfig,
+}
+
+impl WebhookConfig {
+    /// The secret from `GITHUB_WEBHOOK_SECRET`, or else from the config.
+    pub fn secret(&self) -> Option<String> {
+        env::var(SECRET_ENV_VAR)
+            .ok()
+            .or_else(|| self.secret.clone())
+            .filter(|secret| !secret.is_empty())
+    }
+
+    /// The secrets for every route, from the environment or the config.
+    pub fn secrets(&self) -> ListenerSecrets {
+        ListenerSecrets {
+            github: self.secret(),
+            notify: self.notify.token(),
+        }
+    }
+
+    /// Channels an event of `repo` should be announced in.
+    pub fn channels_for(&self, repo: &str, event: &str) -> Vec<ChannelId> {
+        let mut channels: Vec<ChannelId> = Vec::new();
+        for route in self.routes.iter().filter(|r| r.matches(repo, event)) {
+            if !channels.contains(&route.channel) {
+                channels.push(route.channel);
+            }
+        }
+        channels
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct WebhookRoute {
+    /// `owner/repo` names, empty matches every repo.
+    #[serde(default)]
+    pub repos: Vec<String>,
+    /// GitHub event names (`pull_request`, `check_suite`, `release`), empty
+    /// matches every event.
+    #[serde(default)]
+    pub events: Vec<String>,
+    pub channel: ChannelId,
+}
+
+impl WebhookRoute {
+    fn matches(&self, repo: &str, event: &str) -> bool {
+        (self.repos.is_empty() || self.repos.iter().any(|r| r.eq_ignore_ascii_case(repo)))
+            && (self.events.is_empty() || self.events.iter().any(|e| e == event))
+    }
+}
+
+/// What requests are authenticated with. A route without its secret isn't served.
+#[derive(Debug, Clone, Default)]
+pub struct ListenerSecrets {
+    /// Secret GitHub signs deliveries to `/github` with.
+    pub github: Option<String>,
+    /// Bearer token for `/notify`.
+    pub notify: Option<String>,
+}
+
+impl ListenerSecrets {
+    pub fn is_empty(&self) -> bool {
+        self.github.is_none() && self.notify.is_none()
+    }
+}
+
+/// A message the listener wants posted in Discord.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Post {
+    pub channel: ChannelId,
+    pub content: String,
+}
+
+/// Check GitHub's `X-Hub-Signature-256` header (`sha256=<hex hmac>`) against the body.
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(signature) = signature
+        .strip_prefix("sha256=")
+        .and_then(|hex| hex::decode(hex).ok())
+    else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    // constant time, so the signature can't be guessed byte by byte
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// The signature GitHub would send for `body`, handy for replaying payloads.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// What a GitHub event turns into in Discord.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announcement {
+    pub repo: String,
+    pub content: String,
+}
+
+fn str_at<'a>(payload: &'a Value, pointer: &str) -> &'a str {
+    payload
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .unwrap_or("?")
+}
+
+/// Turn a delivery into an announcement, or `None` for events nobody needs to hear about.
+pub fn announcement(event: &str, payload: &Value) -> Option<Announcement> {
+    let repo = payload
+        .pointer("/repository/full_name")
+        .and_then(Value::as_str)?
+        .to_string();
+    let action = str_at(payload, "/action");
+
+    let content = match (event, action) {
+        ("pull_request", "opened") => format!(
+            "🆕 PR opened in **{}** by {}: **{}**\n{}",
+            repo,
+            str_at(payload, "/pull_request/user/login"),
+            str_at(payload, "/pull_request/title"),
+            str_at(payload, "/pull_request/html_url"),
+        ),
+        ("pull_request", "review_requested") => {
+            let reviewer = payload
+                .pointer("/requested_reviewer/login")
+                .or_else(|| payload.pointer("/requested_team/name"))
+                .and_then(Value::as_str)
+                .unwrap_or("?");
+            format!(
+                "👀 {} wants a review from {} on **{}**: **{}**\n{}",
+                str_at(payload, "/sender/login"),
+                reviewer,
+                repo,
+                str_at(payload, "/pull_request/title"),
+                str_at(payload, "/pull_request/html_url"),
+            )
+        }
+        ("pull_request", "closed")
+            if payload.pointer("/pull_request/merged") == Some(&Value::Bool(true)) =>
+        {
+            let merged_by = payload
+                .pointer("/pull_request/merged_by/login")
+                .and_then(Value::as_str)
+                .unwrap_or_else(|| str_at(payload, "/sender/login"));
+            format!(
+                "🎉 Merged in **{}** by {}: **{}**\n{}",
+                repo,
+                merged_by,
+                str_at(payload, "/pull_request/title"),
+                str_at(payload, "/pull_request/html_url"),
+            )
+        }
+        ("check_suite", "completed") => {
+            let conclusion = str_at(payload, "/check_suite/conclusion");
+            if !matches!(conclusion, "failure" | "timed_out") {
+                return None;
+            }
+            let sha = str_at(payload, "/check_suite/head_sha");
+            let revert = commands::simple_reply("failed")
+                .map(|reply| reply.text.en)
+                .unwrap_or_default();
+            format!(
+                "💥 Checks {} on **{}** `{}` ({})\n{}/commit/{}\n> {} (`!failed`)",
+                conclusion.replace('_', " "),
+                repo,
+                str_at(payload, "/check_suite/head_branch"),
+                sha.get(..7).unwrap_or(sha),
+                str_at(payload, "/repository/html_url"),
+                sha,
+                revert,
+            )
+        }
+        ("release", "published") => format!(
+            "🚀 **{}** {} is out: **{}**\n{}",
+            repo,
+            str_at(payload, "/release/tag_name"),
+            payload
+                .pointer("/release/name")
+                .and_then(Value::as_str)
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| str_at(payload, "/release/tag_name")),
+            str_at(payload, "/release/html_url"),
+        ),
+        _ => return None,
+    };
+
+    Some(Announcement { repo, content })
+}
+
+#[derive(Clone)]
+struct ListenerState {
+    config: Arc<WebhookConfig>,
+    secret: Arc<String>,
+    posts: UnboundedSender<Post>,
+}
+
+/// The listener's routes, for every secret that is set. Announcements are
+/// handed to `posts`, whoever holds the receiving end sends them to Discord.
+pub fn router(
+    config: WebhookConfig,
+    secrets: ListenerSecrets,
+    posts: UnboundedSender<Post>,
+) -> Router {
+    let mut router = Router::new();
+
+    if let Some(token) = secrets.notify {
+        router = router.merge(notify::router(config.notify.clone(), token, posts.clone()));
+    }
+
+    if let Some(secret) = secrets.github {
+        let state = ListenerState {
+            config: Arc::new(config),
+            secret: Arc::new(secret),
+            posts,
+        };
+        router = router.merge(
+            Router::new()
+                .route(GITHUB_PATH, post(github))
+                .with_state(state),
+        );
+    }
+
+    router
+}
+
+/// Serve the webhook routes until the listener fails.
+pub async fn serve(listener: TcpListener, router: Router) -> std::io::Result<()> {
+    info!(address = ?listener.local_addr().ok(), "Webhook listener started");
+    axum::serve(listener, router).await
+}
+
+async fn github(State(state): State<ListenerState>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !verify_signature(&state.secret, &body, signature) {
+        warn!("Rejected webhook delivery with a bad signature");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let Some(event) = headers
+        .get(EVENT_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            warn!(error = ?e, event = %event, "Webhook delivery isn't JSON");
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    let Some(announcement) = announcement(event, &payload) else {
+        debug!(event = %event, "Ignoring webhook event");
+        return StatusCode::NO_CONTENT;
+    };
+
+    let channels = state.config.channels_for(&announcement.repo, event);
+    info!(
+        event = %event,
+        repo = %announcement.repo,
+        channel_count = channels.len(),
+        "Announcing webhook event"
+    );
+    for channel in channels {
+        let post = Post {
+            channel,
+            content: announcement.content.clone(),
+        };
+        if state.posts.send(post).is_err() {
+            warn!("Nobody is posting webhook announcements anymore");
+            return StatusCode::SERVICE_UNAVAILABLE;
+        }
+    }
+    StatusCode::OK
+}
@@ -0,0 +1,49 @@
+use the_bot::approve::{self, ApproveConfig, Style, format_approval};
+use the_bot::config::BotConfig;
+
+#[test]
+fn parses_styles() {
+    assert_eq!(Style::parse("short"), Some(Style::Short));
+    assert_eq!(Style::parse("Enthusiastic"), Some(Style::Enthusiastic));
+    assert_eq!(Style::parse("sarcastic"), Some(Style::Sarcastic));
+    assert_eq!(Style::parse("grumpy"), None);
+}
+
+#[test]
+fn approval_uses_a_phrase_of_the_style() {
+    let config = ApproveConfig::default();
+    for style in [Style::Short, Style::Enthusiastic, Style::Sarcastic] {
+        let message = approve::approval(&config, style);
+        assert!(
+            config
+                .phrases(style)
+                .iter()
+                .any(|p| message.starts_with(p.as_str())),
+            "{} doesn't start with a {:?} phrase",
+            message,
+            style
+        );
+    }
+}
+
+#[test]
+fn default_phrases_include_bangla() {
+    let config = ApproveConfig::default();
+    assert!(
+        config
+            .short
+            .iter()
+            .any(|p| p.chars().any(|c| ('\u{0980}'..='\u{09FF}').contains(&c)))
+    );
+}
+
+#[test]
+fn phrases_are_configurable_per_style() {
+    let config = BotConfig::parse("[approve]\nshort = [\"Yep\"]").unwrap();
+    assert_eq!(config.approve.short, vec!["Yep".to_string()]);
+    assert!(!config.approve.sarcastic.is_empty());
+
+    let message = approve::approval(&config.approve, Style::Short);
+    assert!(message.starts_with("Yep "));
+    assert_eq!(format_approval("LGTM", &[]), "LGTM");
+}
@@ -1,6 +1,6 @@
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use rand::Rng;
 use the_bot::bot::BotState;
@@ -13,8 +13,8 @@ fn make_temp_dir() -> PathBuf {
     dir
 }
 
-fn write_file(path: &PathBuf, name: &str, content: &str) -> PathBuf {
-    let mut file_path = path.clone();
+fn write_file(path: &Path, name: &str, content: &str) -> PathBuf {
+    let mut file_path = path.to_path_buf();
     file_path.push(name);
     let mut f = fs::File::create(&file_path).unwrap();
     f.write_all(content.as_bytes()).unwrap();
@@ -52,3 +52,14 @@ async fn bot_state_new_with_missing_folder_returns_error() {
     // Assert
     assert!(res.is_err(), "Expected error when folder does not exist");
 }
+
+#[tokio::test]
+async fn bot_state_new_uses_an_in_memory_database() {
+    let dir = make_temp_dir();
+    let _f1 = write_file(&dir, "a.dat", "Hello world\n");
+
+    let state = BotState::new(dir.to_str().unwrap()).await.unwrap();
+
+    assert!(state.storage.schema_version().unwrap() > 0);
+    assert!(state.storage.all_guild_settings().unwrap().is_empty());
+}
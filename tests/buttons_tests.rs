@@ -0,0 +1,37 @@
+use serde_json::json;
+use the_bot::buttons::{self, ButtonAction};
+
+#[test]
+fn custom_ids_round_trip() {
+    for action in [
+        ButtonAction::Reveal,
+        ButtonAction::Another("joke"),
+        ButtonAction::Another("oracle"),
+        ButtonAction::Another("guysay"),
+    ] {
+        assert_eq!(ButtonAction::parse(&action.custom_id()), Some(action));
+    }
+    assert_eq!(ButtonAction::parse("another:perm"), None);
+    assert_eq!(ButtonAction::parse("poll:1"), None);
+}
+
+#[test]
+fn jokes_get_a_reveal_button() {
+    let rows = serde_json::to_value(buttons::buttons("joke", true, false)).unwrap();
+    let ids: Vec<&str> = rows[0]["components"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|button| button["custom_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["reveal", "another:joke"]);
+
+    let rows = serde_json::to_value(buttons::buttons("guysay", false, false)).unwrap();
+    assert_eq!(rows[0]["components"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn timed_out_buttons_are_disabled() {
+    let rows = serde_json::to_value(buttons::buttons("oracle", false, true)).unwrap();
+    assert_eq!(rows[0]["components"][0]["disabled"], json!(true));
+}
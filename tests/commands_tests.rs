@@ -0,0 +1,46 @@
+use the_bot::commands::{PREFIX, parse, simple_reply, with_prefix};
+
+#[test]
+fn parse_splits_name_and_args() {
+    let invocation = parse("!health backend dev", PREFIX).unwrap();
+    assert_eq!(invocation.name, "health");
+    assert_eq!(invocation.args, "backend dev");
+}
+
+#[test]
+fn parse_without_args() {
+    let invocation = parse("!oracle", PREFIX).unwrap();
+    assert_eq!(invocation.name, "oracle");
+    assert_eq!(invocation.args, "");
+}
+
+#[test]
+fn parse_requires_exact_command_name() {
+    assert!(parse("!nope", PREFIX).is_none());
+    assert!(parse("!alert", PREFIX).is_none());
+    assert!(parse("!no way", PREFIX).is_some());
+}
+
+#[test]
+fn parse_ignores_messages_without_prefix() {
+    assert!(parse("oracle", PREFIX).is_none());
+    assert!(parse("hello !oracle", PREFIX).is_none());
+}
+
+#[test]
+fn with_prefix_swaps_command_prefixes() {
+    assert_eq!(
+        with_prefix("Usage: `!karma`, `!karma top` (not !this)", "?"),
+        "Usage: `?karma`, `?karma top` (not !this)"
+    );
+    assert_eq!(with_prefix("`!help`", PREFIX), "`!help`");
+}
+
+#[test]
+fn simple_replies_keep_their_original_text() {
+    // য় is written as the single code point U+09DF, as it always was
+    let qa = simple_reply("QA").unwrap().text.bn;
+    assert!(qa.ends_with("দেখতেসি বিষ\u{09DF}টা।"));
+    let jiggu = simple_reply("jiggu").unwrap().text.bn;
+    assert!(jiggu.contains("মুনি\u{09DF}ার মা"));
+}
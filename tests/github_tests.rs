@@ -0,0 +1,162 @@
+use std::fs;
+
+use mockito::{Matcher, Server};
+use rand::Rng;
+use serde_json::json;
+use the_bot::github::{
+    self, GitHubConfig, GitHubError, ReviewKind, TOKEN_ENV_VAR, TOKEN_FILE_ENV_VAR,
+};
+use the_bot::pr_links::PullRequestLink;
+
+fn link() -> PullRequestLink {
+    PullRequestLink::parse("https://github.com/owner/repo/pull/42").unwrap()
+}
+
+#[test]
+fn builds_review_and_comment_requests() {
+    let review = github::review_request(
+        "https://api.github.com/",
+        &link(),
+        "LGTM",
+        ReviewKind::Approve,
+    )
+    .unwrap();
+    assert_eq!(
+        review.url,
+        "https://api.github.com/repos/owner/repo/pulls/42/reviews"
+    );
+    assert_eq!(review.body, json!({ "body": "LGTM", "event": "APPROVE" }));
+
+    let comment = github::review_request(
+        "https://ghe.example.com/api/v3",
+        &link(),
+        "LGTM",
+        ReviewKind::Comment,
+    )
+    .unwrap();
+    assert_eq!(
+        comment.url,
+        "https://ghe.example.com/api/v3/repos/owner/repo/issues/42/comments"
+    );
+}
+
+#[test]
+fn enterprise_links_are_accepted_for_the_configured_host() {
+    assert_eq!(GitHubConfig::default().enterprise_host(), None);
+
+    let config = GitHubConfig {
+        api_url: "https://ghe.example.com/api/v3".to_string(),
+        ..GitHubConfig::default()
+    };
+    let host = config.enterprise_host();
+    assert_eq!(host.as_deref(), Some("ghe.example.com"));
+
+    let url = "https://ghe.example.com/owner/repo/pull/7";
+    assert_eq!(PullRequestLink::parse(url), None);
+    let link = PullRequestLink::parse_on(url, host.as_deref()).unwrap();
+    assert_eq!(link.repo, "owner/repo");
+    assert_eq!(link.number, 7);
+
+    let review =
+        github::review_request(&config.api_url, &link, "LGTM", ReviewKind::Approve).unwrap();
+    assert_eq!(
+        review.url,
+        "https://ghe.example.com/api/v3/repos/owner/repo/pulls/7/reviews"
+    );
+}
+
+#[test]
+fn dry_run_output_shows_the_request() {
+    let request = github::review_request(
+        "https://api.github.com",
+        &link(),
+        "Ship it",
+        ReviewKind::Approve,
+    )
+    .unwrap();
+    let printed = request.to_string();
+    assert!(printed.starts_with("POST https://api.github.com/repos/owner/repo/pulls/42/reviews"));
+    assert!(printed.contains("\"event\": \"APPROVE\""));
+}
+
+#[test]
+fn gitlab_links_are_rejected() {
+    let gitlab =
+        PullRequestLink::parse("https://gitlab.com/group/project/-/merge_requests/1").unwrap();
+    let result = github::review_request(
+        "https://api.github.com",
+        &gitlab,
+        "LGTM",
+        ReviewKind::Approve,
+    );
+    assert!(matches!(result, Err(GitHubError::NotAPullRequest(_))));
+}
+
+#[tokio::test]
+async fn submit_posts_with_the_token() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/repos/owner/repo/pulls/42/reviews")
+        .match_header("authorization", "Bearer secret")
+        .match_body(Matcher::Json(json!({ "body": "LGTM", "event": "APPROVE" })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"html_url":"https://github.com/owner/repo/pull/42#pullrequestreview-1"}"#)
+        .create_async()
+        .await;
+
+    let request =
+        github::review_request(&server.url(), &link(), "LGTM", ReviewKind::Approve).unwrap();
+    let url = github::submit(&request, "secret").await.unwrap();
+
+    assert_eq!(
+        url.as_deref(),
+        Some("https://github.com/owner/repo/pull/42#pullrequestreview-1")
+    );
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn submit_reports_api_errors() {
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/repos/owner/repo/pulls/42/reviews")
+        .with_status(422)
+        .with_body("Can not approve your own pull request")
+        .create_async()
+        .await;
+
+    let request =
+        github::review_request(&server.url(), &link(), "LGTM", ReviewKind::Approve).unwrap();
+    match github::submit(&request, "secret").await {
+        Err(GitHubError::Api { status, body }) => {
+            assert_eq!(status, 422);
+            assert!(body.contains("own pull request"));
+        }
+        other => panic!("expected an API error, got {:?}", other),
+    }
+}
+
+#[test]
+fn token_is_read_from_the_configured_file() {
+    // the only test in this file touching the environment
+    unsafe {
+        std::env::remove_var(TOKEN_ENV_VAR);
+        std::env::remove_var(TOKEN_FILE_ENV_VAR);
+    }
+
+    let path =
+        std::env::temp_dir().join(format!("the_guy_bot_token_{}", rand::rng().random::<u64>()));
+    fs::write(&path, "file-token\n").unwrap();
+
+    let config = GitHubConfig {
+        token_file: Some(path),
+        ..GitHubConfig::default()
+    };
+    assert_eq!(config.token().unwrap(), "file-token");
+
+    assert!(matches!(
+        GitHubConfig::default().token(),
+        Err(GitHubError::MissingToken)
+    ));
+}
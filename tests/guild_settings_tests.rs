@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rand::Rng;
+use serenity::model::id::{ChannelId, GuildId};
+use the_bot::guild_settings::{self, GuildSettings, GuildSettingsStore};
+use the_bot::language::Language;
+use the_bot::storage::Storage;
+
+fn temp_db_path() -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "the_guy_bot_settings_{}",
+        rand::rng().random::<u64>()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    dir.push("the-bot.db");
+    dir
+}
+
+fn categories() -> Vec<String> {
+    vec!["fortunes".to_string(), "riddles".to_string()]
+}
+
+#[test]
+fn set_prefix_and_language() {
+    let mut settings = GuildSettings::default();
+    assert_eq!(settings.prefix, "!");
+
+    settings.apply_command("set prefix ?", &categories());
+    assert_eq!(settings.prefix, "?");
+
+    settings.apply_command("set language en", &categories());
+    assert_eq!(settings.language, Some(Language::English));
+
+    settings.apply_command("set language default", &categories());
+    assert_eq!(settings.language, None);
+
+    // too long prefixes are refused
+    settings.apply_command("set prefix !!!!!!", &categories());
+    assert_eq!(settings.prefix, "?");
+
+    // usage follows the prefix
+    let usage = settings.apply_command("nonsense", &categories());
+    assert!(usage.contains("`?config show`"));
+    assert!(!usage.contains("`!"));
+}
+
+#[test]
+fn enable_and_disable_commands() {
+    let mut settings = GuildSettings::default();
+
+    settings.apply_command("disable joke", &categories());
+    assert!(!settings.is_enabled("joke"));
+    assert!(settings.is_enabled("oracle"));
+
+    settings.apply_command("enable joke", &categories());
+    assert!(settings.is_enabled("joke"));
+
+    settings.apply_command("disable config", &categories());
+    assert!(settings.is_enabled("config"));
+
+    settings.apply_command("disable nonsense", &categories());
+    assert!(settings.disabled_commands.is_empty());
+}
+
+#[test]
+fn quote_category_and_alert_channel() {
+    let mut settings = GuildSettings::default();
+
+    settings.apply_command("set quotes riddles", &categories());
+    assert_eq!(settings.quote_category.as_deref(), Some("riddles"));
+
+    let reply = settings.apply_command("set quotes poems", &categories());
+    assert!(reply.contains("fortunes"));
+    assert_eq!(settings.quote_category.as_deref(), Some("riddles"));
+
+    settings.apply_command("set alert <#55>", &categories());
+    assert_eq!(settings.alert_channel, Some(ChannelId::new(55)));
+
+    settings.apply_command("reset", &categories());
+    assert_eq!(settings, GuildSettings::default());
+}
+
+#[tokio::test]
+async fn store_writes_through_to_storage() {
+    let path = temp_db_path();
+    let guild = GuildId::new(7);
+
+    let mut store = GuildSettingsStore::load(Arc::new(Storage::open(&path).unwrap())).unwrap();
+    assert_eq!(store.get(Some(guild)), GuildSettings::default());
+    store
+        .update(guild, |settings| {
+            settings.apply_command("set prefix ?", &categories())
+        })
+        .await
+        .unwrap();
+
+    let reopened = GuildSettingsStore::load(Arc::new(Storage::open(&path).unwrap())).unwrap();
+    assert_eq!(reopened.get(Some(guild)).prefix, "?");
+    // other guilds and DMs keep the defaults
+    assert_eq!(reopened.get(Some(GuildId::new(8))).prefix, "!");
+    assert_eq!(reopened.get(None).prefix, "!");
+}
+
+#[test]
+fn imports_settings_from_the_old_json_file() {
+    let path = temp_db_path();
+    let json = path.with_file_name("guild_settings.json");
+    fs::write(
+        &json,
+        r#"{"7": {"prefix": "?"}, "8": {"prefix": "$", "language": "bangla"}}"#,
+    )
+    .unwrap();
+
+    let storage = Storage::open(&path).unwrap();
+    let kept = GuildSettings {
+        prefix: ".".to_string(),
+        ..GuildSettings::default()
+    };
+    storage.save_guild_settings(GuildId::new(8), &kept).unwrap();
+
+    // guild 8 already has settings in storage, those win
+    assert_eq!(guild_settings::import_json(&storage, &json).unwrap(), 1);
+    let store = GuildSettingsStore::load(Arc::new(storage)).unwrap();
+    assert_eq!(store.get(Some(GuildId::new(7))).prefix, "?");
+    assert_eq!(store.get(Some(GuildId::new(8))).prefix, ".");
+
+    assert!(!json.exists());
+    assert!(json.with_extension("json.imported").exists());
+    let storage = Storage::open(&path).unwrap();
+    assert_eq!(guild_settings::import_json(&storage, &json).unwrap(), 0);
+}
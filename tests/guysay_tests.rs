@@ -1,4 +1,4 @@
-use the_bot::guysay::say;
+use the_bot::guysay::{say, say_within};
 
 #[test]
 fn guysay_plain_contains_quote() {
@@ -16,4 +16,14 @@ fn guysay_bash_wrapped() {
     assert!(out.contains("Only one"));
     assert!(out.starts_with("```bash"));
     assert!(out.ends_with("```"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn guysay_within_skips_long_quotes() {
+    let quotes = vec!["word ".repeat(500), "Short".to_string()];
+    for _ in 0..10 {
+        let out = say_within(&quotes, true, 2000).unwrap();
+        assert!(out.contains("Short"));
+    }
+    assert_eq!(say_within(&quotes[..1], true, 2000), None);
+}
@@ -1,5 +1,6 @@
-use the_bot::health_checker::check_health;
+use the_bot::health_checker::{check, check_health};
 use mockito::Server;
+use the_bot::output::COLOR_OK;
 
 fn set_env_for(service: &str, env_name: &str, url: &str) {
     let key = format!("{}_{}_URL", service.to_uppercase(), env_name.to_uppercase());
@@ -99,3 +100,27 @@ async fn invalid_format_returns_funny_message() {
     let res = check_health("!health backend".to_string()).await.unwrap();
     assert!(!res.is_empty());
 }
+
+#[tokio::test]
+async fn health_reply_has_fields_for_the_embed() {
+    let mut server = Server::new_async().await;
+    let _m = server
+        .mock("GET", "/health")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{\"status\":\"ok\"}")
+        .create_async()
+        .await;
+    set_env_for("frontend", "staging", &format!("{}/health", server.url()));
+
+    let reply = check("!health frontend staging").await.unwrap();
+    // the text keeps the code block layout, the embed gets fields
+    assert!(reply.text.starts_with("```\n🚀 | Service Healthy"));
+    assert!(!reply.down);
+    let reply = reply.embed.unwrap();
+    assert_eq!(reply.color, Some(COLOR_OK));
+    assert_eq!(reply.footer.as_deref(), Some("FRONTEND_STAGING_URL"));
+    let names: Vec<&str> = reply.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["Environment", "Status", "Latency", "Health Check"]);
+    assert!(reply.fields[2].value.ends_with(" ms"));
+}
@@ -0,0 +1,60 @@
+use the_bot::commands::{COMMANDS, Command, PREFIX, find, parse};
+use the_bot::help::{self, HelpQuery, MESSAGE_LIMIT};
+
+#[test]
+fn every_command_has_a_description() {
+    for command in COMMANDS {
+        assert!(
+            !command.description.is_empty(),
+            "{} has no description",
+            command.name
+        );
+    }
+}
+
+#[test]
+fn aliases_resolve_to_the_command() {
+    assert_eq!(find("abubakr").unwrap().name, "abubakar");
+    assert_eq!(parse("!commands", PREFIX).unwrap().name, "help");
+}
+
+#[test]
+fn parses_help_queries() {
+    assert_eq!(HelpQuery::parse("", PREFIX), HelpQuery::Page(1));
+    assert_eq!(HelpQuery::parse("2", PREFIX), HelpQuery::Page(2));
+    assert_eq!(
+        HelpQuery::parse("!joke", PREFIX),
+        HelpQuery::Command("joke")
+    );
+    assert_eq!(HelpQuery::parse("joke", "?"), HelpQuery::Command("joke"));
+}
+
+#[test]
+fn command_help_lists_usage_and_aliases() {
+    let text = help::command_help(find("abubakar").unwrap(), "?");
+    assert!(text.contains("Usage: `?abubakar`"));
+    assert!(text.contains("Aliases: `?abubakr`"));
+}
+
+#[test]
+fn pages_fit_in_a_message() {
+    let all: Vec<&Command> = COMMANDS.iter().collect();
+    // repeat the list until it can't fit in one message
+    let many: Vec<&Command> = all.iter().cycle().take(all.len() * 10).copied().collect();
+
+    let pages = help::pages(&many, PREFIX);
+
+    assert!(pages.len() > 1);
+    assert!(
+        pages
+            .iter()
+            .all(|page| page.chars().count() <= MESSAGE_LIMIT)
+    );
+    assert!(pages[0].contains("`!help 2` for more"));
+    assert!(
+        pages
+            .last()
+            .unwrap()
+            .contains(&format!("Page {}/{}", pages.len(), pages.len()))
+    );
+}
@@ -0,0 +1,38 @@
+use serenity::model::id::MessageId;
+use the_bot::config::BotConfig;
+use the_bot::jokes::{self, Punchlines};
+use the_bot::output::COLOR_FUN;
+
+#[test]
+fn setup_and_punchline_are_separate_messages() {
+    let setup = jokes::setup_reply("Why dark mode?");
+    assert_eq!(setup.to_text(), "🎭 **Why dark mode?**");
+    assert_eq!(setup.color, Some(COLOR_FUN));
+    assert_eq!(
+        jokes::punchline_reply("Light attracts bugs").to_text(),
+        "💡 _Light attracts bugs_"
+    );
+}
+
+#[test]
+fn punchline_is_handed_out_once() {
+    let mut punchlines = Punchlines::new();
+    let setup = MessageId::new(1);
+    punchlines.hold(setup, "Light attracts bugs");
+
+    assert!(punchlines.is_pending(setup));
+    assert_eq!(
+        punchlines.take(setup).as_deref(),
+        Some("Light attracts bugs")
+    );
+    assert!(!punchlines.is_pending(setup));
+    assert_eq!(punchlines.take(setup), None);
+    assert_eq!(punchlines.take(MessageId::new(2)), None);
+}
+
+#[test]
+fn punchline_delay_is_configurable() {
+    assert_eq!(BotConfig::default().joke.punchline_delay_secs, 5);
+    let config = BotConfig::parse("[joke]\npunchline_delay_secs = 2").unwrap();
+    assert_eq!(config.joke.punchline_delay_secs, 2);
+}
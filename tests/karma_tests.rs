@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+use serenity::model::id::{GuildId, UserId};
+use the_bot::karma::{self, KarmaChange, KarmaConfig, KarmaCooldown, KarmaTarget};
+use the_bot::storage::Storage;
+
+fn user(id: u64) -> KarmaTarget {
+    KarmaTarget::User(UserId::new(id))
+}
+
+fn thing(name: &str) -> KarmaTarget {
+    KarmaTarget::Thing(name.to_string())
+}
+
+#[test]
+fn finds_karma_changes() {
+    assert_eq!(
+        karma::find_changes("thanks <@1> ++ and <@!2>-- for the Biriyani++"),
+        vec![
+            KarmaChange {
+                target: user(1),
+                delta: 1
+            },
+            KarmaChange {
+                target: user(2),
+                delta: -1
+            },
+            KarmaChange {
+                target: thing("biriyani"),
+                delta: 1
+            },
+        ]
+    );
+    // one change per target and message
+    assert_eq!(
+        karma::find_changes("deploys++ deploys++ deploys--").len(),
+        1
+    );
+}
+
+#[test]
+fn ignores_code_dashes_and_single_letters() {
+    assert!(karma::find_changes("I write c++ and `counter++` in loops").is_empty());
+    assert!(karma::find_changes("this -- that ++ other").is_empty());
+    assert!(karma::find_changes("```\nfor (i = 0; i < n; index++)\n```").is_empty());
+    assert!(karma::find_changes("<@&123>++ <#456>++ https://x.io/a++").is_empty());
+}
+
+#[test]
+fn limits_repeated_changes() {
+    let mut cooldown = KarmaCooldown::new(&KarmaConfig { cooldown_secs: 60 });
+    let now = Instant::now();
+    let giver = UserId::new(1);
+
+    assert!(cooldown.allow(giver, &user(2), now));
+    assert!(!cooldown.allow(giver, &user(2), now + Duration::from_secs(30)));
+    assert!(cooldown.allow(giver, &user(3), now + Duration::from_secs(30)));
+    assert!(cooldown.allow(UserId::new(4), &user(2), now + Duration::from_secs(30)));
+    assert!(cooldown.allow(giver, &user(2), now + Duration::from_secs(61)));
+}
+
+#[test]
+fn keeps_scores_per_guild() {
+    let storage = Storage::in_memory().unwrap();
+    let guild = GuildId::new(100);
+
+    assert_eq!(storage.add_karma(guild, &user(1), 1).unwrap(), 1);
+    assert_eq!(storage.add_karma(guild, &user(1), 1).unwrap(), 2);
+    assert_eq!(storage.add_karma(guild, &thing("biriyani"), 1).unwrap(), 1);
+    assert_eq!(storage.add_karma(guild, &thing("deploys"), -1).unwrap(), -1);
+    assert_eq!(
+        storage.add_karma(GuildId::new(200), &user(1), -1).unwrap(),
+        -1
+    );
+
+    assert_eq!(storage.karma(guild, &user(1)).unwrap(), 2);
+    assert_eq!(storage.karma(guild, &user(9)).unwrap(), 0);
+    assert_eq!(
+        storage.karma_top(guild, 2).unwrap(),
+        vec![(user(1), 2), (thing("biriyani"), 1)]
+    );
+
+    let board = karma::leaderboard(&storage.karma_top(guild, 10).unwrap());
+    assert!(board.contains("1. <@1> 2\n2. **biriyani** 1\n3. **deploys** -1"));
+}
+
+#[test]
+fn parses_targets_and_keys() {
+    assert_eq!(KarmaTarget::parse("<@!5>"), Some(user(5)));
+    assert_eq!(KarmaTarget::parse("@Lunch"), Some(thing("lunch")));
+    assert_eq!(KarmaTarget::parse("x"), None);
+    assert_eq!(KarmaTarget::from_key(&user(5).key()), Some(user(5)));
+    assert_eq!(KarmaTarget::from_key(&thing("qa").key()), Some(thing("qa")));
+}
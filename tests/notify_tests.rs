@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use mockito::Server;
+use serde_json::json;
+use serenity::model::id::ChannelId;
+use the_bot::notify::{NOTIFY_PATH, Notification, NotifyConfig};
+use the_bot::webhook::{self, ListenerSecrets, Post, WebhookConfig};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+const TOKEN: &str = "deploy-token";
+
+fn notification(status: &str) -> Notification {
+    Notification {
+        service: "backend".to_string(),
+        environment: "staging".to_string(),
+        version: Some("v1.4.2".to_string()),
+        status: status.to_string(),
+        link: Some("https://ci.example.com/runs/7".to_string()),
+    }
+}
+
+async fn start(notify: NotifyConfig) -> (String, mpsc::UnboundedReceiver<Post>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), NOTIFY_PATH);
+    let (posts, receiver) = mpsc::unbounded_channel();
+    let config = WebhookConfig {
+        notify,
+        ..WebhookConfig::default()
+    };
+    let secrets = ListenerSecrets {
+        github: None,
+        notify: Some(TOKEN.to_string()),
+    };
+    tokio::spawn(webhook::serve(
+        listener,
+        webhook::router(config, secrets, posts),
+    ));
+    (url, receiver)
+}
+
+fn routed_config() -> NotifyConfig {
+    NotifyConfig {
+        channels: HashMap::from([("backend".to_string(), ChannelId::new(1))]),
+        ..NotifyConfig::default()
+    }
+}
+
+async fn send(url: &str, token: &str, body: serde_json::Value) -> u16 {
+    reqwest::Client::new()
+        .post(url)
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
+#[test]
+fn formats_deploy_messages() {
+    assert_eq!(
+        notification("success").message(),
+        "🚀 **backend** `v1.4.2` deployed to **staging**\nhttps://ci.example.com/runs/7"
+    );
+    assert!(
+        notification("failed")
+            .message()
+            .starts_with("💥 **backend** `v1.4.2` failed to deploy to")
+    );
+    assert!(
+        notification("started")
+            .message()
+            .contains("is deploying to **staging**")
+    );
+    assert!(
+        notification("paused")
+            .message()
+            .contains("is `paused` on **staging**")
+    );
+}
+
+#[test]
+fn uses_the_health_check_vocabulary() {
+    assert!(notification("success").validate().is_ok());
+
+    let mut unknown = notification("success");
+    unknown.service = "database".to_string();
+    assert!(
+        unknown
+            .validate()
+            .unwrap_err()
+            .contains("backend, frontend")
+    );
+
+    let mut unknown = notification("success");
+    unknown.environment = "moon".to_string();
+    assert!(unknown.validate().is_err());
+}
+
+#[test]
+fn falls_back_to_the_default_channel() {
+    let config = NotifyConfig {
+        default_channel: Some(ChannelId::new(9)),
+        ..routed_config()
+    };
+    assert_eq!(config.channel_for("backend"), Some(ChannelId::new(1)));
+    assert_eq!(config.channel_for("frontend"), Some(ChannelId::new(9)));
+    assert_eq!(routed_config().channel_for("frontend"), None);
+}
+
+#[tokio::test]
+async fn posts_authenticated_notifications() {
+    let (url, mut posts) = start(routed_config()).await;
+    let body = json!({ "service": "backend", "environment": "prod", "status": "success" });
+
+    assert_eq!(send(&url, "wrong", body.clone()).await, 401);
+    // same length, and a prefix of the real one
+    assert_eq!(send(&url, "deploy-tokem", body.clone()).await, 401);
+    assert_eq!(send(&url, "deploy", body.clone()).await, 401);
+    assert!(posts.try_recv().is_err());
+
+    assert_eq!(send(&url, TOKEN, body).await, 202);
+    let post = posts.recv().await.unwrap();
+    assert_eq!(post.channel, ChannelId::new(1));
+    assert_eq!(post.content, "🚀 **backend** deployed to **prod**");
+
+    let unknown = json!({ "service": "frontend", "environment": "prod", "status": "success" });
+    assert_eq!(send(&url, TOKEN, unknown).await, 422);
+}
+
+#[tokio::test]
+async fn checks_health_after_a_successful_deploy() {
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/health")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{\"status\":\"ok\"}")
+        .create_async()
+        .await;
+    // In Rust 2024 edition, setting env var is unsafe
+    unsafe { std::env::set_var("BACKEND_QA_URL", format!("{}/health", server.url())) };
+
+    let config = NotifyConfig {
+        health_check_delay_secs: Some(0),
+        ..routed_config()
+    };
+    let (url, mut posts) = start(config).await;
+    let body = json!({ "service": "backend", "environment": "qa", "status": "deployed" });
+    assert_eq!(send(&url, TOKEN, body).await, 202);
+
+    let announced = posts.recv().await.unwrap();
+    assert!(announced.content.contains("deployed to **qa**"));
+    let health = posts.recv().await.unwrap();
+    assert!(health.content.contains("Service Healthy"));
+}
@@ -0,0 +1,131 @@
+use serde_json::Value;
+use the_bot::config::BotConfig;
+use the_bot::output::{self, COLOR_INFO, Fitted, OutputConfig, Reply};
+
+fn json(reply: &Reply, embeds: bool) -> Value {
+    serde_json::to_value(reply.to_message(embeds)).unwrap()
+}
+
+fn report() -> Reply {
+    Reply::default()
+        .title("Stats")
+        .description("Last 7 days")
+        .color(COLOR_INFO)
+        .field("joke", "3", true)
+        .field("Top users", "1. <@1> 3", false)
+        .footer("the-bot")
+}
+
+#[test]
+fn text_rendering_keeps_everything() {
+    assert_eq!(
+        report().to_text(),
+        "**Stats**\nLast 7 days\njoke: 3\n**Top users**\n1. <@1> 3\n_the-bot_"
+    );
+    assert_eq!(Reply::text("hello").to_text(), "hello");
+}
+
+#[test]
+fn rich_replies_become_embeds() {
+    let message = json(&report(), true);
+    let embed = &message["embeds"][0];
+    assert_eq!(embed["title"], "Stats");
+    assert_eq!(embed["color"], COLOR_INFO);
+    assert_eq!(embed["fields"].as_array().unwrap().len(), 2);
+    assert!(message.get("content").is_none());
+}
+
+#[test]
+fn plain_text_when_embeds_are_off_or_not_needed() {
+    let message = json(&report(), false);
+    assert!(
+        message["content"]
+            .as_str()
+            .unwrap()
+            .starts_with("**Stats**")
+    );
+    assert!(
+        message
+            .get("embeds")
+            .is_none_or(|e| e.as_array().unwrap().is_empty())
+    );
+
+    let message = json(&Reply::text("hello"), true);
+    assert_eq!(message["content"], "hello");
+}
+
+#[test]
+fn short_content_is_one_message() {
+    assert_eq!(
+        output::fit("hello", 20, &OutputConfig::default()),
+        Fitted::Messages(vec!["hello".to_string()])
+    );
+}
+
+#[test]
+fn splits_on_line_boundaries() {
+    let content = "aaaa\nbbbb\ncccc\ndddd";
+    assert_eq!(
+        output::split_message(content, 10),
+        vec!["aaaa\nbbbb", "cccc\ndddd"]
+    );
+}
+
+#[test]
+fn code_fences_stay_balanced_across_chunks() {
+    let content = format!(
+        "```bash\n{}\n```",
+        ["line one", "line two", "line three"].join("\n")
+    );
+    let chunks = output::split_message(&content, 30);
+
+    assert!(chunks.len() > 1);
+    for chunk in &chunks {
+        assert!(chunk.chars().count() <= 30, "{:?} is too long", chunk);
+        assert!(chunk.starts_with("```bash\n"), "{:?} isn't reopened", chunk);
+        assert!(chunk.ends_with("\n```"), "{:?} isn't closed", chunk);
+    }
+    let lines: Vec<&str> = chunks
+        .iter()
+        .flat_map(|chunk| chunk.lines())
+        .filter(|line| !line.starts_with("```"))
+        .collect();
+    assert_eq!(lines, ["line one", "line two", "line three"]);
+}
+
+#[test]
+fn lines_longer_than_a_message_are_cut() {
+    let content = "ক".repeat(25);
+    let chunks = output::split_message(&content, 10);
+    assert_eq!(chunks.len(), 3);
+    assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 10));
+    assert_eq!(chunks.concat(), content);
+}
+
+#[test]
+fn too_many_chunks_become_an_attachment() {
+    let content = format!("```bash\n{}\n```", "moo\n".repeat(20).trim_end());
+    let config = OutputConfig { max_messages: 2 };
+    match output::fit(&content, 20, &config) {
+        Fitted::Attachment(text) => assert_eq!(text, "moo\n".repeat(20).trim_end()),
+        other => panic!("expected an attachment, got {:?}", other),
+    }
+
+    let config = OutputConfig { max_messages: 100 };
+    assert!(matches!(
+        output::fit(&content, 20, &config),
+        Fitted::Messages(_)
+    ));
+}
+
+#[test]
+fn long_reply_handling_is_configurable() {
+    let config = BotConfig::default();
+    assert_eq!(config.output.max_messages, 3);
+    assert!(!config.guysay.skip_long);
+
+    let config =
+        BotConfig::parse("[output]\nmax_messages = 0\n\n[guysay]\nskip_long = true").unwrap();
+    assert_eq!(config.output.max_messages, 0);
+    assert!(config.guysay.skip_long);
+}
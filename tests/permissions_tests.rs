@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use serenity::model::id::{ChannelId, RoleId, UserId};
+use the_bot::config::{BotConfig, PermissionConfig};
+use the_bot::permissions::{Caller, DenyReason, GuildRules, PermissionRule, Permissions, Target};
+
+const OPS: ChannelId = ChannelId::new(100);
+const ANNOUNCEMENTS: ChannelId = ChannelId::new(200);
+const GENERAL: ChannelId = ChannelId::new(300);
+
+fn caller(channel: ChannelId, roles: &[RoleId]) -> Caller<'_> {
+    Caller {
+        user: UserId::new(42),
+        roles,
+        channel,
+    }
+}
+
+fn sample_permissions() -> Permissions {
+    let mut groups = HashMap::new();
+    groups.insert(
+        "fun".to_string(),
+        vec!["oracle".to_string(), "joke".to_string()],
+    );
+
+    let mut rules = HashMap::new();
+    rules.insert(
+        "health".to_string(),
+        PermissionRule {
+            allow_channels: vec![OPS],
+            ..PermissionRule::default()
+        },
+    );
+    rules.insert(
+        "fun".to_string(),
+        PermissionRule {
+            deny_channels: vec![ANNOUNCEMENTS],
+            ..PermissionRule::default()
+        },
+    );
+
+    Permissions::new(PermissionConfig {
+        admin_roles: vec![RoleId::new(9)],
+        groups,
+        rules,
+        ..PermissionConfig::default()
+    })
+}
+
+#[test]
+fn allow_channels_restrict_where_a_command_runs() {
+    let permissions = sample_permissions();
+
+    assert!(
+        permissions
+            .check(&GuildRules::new(), "health", &caller(OPS, &[]))
+            .is_ok()
+    );
+    let denied = permissions
+        .check(&GuildRules::new(), "health", &caller(GENERAL, &[]))
+        .unwrap_err();
+    assert_eq!(denied.reason, DenyReason::Channel);
+    assert_eq!(denied.rule, "health");
+}
+
+#[test]
+fn group_rules_apply_to_their_commands() {
+    let permissions = sample_permissions();
+
+    let denied = permissions
+        .check(&GuildRules::new(), "joke", &caller(ANNOUNCEMENTS, &[]))
+        .unwrap_err();
+    assert_eq!(denied.rule, "fun");
+    assert!(
+        permissions
+            .check(&GuildRules::new(), "joke", &caller(GENERAL, &[]))
+            .is_ok()
+    );
+    // commands outside the group are untouched
+    assert!(
+        permissions
+            .check(&GuildRules::new(), "sprint", &caller(ANNOUNCEMENTS, &[]))
+            .is_ok()
+    );
+}
+
+#[test]
+fn role_restrictions_and_deny_lists() {
+    let rule = PermissionRule {
+        allow_roles: vec![RoleId::new(5)],
+        deny_users: vec![UserId::new(42)],
+        ..PermissionRule::default()
+    };
+    let mut rules = HashMap::new();
+    rules.insert("PM".to_string(), rule);
+    let permissions = Permissions::new(PermissionConfig {
+        rules,
+        ..PermissionConfig::default()
+    });
+
+    // denied user loses even with the right role
+    let denied = permissions
+        .check(
+            &GuildRules::new(),
+            "PM",
+            &caller(GENERAL, &[RoleId::new(5)]),
+        )
+        .unwrap_err();
+    assert_eq!(denied.reason, DenyReason::Caller);
+
+    let someone_else = Caller {
+        user: UserId::new(7),
+        roles: &[RoleId::new(5)],
+        channel: GENERAL,
+    };
+    assert!(
+        permissions
+            .check(&GuildRules::new(), "PM", &someone_else)
+            .is_ok()
+    );
+
+    let no_role = Caller {
+        user: UserId::new(7),
+        roles: &[],
+        channel: GENERAL,
+    };
+    assert!(
+        permissions
+            .check(&GuildRules::new(), "PM", &no_role)
+            .is_err()
+    );
+}
+
+#[test]
+fn guild_overrides_replace_file_rules() {
+    let permissions = sample_permissions();
+    let mut overrides = GuildRules::new();
+
+    let reply = permissions.apply_command(&mut overrides, "allow health <#300>");
+    assert!(reply.contains("<#100>") && reply.contains("<#300>"));
+    assert!(
+        permissions
+            .check(&overrides, "health", &caller(GENERAL, &[]))
+            .is_ok()
+    );
+
+    // guilds without the override still use the file rule
+    assert!(
+        permissions
+            .check(&GuildRules::new(), "health", &caller(GENERAL, &[]))
+            .is_err()
+    );
+
+    permissions.apply_command(&mut overrides, "reset health");
+    assert!(overrides.is_empty());
+    assert!(
+        permissions
+            .check(&overrides, "health", &caller(GENERAL, &[]))
+            .is_err()
+    );
+}
+
+#[test]
+fn apply_command_rejects_unknown_names_and_bad_targets() {
+    let permissions = sample_permissions();
+    let mut overrides = GuildRules::new();
+
+    assert!(
+        permissions
+            .apply_command(&mut overrides, "allow nonsense <#1>")
+            .contains("nonsense")
+    );
+    assert!(
+        permissions
+            .apply_command(&mut overrides, "deny joke ops")
+            .starts_with("Usage")
+    );
+    assert!(
+        permissions
+            .apply_command(&mut overrides, "")
+            .starts_with("Usage")
+    );
+    assert!(overrides.is_empty());
+}
+
+#[test]
+fn admins_are_recognised_by_role() {
+    let permissions = sample_permissions();
+    assert!(permissions.is_admin(UserId::new(1), &[RoleId::new(9)]));
+    assert!(!permissions.is_admin(UserId::new(1), &[RoleId::new(8)]));
+}
+
+#[test]
+fn rules_cant_lock_admins_out_of_perm_and_config() {
+    let permissions = sample_permissions();
+    let mut overrides = GuildRules::new();
+    permissions.apply_command(&mut overrides, "deny perm <@&9>");
+    permissions.apply_command(&mut overrides, "allow config <#100>");
+
+    let admin_roles = [RoleId::new(9)];
+    let admin = caller(GENERAL, &admin_roles);
+    assert!(permissions.check(&overrides, "perm", &admin).is_ok());
+    assert!(permissions.check(&overrides, "config", &admin).is_ok());
+
+    // everyone else is still held to them
+    assert!(
+        permissions
+            .check(&overrides, "config", &caller(GENERAL, &[]))
+            .is_err()
+    );
+    // and admins to the rules of every other command
+    assert!(permissions.check(&overrides, "health", &admin).is_err());
+}
+
+#[test]
+fn target_parses_discord_mentions() {
+    assert_eq!(
+        Target::parse("<#12>"),
+        Some(Target::Channel(ChannelId::new(12)))
+    );
+    assert_eq!(Target::parse("<@&12>"), Some(Target::Role(RoleId::new(12))));
+    assert_eq!(Target::parse("<@12>"), Some(Target::User(UserId::new(12))));
+    assert_eq!(Target::parse("<@!12>"), Some(Target::User(UserId::new(12))));
+    assert_eq!(Target::parse("#general"), None);
+    assert_eq!(Target::parse("<#0>"), None);
+}
+
+#[test]
+fn config_parses_permission_rules() {
+    let config = BotConfig::parse(
+        r#"
+        [permissions]
+        admin_users = [1]
+
+        [permissions.groups]
+        fun = ["oracle", "joke"]
+
+        [permissions.rules.health]
+        allow_channels = [100]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.permissions.admin_users, vec![UserId::new(1)]);
+    assert_eq!(config.permissions.groups["fun"].len(), 2);
+    assert_eq!(
+        config.permissions.rules["health"].allow_channels,
+        vec![ChannelId::new(100)]
+    );
+}
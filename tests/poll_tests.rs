@@ -0,0 +1,123 @@
+use serenity::model::id::{ChannelId, GuildId, MessageId, UserId};
+use the_bot::poll::{self, Poll, PollError};
+use the_bot::storage::Storage;
+
+fn poll(multi: bool) -> Poll {
+    Poll {
+        id: 0,
+        guild: Some(GuildId::new(100)),
+        channel: ChannelId::new(10),
+        message: MessageId::new(1000),
+        author: UserId::new(1),
+        question: "Lunch?".to_string(),
+        options: vec!["biriyani".to_string(), "khichuri".to_string()],
+        multi,
+        closes_at: 5_000,
+    }
+}
+
+#[test]
+fn parses_quoted_questions_and_flags() {
+    let parsed =
+        poll::parse(r#""Where to eat?" biriyani "star kabab" --duration 30m --multi"#).unwrap();
+    assert_eq!(parsed.question, "Where to eat?");
+    assert_eq!(parsed.options, vec!["biriyani", "star kabab"]);
+    assert_eq!(parsed.duration_secs, 30 * 60);
+    assert!(parsed.multi);
+
+    let parsed = poll::parse("“Deploy today?” yes no").unwrap();
+    assert_eq!(parsed.question, "Deploy today?");
+    assert_eq!(parsed.duration_secs, poll::DEFAULT_DURATION_SECS);
+    assert!(!parsed.multi);
+}
+
+#[test]
+fn rejects_bad_polls() {
+    assert_eq!(poll::parse(""), Err(PollError::MissingQuestion));
+    assert_eq!(
+        poll::parse(r#""Lunch?" biriyani"#),
+        Err(PollError::TooFewOptions)
+    );
+    assert_eq!(
+        poll::parse(r#""Pick" 1 2 3 4 5 6 7 8 9 10 11"#),
+        Err(PollError::TooManyOptions)
+    );
+    assert_eq!(
+        poll::parse(r#""Lunch?" a b --duration 30d"#),
+        Err(PollError::BadDuration("30d".to_string()))
+    );
+    // too long to represent at all, not a panic
+    assert_eq!(
+        poll::parse(r#""q" a b --duration 9999999999999w"#),
+        Err(PollError::BadDuration("9999999999999w".to_string()))
+    );
+}
+
+#[test]
+fn tallies_and_draws_results() {
+    assert_eq!(poll::option_for("2️⃣"), Some(1));
+    assert_eq!(poll::option_for("👍"), None);
+
+    let votes = [
+        (UserId::new(1), 0),
+        (UserId::new(2), 0),
+        (UserId::new(3), 1),
+        (UserId::new(4), 7),
+    ];
+    let counts = poll::tally(2, &votes);
+    assert_eq!(counts, vec![2, 1]);
+
+    let results = poll::results_message(&poll(false), &counts);
+    assert!(results.contains("1️⃣ biriyani\n`████████████████████` 2 (66%) 🏆"));
+    assert!(results.ends_with("2️⃣ khichuri\n`██████████░░░░░░░░░░` 1 (33%)"));
+
+    let empty = poll::results_message(&poll(false), &[0, 0]);
+    assert!(empty.contains("Nobody voted."));
+}
+
+#[test]
+fn keeps_one_vote_per_user_unless_multi() {
+    let storage = Storage::in_memory().unwrap();
+    let single = storage.add_poll(&poll(false)).unwrap();
+    let user = UserId::new(2);
+
+    assert!(
+        storage
+            .record_vote(single, user, 0, false)
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        storage.record_vote(single, user, 1, false).unwrap(),
+        vec![0]
+    );
+    assert_eq!(storage.poll_votes(single).unwrap(), vec![(user, 1)]);
+
+    let multi = storage
+        .add_poll(&Poll {
+            message: MessageId::new(1001),
+            ..poll(true)
+        })
+        .unwrap();
+    storage.record_vote(multi, user, 0, true).unwrap();
+    storage.record_vote(multi, user, 1, true).unwrap();
+    storage.remove_vote(multi, user, 0).unwrap();
+    assert_eq!(storage.poll_votes(multi).unwrap(), vec![(user, 1)]);
+}
+
+#[test]
+fn persists_polls_until_closed() {
+    let storage = Storage::in_memory().unwrap();
+    let id = storage.add_poll(&poll(false)).unwrap();
+
+    let open = storage.open_poll(MessageId::new(1000)).unwrap().unwrap();
+    assert_eq!(open.id, id);
+    assert_eq!(open.options, vec!["biriyani", "khichuri"]);
+
+    assert!(storage.due_polls(4_999).unwrap().is_empty());
+    assert_eq!(storage.due_polls(5_000).unwrap().len(), 1);
+
+    storage.close_poll(id).unwrap();
+    assert!(storage.due_polls(5_000).unwrap().is_empty());
+    assert_eq!(storage.open_poll(MessageId::new(1000)).unwrap(), None);
+}
@@ -0,0 +1,70 @@
+use serenity::model::id::{ChannelId, MessageId};
+use the_bot::config::BotConfig;
+use the_bot::pr_links::{
+    Forge, PrReactionConfig, PullRequestLink, SeenMessages, find_pull_requests,
+};
+
+#[test]
+fn parses_github_and_gitlab_links() {
+    let github = PullRequestLink::parse("https://github.com/owner/repo/pull/42/files?w=1").unwrap();
+    assert_eq!(github.forge, Forge::GitHub);
+    assert_eq!(github.repo, "owner/repo");
+    assert_eq!(github.number, 42);
+
+    let gitlab =
+        PullRequestLink::parse("https://gitlab.example.com/group/sub/project/-/merge_requests/7")
+            .unwrap();
+    assert_eq!(gitlab.forge, Forge::GitLab);
+    assert_eq!(gitlab.repo, "group/sub/project");
+    assert_eq!(gitlab.number, 7);
+
+    assert!(PullRequestLink::parse("https://github.com/owner/repo/issues/42").is_none());
+    assert!(PullRequestLink::parse("https://example.com/owner/repo/pull/42").is_none());
+}
+
+#[test]
+fn finds_links_in_a_message() {
+    let links = find_pull_requests(
+        "please review <https://github.com/a/b/pull/1> and (https://github.com/a/c/pull/2)",
+    );
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[1].repo, "a/c");
+}
+
+#[test]
+fn config_filters_by_channel_repo_and_keyword() {
+    let config = PrReactionConfig {
+        channels: vec![ChannelId::new(1)],
+        repos: vec!["owner/repo".to_string()],
+        keyword: Some("review".to_string()),
+        ..PrReactionConfig::default()
+    };
+    let link = "https://github.com/owner/repo/pull/3";
+
+    assert!(config.matches(ChannelId::new(1), &format!("Review please {}", link)));
+    assert!(!config.matches(ChannelId::new(2), &format!("review please {}", link)));
+    assert!(!config.matches(ChannelId::new(1), link));
+    assert!(!config.matches(
+        ChannelId::new(1),
+        "review https://github.com/other/repo/pull/3"
+    ));
+}
+
+#[test]
+fn seen_messages_dedupe() {
+    let mut seen = SeenMessages::new();
+    assert!(seen.insert(MessageId::new(1)));
+    assert!(!seen.insert(MessageId::new(1)));
+    assert!(seen.insert(MessageId::new(2)));
+}
+
+#[test]
+fn pr_reactions_are_off_by_default() {
+    let config = BotConfig::default();
+    assert!(config.pr_reactions.channels.is_empty());
+    assert!(
+        !config
+            .pr_reactions
+            .matches(ChannelId::new(1), "https://github.com/a/b/pull/1")
+    );
+}
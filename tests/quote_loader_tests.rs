@@ -1,9 +1,9 @@
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use rand::Rng;
-use the_bot::quote_loader::{load_from_file, load_from_folder};
+use the_bot::quote_loader::{load_categories_from_folder, load_from_file, load_from_folder};
 
 fn make_temp_dir() -> PathBuf {
     let mut dir = std::env::temp_dir();
@@ -13,8 +13,8 @@ fn make_temp_dir() -> PathBuf {
     dir
 }
 
-fn write_file(path: &PathBuf, name: &str, content: &str) -> PathBuf {
-    let mut file_path = path.clone();
+fn write_file(path: &Path, name: &str, content: &str) -> PathBuf {
+    let mut file_path = path.to_path_buf();
     file_path.push(name);
     let mut f = fs::File::create(&file_path).unwrap();
     f.write_all(content.as_bytes()).unwrap();
@@ -42,6 +42,18 @@ fn load_from_folder_aggregates_files() {
     assert_eq!(quotes.len(), 4);
 }
 
+#[test]
+fn load_categories_groups_quotes_by_file_name() {
+    let dir = make_temp_dir();
+    let _f1 = write_file(&dir, "riddles.txt", "R1\n%\nR2\n");
+    let _f2 = write_file(&dir, "fortunes.txt", "F1\n");
+
+    let categories = load_categories_from_folder(dir.to_str().unwrap()).unwrap();
+    assert_eq!(categories.len(), 2);
+    assert_eq!(categories["riddles"].len(), 2);
+    assert_eq!(categories["fortunes"], vec!["F1".to_string()]);
+}
+
 #[test]
 fn load_from_folder_missing_returns_error() {
     let mut dir = std::env::temp_dir();
@@ -0,0 +1,177 @@
+use std::time::{Duration, Instant};
+
+use serenity::model::id::{ChannelId, RoleId, UserId};
+use the_bot::config::{BotConfig, CooldownConfig, RateLimitConfig};
+use the_bot::language::Language;
+use the_bot::rate_limit::{RateLimiter, Scope, throttle_message};
+
+fn limiter_with(command: &str, cooldown: CooldownConfig) -> RateLimiter {
+    let mut config = RateLimitConfig::default();
+    config.commands.insert(command.to_string(), cooldown);
+    RateLimiter::new(config)
+}
+
+#[test]
+fn user_cooldown_blocks_second_use_by_same_user() {
+    let mut limiter = limiter_with(
+        "joke",
+        CooldownConfig {
+            user_secs: 10,
+            channel_secs: 0,
+            global_secs: 0,
+        },
+    );
+    let now = Instant::now();
+    let (user, channel) = (UserId::new(1), ChannelId::new(10));
+
+    assert!(limiter.check("joke", user, channel, now).is_ok());
+
+    let throttled = limiter
+        .check("joke", user, channel, now + Duration::from_secs(4))
+        .unwrap_err();
+    assert_eq!(throttled.scope, Scope::User);
+    assert_eq!(throttled.retry_after, Duration::from_secs(6));
+
+    // someone else is not affected
+    assert!(limiter.check("joke", UserId::new(2), channel, now).is_ok());
+    // and the cooldown expires
+    assert!(
+        limiter
+            .check("joke", user, channel, now + Duration::from_secs(10))
+            .is_ok()
+    );
+}
+
+#[test]
+fn channel_and_global_cooldowns_apply_across_users() {
+    let mut limiter = limiter_with(
+        "oracle",
+        CooldownConfig {
+            user_secs: 0,
+            channel_secs: 5,
+            global_secs: 2,
+        },
+    );
+    let now = Instant::now();
+
+    assert!(
+        limiter
+            .check("oracle", UserId::new(1), ChannelId::new(10), now)
+            .is_ok()
+    );
+
+    let same_channel = limiter
+        .check(
+            "oracle",
+            UserId::new(2),
+            ChannelId::new(10),
+            now + Duration::from_secs(3),
+        )
+        .unwrap_err();
+    assert_eq!(same_channel.scope, Scope::Channel);
+
+    let other_channel = limiter
+        .check(
+            "oracle",
+            UserId::new(2),
+            ChannelId::new(11),
+            now + Duration::from_secs(1),
+        )
+        .unwrap_err();
+    assert_eq!(other_channel.scope, Scope::Global);
+
+    assert!(
+        limiter
+            .check(
+                "oracle",
+                UserId::new(2),
+                ChannelId::new(11),
+                now + Duration::from_secs(2)
+            )
+            .is_ok()
+    );
+}
+
+#[test]
+fn commands_have_independent_cooldowns() {
+    let mut limiter = RateLimiter::new(RateLimitConfig::default());
+    let now = Instant::now();
+    let (user, channel) = (UserId::new(1), ChannelId::new(10));
+
+    assert!(limiter.check("joke", user, channel, now).is_ok());
+    assert!(limiter.check("sprint", user, channel, now).is_ok());
+}
+
+#[test]
+fn throttle_notice_is_rate_limited() {
+    let mut limiter = RateLimiter::new(RateLimitConfig::default());
+    let now = Instant::now();
+    let user = UserId::new(1);
+
+    assert!(limiter.should_notify(user, now));
+    assert!(!limiter.should_notify(user, now + Duration::from_secs(5)));
+    assert!(limiter.should_notify(user, now + Duration::from_secs(30)));
+}
+
+#[test]
+fn exempt_roles_are_recognised() {
+    let config = RateLimitConfig {
+        exempt_roles: vec![RoleId::new(42)],
+        ..RateLimitConfig::default()
+    };
+    let limiter = RateLimiter::new(config);
+
+    assert!(limiter.is_exempt(&[RoleId::new(7), RoleId::new(42)]));
+    assert!(!limiter.is_exempt(&[RoleId::new(7)]));
+    assert!(!limiter.is_exempt(&[]));
+}
+
+#[test]
+fn throttle_message_rounds_retry_up() {
+    let throttled = the_bot::rate_limit::Throttled {
+        scope: Scope::User,
+        retry_after: Duration::from_millis(2100),
+    };
+    assert!(throttle_message(&throttled, None).contains('3'));
+
+    let english = throttle_message(&throttled, Some(Language::English));
+    assert!(english.contains('3'));
+    // no Bengali script in the English variant
+    assert!(!english.chars().any(|c| ('\u{0980}'..='\u{09FF}').contains(&c)));
+}
+
+#[test]
+fn config_parses_rate_limit_section() {
+    let config = BotConfig::parse(
+        r#"
+        [rate_limit]
+        exempt_roles = [123]
+        notice_cooldown_secs = 60
+
+        [rate_limit.default]
+        user_secs = 1
+
+        [rate_limit.commands.joke]
+        user_secs = 30
+        global_secs = 5
+        "#,
+    )
+    .unwrap();
+
+    let limiter_config = config.rate_limit;
+    assert_eq!(limiter_config.exempt_roles, vec![RoleId::new(123)]);
+    assert_eq!(limiter_config.notice_cooldown_secs, 60);
+    assert_eq!(limiter_config.default.user_secs, 1);
+
+    let joke = limiter_config.commands["joke"];
+    assert_eq!(joke.user_secs, 30);
+    assert_eq!(joke.channel_secs, 0);
+    assert_eq!(joke.global_secs, 5);
+}
+
+#[test]
+fn empty_config_uses_defaults() {
+    let config = BotConfig::parse("").unwrap();
+    assert!(config.rate_limit.commands.contains_key("joke"));
+    assert_eq!(config.rate_limit.default.user_secs, 3);
+}
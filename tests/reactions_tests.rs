@@ -0,0 +1,44 @@
+use the_bot::emoji_generator::EmojiGenerator;
+use the_bot::guild_settings::GuildSettings;
+use the_bot::reactions::{EmojiMode, MAX_REACTIONS, is_reactable, pick_reactions};
+
+#[test]
+fn rejects_flags_zwj_sequences_and_skin_tones() {
+    assert!(is_reactable("😀"));
+    assert!(is_reactable("🌶️"));
+    assert!(!is_reactable("🇧🇩"));
+    assert!(!is_reactable("👨🏾‍💻"));
+    assert!(!is_reactable("👍🏽"));
+    assert!(!is_reactable(""));
+}
+
+#[test]
+fn picks_respect_the_reaction_limit() {
+    let emojis = EmojiGenerator::new().generate(40);
+
+    assert!(pick_reactions(&emojis, 0).len() <= MAX_REACTIONS);
+    assert!(pick_reactions(&emojis, 15).len() <= MAX_REACTIONS - 15);
+    assert!(pick_reactions(&emojis, MAX_REACTIONS).is_empty());
+}
+
+#[test]
+fn picks_skip_duplicates_and_rejected_emojis() {
+    let emojis: Vec<String> = ["🇧🇩", "😀", "😀", "🤖"]
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+    assert_eq!(pick_reactions(&emojis, 0), vec!["😀", "🤖"]);
+}
+
+#[test]
+fn emoji_mode_is_a_guild_setting() {
+    let mut settings = GuildSettings::default();
+    assert_eq!(settings.emoji_mode, EmojiMode::Text);
+
+    settings.apply_command("set emojis reactions", &[]);
+    assert_eq!(settings.emoji_mode, EmojiMode::Reactions);
+    assert!(settings.describe().contains("emojis: reactions"));
+
+    settings.apply_command("set emojis sideways", &[]);
+    assert_eq!(settings.emoji_mode, EmojiMode::Reactions);
+}
@@ -0,0 +1,178 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use the_bot::reminders::{self, Reminder, ReminderError};
+use the_bot::storage::Storage;
+
+// Wed 15 Nov 2023 10:00 UTC, 16:00 in Dhaka
+fn now() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2023, 11, 15, 10, 0, 0).unwrap()
+}
+
+fn reminder(author: u64, target: u64, due_at: i64) -> Reminder {
+    Reminder {
+        id: 0,
+        guild: Some(GuildId::new(100)),
+        channel: ChannelId::new(10),
+        author: UserId::new(author),
+        target: UserId::new(target),
+        text: "check staging".to_string(),
+        due_at,
+        dm: false,
+    }
+}
+
+#[test]
+fn parses_relative_durations() {
+    let parsed = reminders::parse("in 2h check staging", now(), Tz::UTC).unwrap();
+    assert_eq!(parsed.due_at, now() + Duration::hours(2));
+    assert_eq!(parsed.text, "check staging");
+    assert!(!parsed.dm);
+
+    let parsed = reminders::parse("in 1h30m deploy", now(), Tz::UTC).unwrap();
+    assert_eq!(parsed.due_at, now() + Duration::minutes(90));
+
+    let parsed = reminders::parse("in 2 hours 10 minutes standup --dm", now(), Tz::UTC).unwrap();
+    assert_eq!(parsed.due_at, now() + Duration::minutes(130));
+    assert_eq!(parsed.text, "standup");
+    assert!(parsed.dm);
+}
+
+#[test]
+fn reads_clock_times_in_the_users_timezone() {
+    let dhaka: Tz = "Asia/Dhaka".parse().unwrap();
+
+    // 17:00 in Dhaka is 11:00 UTC, still ahead today
+    let parsed = reminders::parse("at 17:00 standup", now(), dhaka).unwrap();
+    assert_eq!(
+        parsed.due_at,
+        Utc.with_ymd_and_hms(2023, 11, 15, 11, 0, 0).unwrap()
+    );
+
+    // 9am in Dhaka has passed, so it is tomorrow's
+    let parsed = reminders::parse("at 9am standup", now(), dhaka).unwrap();
+    assert_eq!(
+        parsed.due_at,
+        Utc.with_ymd_and_hms(2023, 11, 16, 3, 0, 0).unwrap()
+    );
+
+    let parsed = reminders::parse("tomorrow at 5:30pm deploy", now(), Tz::UTC).unwrap();
+    assert_eq!(
+        parsed.due_at,
+        Utc.with_ymd_and_hms(2023, 11, 16, 17, 30, 0).unwrap()
+    );
+
+    let parsed = reminders::parse("tomorrow deploy", now(), Tz::UTC).unwrap();
+    assert_eq!(
+        parsed.due_at,
+        Utc.with_ymd_and_hms(2023, 11, 16, 9, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn rejects_unreadable_reminders() {
+    assert_eq!(
+        reminders::parse("check staging", now(), Tz::UTC),
+        Err(ReminderError::MissingTime)
+    );
+    assert_eq!(
+        reminders::parse("in soon check staging", now(), Tz::UTC),
+        Err(ReminderError::BadTime("soon".to_string()))
+    );
+    assert_eq!(
+        reminders::parse("at 25:00 standup", now(), Tz::UTC),
+        Err(ReminderError::BadTime("25:00".to_string()))
+    );
+    assert_eq!(
+        reminders::parse("in 2h", now(), Tz::UTC),
+        Err(ReminderError::MissingText)
+    );
+    assert!(reminders::parse_timezone("Mars/Olympus").is_none());
+}
+
+#[test]
+fn rejects_durations_too_long_to_represent_or_wait_for() {
+    for (args, bad) in [
+        ("in 9999999999999w x", "9999999999999w"),
+        ("in 1000000000d x", "1000000000d"),
+        ("in 9999999999999 weeks x", "9999999999999"),
+        ("in 2 years x", "2"),
+        ("in 400d x", "400d"),
+    ] {
+        assert_eq!(
+            reminders::parse(args, now(), Tz::UTC),
+            Err(ReminderError::BadTime(bad.to_string())),
+            "{}",
+            args
+        );
+    }
+    assert_eq!(reminders::parse_compact("9999999999999w"), None);
+    assert!(reminders::parse("in 52w x", now(), Tz::UTC).is_ok());
+}
+
+#[test]
+fn stores_and_delivers_due_reminders() {
+    let storage = Storage::in_memory().unwrap();
+    let soon = storage.add_reminder(&reminder(1, 1, 1_000)).unwrap();
+    let later = storage.add_reminder(&reminder(1, 2, 5_000)).unwrap();
+
+    let due = storage.due_reminders(2_000).unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].id, soon);
+    assert_eq!(due[0].guild, Some(GuildId::new(100)));
+
+    storage.delete_reminder(soon).unwrap();
+    assert!(storage.due_reminders(2_000).unwrap().is_empty());
+
+    // the target sees it too, but only the author's count is limited
+    let listed = storage.reminders_for(UserId::new(2)).unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, later);
+    assert_eq!(storage.reminder_count(UserId::new(1)).unwrap(), 1);
+    assert_eq!(storage.reminder_count(UserId::new(2)).unwrap(), 0);
+}
+
+#[test]
+fn only_the_author_or_target_can_cancel() {
+    let storage = Storage::in_memory().unwrap();
+    let id = storage.add_reminder(&reminder(1, 2, 5_000)).unwrap();
+
+    assert!(!storage.cancel_reminder(id, UserId::new(3)).unwrap());
+    assert!(storage.cancel_reminder(id, UserId::new(2)).unwrap());
+    assert!(!storage.cancel_reminder(id, UserId::new(1)).unwrap());
+}
+
+#[test]
+fn remembers_timezones() {
+    let storage = Storage::in_memory().unwrap();
+    let user = UserId::new(1);
+    assert_eq!(storage.user_timezone(user).unwrap(), None);
+
+    storage.set_user_timezone(user, "Asia/Dhaka").unwrap();
+    storage.set_user_timezone(user, "Europe/Berlin").unwrap();
+    assert_eq!(
+        storage.user_timezone(user).unwrap(),
+        Some("Europe/Berlin".to_string())
+    );
+}
+
+#[test]
+fn formats_reminders_for_their_reader() {
+    let dhaka: Tz = "Asia/Dhaka".parse().unwrap();
+    let due = Utc
+        .with_ymd_and_hms(2023, 11, 15, 11, 0, 0)
+        .unwrap()
+        .timestamp();
+    assert_eq!(reminders::format_due(due, dhaka), "Wed 15 Nov 17:00 +06");
+
+    let mine = reminder(1, 1, due);
+    assert!(reminders::delivery_message(&mine).contains("<@1>"));
+    let theirs = reminder(1, 2, due);
+    let message = reminders::delivery_message(&theirs);
+    assert!(message.contains("<@2>") && message.contains("<@1> asked me"));
+
+    let list = reminders::list_message(&[theirs], UserId::new(1), dhaka, "?");
+    assert!(list.contains("(for <@2>)"));
+    assert!(list.contains("Asia/Dhaka"));
+    assert!(list.contains("`?reminders cancel <id>`"));
+}
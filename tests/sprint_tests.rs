@@ -0,0 +1,130 @@
+use chrono::NaiveDate;
+use serenity::model::id::ChannelId;
+use the_bot::sprint::{self, Sprint, SprintCommand, SprintError, SprintItem};
+use the_bot::storage::Storage;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2023, 11, d).unwrap()
+}
+
+fn sprint() -> Sprint {
+    Sprint {
+        id: 0,
+        channel: ChannelId::new(10),
+        name: "Sprint 42".to_string(),
+        started_on: day(13),
+        ends_on: day(24),
+        goals: vec!["Ship login".to_string()],
+    }
+}
+
+fn item(number: u32, added_on: NaiveDate, done_on: Option<NaiveDate>) -> SprintItem {
+    SprintItem {
+        number,
+        title: format!("item {}", number),
+        added_on,
+        done_on,
+    }
+}
+
+#[test]
+fn parses_sprint_commands() {
+    let today = day(13);
+    assert_eq!(SprintCommand::parse("", today), Ok(SprintCommand::Status));
+    assert_eq!(
+        SprintCommand::parse("start Sprint 42 2023-11-24", today),
+        Ok(SprintCommand::Start {
+            name: "Sprint 42".to_string(),
+            ends_on: day(24)
+        })
+    );
+    assert_eq!(
+        SprintCommand::parse("start Sprint 43 2w", today),
+        Ok(SprintCommand::Start {
+            name: "Sprint 43".to_string(),
+            ends_on: day(27)
+        })
+    );
+    assert_eq!(
+        SprintCommand::parse("add fix the login page", today),
+        Ok(SprintCommand::Add("fix the login page".to_string()))
+    );
+    assert_eq!(
+        SprintCommand::parse("done #3", today),
+        Ok(SprintCommand::Done(3))
+    );
+
+    assert_eq!(
+        SprintCommand::parse("start 2023-11-24", today),
+        Err(SprintError::MissingName)
+    );
+    assert_eq!(
+        SprintCommand::parse("start Old 2023-11-01", today),
+        Err(SprintError::EndsBeforeStart)
+    );
+    assert_eq!(
+        SprintCommand::parse("start Forever 100000000d", today),
+        Err(SprintError::BadDate("100000000d".to_string()))
+    );
+    assert_eq!(
+        SprintCommand::parse("add", today),
+        Err(SprintError::MissingText)
+    );
+    assert_eq!(
+        SprintCommand::parse("done it", today),
+        Err(SprintError::BadItem("it".to_string()))
+    );
+}
+
+#[test]
+fn burns_down_open_items() {
+    let items = [
+        item(1, day(13), Some(day(14))),
+        item(2, day(13), None),
+        item(3, day(15), Some(day(15))),
+    ];
+    assert_eq!(
+        sprint::burndown(&sprint(), &items, day(15)),
+        vec![(day(13), 2), (day(14), 1), (day(15), 1)]
+    );
+
+    let status = sprint::status_message(&sprint(), &items, day(15), "flavor", "!");
+    assert!(status.starts_with("🏃 **Sprint 42**, 13 Nov to 24 Nov, 9 days left (2/3 done)"));
+    assert!(status.contains("🎯 Ship login"));
+    assert!(status.contains("☐ `#2` item 2"));
+    assert!(status.contains("`Mon 13 ████████████████████ 2`"));
+    assert!(status.ends_with("_flavor_"));
+}
+
+#[test]
+fn sums_up_the_sprint_when_it_ends() {
+    let items = [item(1, day(13), Some(day(14))), item(2, day(13), None)];
+    let retro = sprint::retrospective(&sprint(), &items, day(26));
+    assert!(retro.starts_with("🏁 **Sprint 42** is over: 1 of 2 items done in 14 day(s)"));
+    assert!(retro.contains("It ran 2 day(s) past its end date."));
+    assert!(retro.contains("**Carried over** 📦\n`#2` item 2"));
+}
+
+#[test]
+fn keeps_one_running_sprint_per_channel() {
+    let storage = Storage::in_memory().unwrap();
+    let id = storage.start_sprint(&sprint()).unwrap().unwrap();
+    assert_eq!(storage.start_sprint(&sprint()).unwrap(), None);
+
+    assert_eq!(storage.add_sprint_item(id, "login", day(13)).unwrap(), 1);
+    assert_eq!(storage.add_sprint_item(id, "logout", day(14)).unwrap(), 2);
+    assert!(storage.complete_sprint_item(id, 1, day(15)).unwrap());
+    assert!(!storage.complete_sprint_item(id, 1, day(15)).unwrap());
+    assert!(!storage.complete_sprint_item(id, 9, day(15)).unwrap());
+
+    storage
+        .set_sprint_goals(id, &["Ship login".to_string(), "No P1s".to_string()])
+        .unwrap();
+    let active = storage.active_sprint(ChannelId::new(10)).unwrap().unwrap();
+    assert_eq!(active.goals.len(), 2);
+    assert_eq!(storage.sprint_items(id).unwrap()[0].done_on, Some(day(15)));
+
+    storage.end_sprint(id).unwrap();
+    assert_eq!(storage.active_sprint(ChannelId::new(10)).unwrap(), None);
+    assert!(storage.start_sprint(&sprint()).unwrap().is_some());
+}
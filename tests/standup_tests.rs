@@ -0,0 +1,132 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use serenity::model::id::{ChannelId, UserId};
+use the_bot::config::BotConfig;
+use the_bot::standup::{self, Answers, Delivery, TeamConfig};
+use the_bot::storage::Storage;
+
+fn team() -> TeamConfig {
+    let config = BotConfig::parse(
+        r#"
+        [[standup.teams]]
+        name = "backend"
+        channel = 10
+        members = [1, 2, 3]
+        time = "09:30"
+        timezone = "Asia/Dhaka"
+        days = ["sun", "mon", "tue", "wed", "thu"]
+        window_mins = 60
+        "#,
+    )
+    .unwrap();
+    config.standup.team("Backend").unwrap().clone()
+}
+
+#[test]
+fn parses_teams_with_defaults() {
+    let team = team();
+    assert_eq!(team.channel, ChannelId::new(10));
+    assert_eq!(team.delivery, Delivery::Dm);
+    assert_eq!(team.questions.len(), 3);
+    assert!(team.is_member(UserId::new(2)));
+    assert_eq!(team.problem(), None);
+
+    let broken = TeamConfig {
+        timezone: "Mars/Olympus".to_string(),
+        ..team
+    };
+    assert!(broken.problem().is_some());
+
+    // a team without a channel has nowhere to post
+    assert!(BotConfig::parse("[[standup.teams]]\nname = \"backend\"").is_err());
+}
+
+#[test]
+fn rounds_run_in_the_teams_timezone() {
+    let team = team();
+    // Wed 15 Nov 2023, 09:30 in Dhaka is 03:30 UTC
+    let day = NaiveDate::from_ymd_opt(2023, 11, 15).unwrap();
+    let at = |h, m| Utc.with_ymd_and_hms(2023, 11, 15, h, m, 0).unwrap();
+
+    assert_eq!(team.due_round(at(3, 29)), None);
+    assert_eq!(team.due_round(at(3, 30)), Some(day));
+    assert_eq!(team.due_round(at(4, 29)), Some(day));
+    assert_eq!(team.due_round(at(4, 30)), None);
+    assert_eq!(team.closes_at(day), Some(at(4, 30)));
+
+    // Friday isn't a standup day for this team
+    let friday = Utc.with_ymd_and_hms(2023, 11, 17, 3, 45, 0).unwrap();
+    assert_eq!(team.due_round(friday), None);
+
+    // nothing to ask, so never a round
+    let silent = TeamConfig {
+        questions: Vec::new(),
+        ..team
+    };
+    assert_eq!(silent.due_round(at(3, 30)), None);
+}
+
+#[test]
+fn splits_answers() {
+    assert_eq!(
+        standup::parse_answers("fixed the build | reviews |  | none"),
+        vec!["fixed the build", "reviews", "none"]
+    );
+    assert!(standup::parse_answers("  ").is_empty());
+}
+
+#[test]
+fn summarizes_answers_and_missing_members() {
+    let team = team();
+    let day = NaiveDate::from_ymd_opt(2023, 11, 15).unwrap();
+    let mut answers = Answers::new();
+    answers.insert(
+        UserId::new(1),
+        vec!["fixed the build".to_string(), "reviews".to_string()],
+    );
+
+    let summary = standup::summary(&team, day, &answers);
+    assert!(summary.starts_with("📋 **backend standup**, Wed 15 Nov"));
+    assert!(summary.contains("**<@1>**"));
+    assert!(summary.contains("> _What are you doing today?_\nreviews"));
+    assert!(summary.contains("No update from <@2>, <@3>."));
+
+    let nobody = standup::summary(&team, day, &Answers::new());
+    assert!(nobody.contains("Nobody answered."));
+}
+
+#[test]
+fn calls_channel_teams_with_the_guild_prefix() {
+    let message = standup::channel_message(&team(), "?");
+    assert!(message.contains("<@1> <@2> <@3>"));
+    assert!(message.contains("2. What are you doing today?"));
+    assert!(message.contains("`?standup backend`"));
+}
+
+#[test]
+fn stores_rounds_and_answers() {
+    let storage = Storage::in_memory().unwrap();
+    let day = NaiveDate::from_ymd_opt(2023, 11, 15).unwrap();
+
+    assert!(storage.start_standup("backend", day).unwrap());
+    assert!(!storage.start_standup("backend", day).unwrap());
+    assert_eq!(
+        storage.open_standups().unwrap(),
+        vec![("backend".to_string(), day)]
+    );
+
+    let user = UserId::new(1);
+    storage
+        .add_standup_answer("backend", day, user, 0, "first")
+        .unwrap();
+    storage
+        .add_standup_answer("backend", day, user, 1, "today")
+        .unwrap();
+    storage
+        .add_standup_answer("backend", day, user, 0, "yesterday")
+        .unwrap();
+    let answers = storage.standup_answers("backend", day).unwrap();
+    assert_eq!(answers[&user], vec!["yesterday", "today"]);
+
+    storage.close_standup("backend", day).unwrap();
+    assert!(storage.open_standups().unwrap().is_empty());
+}
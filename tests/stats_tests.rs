@@ -0,0 +1,137 @@
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use the_bot::stats::{self, CommandUsage, Period, StatsQuery};
+use the_bot::storage::Storage;
+
+const NOW: i64 = 1_700_000_000;
+const DAY: i64 = 24 * 60 * 60;
+
+fn usage(
+    command: &str,
+    user: u64,
+    guild: Option<u64>,
+    used_at: i64,
+    success: bool,
+) -> CommandUsage {
+    CommandUsage {
+        command: command.to_string(),
+        user: UserId::new(user),
+        guild: guild.map(GuildId::new),
+        channel: ChannelId::new(10),
+        used_at,
+        success,
+    }
+}
+
+fn seeded_storage() -> Storage {
+    let storage = Storage::in_memory().unwrap();
+    for record in [
+        usage("joke", 1, Some(100), NOW - 60, true),
+        usage("joke", 2, Some(100), NOW - 2 * DAY, true),
+        usage("joke", 1, Some(100), NOW - 30 * DAY, false),
+        usage("oracle", 1, Some(100), NOW - 60, true),
+        usage("health", 3, Some(200), NOW - 60, true),
+        usage("joke", 1, None, NOW - 60, true),
+    ] {
+        storage.record_command_usage(&record).unwrap();
+    }
+    storage
+}
+
+#[test]
+fn parses_stats_queries() {
+    let is_command = |name: &str| (name == "joke").then_some("joke");
+
+    assert_eq!(
+        StatsQuery::parse("", is_command),
+        Some(StatsQuery::Leaderboard(Period::AllTime))
+    );
+    assert_eq!(
+        StatsQuery::parse("week", is_command),
+        Some(StatsQuery::Leaderboard(Period::Week))
+    );
+    assert_eq!(
+        StatsQuery::parse("me day", is_command),
+        Some(StatsQuery::Me(Period::Day))
+    );
+    assert_eq!(
+        StatsQuery::parse("joke", is_command),
+        Some(StatsQuery::Command("joke".to_string()))
+    );
+    assert_eq!(StatsQuery::parse("nope", is_command), None);
+    assert_eq!(StatsQuery::parse("me forever", is_command), None);
+}
+
+#[test]
+fn counts_are_scoped_to_guild_and_period() {
+    let storage = seeded_storage();
+    let guild = Some(GuildId::new(100));
+
+    let all_time = storage.command_counts(guild, 0, None, 10).unwrap();
+    assert_eq!(
+        all_time,
+        vec![("joke".to_string(), 3), ("oracle".to_string(), 1)]
+    );
+
+    let last_day = storage
+        .command_counts(guild, Period::Day.since(NOW), None, 10)
+        .unwrap();
+    assert_eq!(
+        last_day,
+        vec![("joke".to_string(), 1), ("oracle".to_string(), 1)]
+    );
+
+    let users = storage.user_counts(guild, 0, 10).unwrap();
+    assert_eq!(users, vec![(UserId::new(1), 3), (UserId::new(2), 1)]);
+
+    let dms = storage.command_counts(None, 0, None, 10).unwrap();
+    assert_eq!(dms, vec![("joke".to_string(), 1)]);
+}
+
+#[test]
+fn command_report_includes_failures_per_period() {
+    let storage = seeded_storage();
+    let query = StatsQuery::Command("joke".to_string());
+
+    let report = stats::report(
+        &storage,
+        Some(GuildId::new(100)),
+        UserId::new(1),
+        &query,
+        NOW,
+    )
+    .unwrap()
+    .to_text();
+
+    assert!(report.contains("last 24 hours: 1 (0 failed)"));
+    assert!(report.contains("last 7 days: 2 (0 failed)"));
+    assert!(report.contains("all time: 3 (1 failed)"));
+}
+
+#[test]
+fn me_report_only_counts_the_caller() {
+    let storage = seeded_storage();
+    let query = StatsQuery::Me(Period::AllTime);
+
+    let report = stats::report(
+        &storage,
+        Some(GuildId::new(100)),
+        UserId::new(2),
+        &query,
+        NOW,
+    )
+    .unwrap()
+    .to_text();
+    assert!(report.contains("1 commands"));
+    assert!(!report.contains("oracle"));
+
+    let nobody = stats::report(
+        &storage,
+        Some(GuildId::new(100)),
+        UserId::new(9),
+        &query,
+        NOW,
+    )
+    .unwrap()
+    .to_text();
+    assert!(nobody.contains("haven't used any commands"));
+}
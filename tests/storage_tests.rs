@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::PathBuf;
+
+use rand::Rng;
+use serenity::model::id::GuildId;
+use the_bot::guild_settings::GuildSettings;
+use the_bot::language::Language;
+use the_bot::storage::{IN_MEMORY, Storage};
+
+fn temp_db_path() -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "the_guy_bot_storage_{}",
+        rand::rng().random::<u64>()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    dir.push("the-bot.db");
+    dir
+}
+
+#[test]
+fn migrations_run_once_and_reopen_cleanly() {
+    let path = temp_db_path();
+
+    let storage = Storage::open(&path).unwrap();
+    let version = storage.schema_version().unwrap();
+    assert!(version > 0);
+    drop(storage);
+
+    let reopened = Storage::open(&path).unwrap();
+    assert_eq!(reopened.schema_version().unwrap(), version);
+}
+
+#[test]
+fn guild_settings_round_trip() {
+    let storage = Storage::in_memory().unwrap();
+    let guild = GuildId::new(99);
+    assert!(storage.guild_settings(guild).unwrap().is_none());
+
+    let settings = GuildSettings {
+        prefix: "?".to_string(),
+        language: Some(Language::English),
+        ..GuildSettings::default()
+    };
+    storage.save_guild_settings(guild, &settings).unwrap();
+    assert_eq!(
+        storage.guild_settings(guild).unwrap(),
+        Some(settings.clone())
+    );
+
+    // saving again replaces the row
+    let updated = GuildSettings {
+        prefix: "$".to_string(),
+        ..settings
+    };
+    storage.save_guild_settings(guild, &updated).unwrap();
+
+    let all = storage.all_guild_settings().unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[&guild].prefix, "$");
+}
+
+#[test]
+fn data_survives_reopening_the_file() {
+    let path = temp_db_path();
+    let guild = GuildId::new(1);
+
+    Storage::open(&path)
+        .unwrap()
+        .save_guild_settings(guild, &GuildSettings::default())
+        .unwrap();
+
+    assert!(
+        Storage::open(&path)
+            .unwrap()
+            .guild_settings(guild)
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn memory_path_opens_an_in_memory_database() {
+    let storage = Storage::open(IN_MEMORY).unwrap();
+    assert!(storage.all_guild_settings().unwrap().is_empty());
+}
@@ -0,0 +1,45 @@
+use serenity::model::id::{RoleId, UserId};
+use the_bot::config::BotConfig;
+use the_bot::summon::{self, SummonRequest};
+
+#[test]
+fn parse_skips_mentions_and_finds_urgent() {
+    let request = SummonRequest::parse("<@123> <@!456> <@&789> urgent prod is down");
+    assert!(request.urgent);
+    assert_eq!(request.reason.as_deref(), Some("prod is down"));
+
+    let plain = SummonRequest::parse("<@123>");
+    assert_eq!(plain, SummonRequest::default());
+}
+
+#[test]
+fn summon_message_mentions_users_and_roles() {
+    let request = SummonRequest {
+        reason: Some("standup".to_string()),
+        urgent: false,
+    };
+    let message = summon::summon_message(
+        &[UserId::new(1), UserId::new(2)],
+        &[RoleId::new(3)],
+        "🎉",
+        &request,
+    );
+
+    assert!(message.starts_with("<@1> <@2> <@&3> 🎉"));
+    assert!(message.contains("📣 standup"));
+}
+
+#[test]
+fn only_users_that_did_not_post_are_escalated() {
+    let summoned = [UserId::new(1), UserId::new(2), UserId::new(3)];
+    let pending = summon::unanswered(&summoned, &[UserId::new(2), UserId::new(9)]);
+    assert_eq!(pending, vec![UserId::new(1), UserId::new(3)]);
+}
+
+#[test]
+fn escalation_delay_is_configurable() {
+    assert_eq!(BotConfig::default().summon.escalation_delay_secs, 300);
+
+    let config = BotConfig::parse("[summon]\nescalation_delay_secs = 60").unwrap();
+    assert_eq!(config.summon.escalation_delay_secs, 60);
+}
@@ -0,0 +1,189 @@
+use mockito::{Matcher, Server};
+use serde_json::json;
+use the_bot::tracker::{self, HttpTemplate, NewIssue, TrackerConfig, TrackerError, TrackerKind};
+
+const MESSAGE_LINK: &str = "https://discord.com/channels/1/2/3";
+
+fn linear(url: &str) -> TrackerConfig {
+    TrackerConfig {
+        api_url: Some(url.to_string()),
+        token: Some("lin_api_key".to_string()),
+        team_id: Some("team-1".to_string()),
+        ..TrackerConfig::default()
+    }
+}
+
+#[test]
+fn parses_title_and_description() {
+    let issue = NewIssue::parse(" Login is broken | The button does nothing ").unwrap();
+    assert_eq!(issue.title, "Login is broken");
+    assert_eq!(issue.description, "The button does nothing");
+
+    let issue = NewIssue::parse("Just a title").unwrap();
+    assert_eq!(issue.description, "");
+
+    assert_eq!(NewIssue::parse(" | no title"), None);
+}
+
+#[test]
+fn message_becomes_an_issue() {
+    let issue = NewIssue::from_message("\nCrash on start\nstack trace here").unwrap();
+    assert_eq!(issue.title, "Crash on start");
+    assert_eq!(issue.description, "> Crash on start\n> stack trace here");
+
+    let long = "x".repeat(100);
+    let issue = NewIssue::from_message(&long).unwrap();
+    assert_eq!(issue.title.chars().count(), 81);
+    assert!(issue.title.ends_with('…'));
+
+    assert_eq!(NewIssue::from_message("  \n "), None);
+}
+
+#[test]
+fn reporter_and_link_are_appended() {
+    let issue = NewIssue::parse("Title | Details")
+        .unwrap()
+        .reported_from("Rahim", MESSAGE_LINK);
+    assert_eq!(
+        issue.description,
+        format!(
+            "Details\n\nReported by Rahim from Discord: {}",
+            MESSAGE_LINK
+        )
+    );
+
+    let issue = NewIssue::parse("Title")
+        .unwrap()
+        .reported_from("Rahim", MESSAGE_LINK);
+    assert!(issue.description.starts_with("Reported by Rahim"));
+}
+
+#[test]
+fn is_configured_per_kind() {
+    assert!(!TrackerConfig::default().is_configured());
+    assert!(linear("http://localhost").is_configured());
+
+    let http = TrackerConfig {
+        kind: TrackerKind::Http,
+        create: HttpTemplate {
+            url: Some("http://localhost/issues".to_string()),
+            ..HttpTemplate::default()
+        },
+        ..TrackerConfig::default()
+    };
+    assert!(http.is_configured());
+}
+
+#[test]
+fn linear_request_is_a_graphql_mutation() {
+    let issue = NewIssue::parse("Title | Details").unwrap();
+    let request = tracker::issue_request(&linear("http://localhost/graphql"), &issue).unwrap();
+
+    assert_eq!(request.url, "http://localhost/graphql");
+    assert!(
+        request.body["query"]
+            .as_str()
+            .unwrap()
+            .contains("issueCreate")
+    );
+    assert_eq!(
+        request.body["variables"]["input"],
+        json!({ "teamId": "team-1", "title": "Title", "description": "Details" })
+    );
+}
+
+#[test]
+fn http_template_is_filled_everywhere() {
+    let config: TrackerConfig = toml::from_str(
+        r#"
+        kind = "http"
+
+        [create]
+        url = "http://localhost/issues"
+        link = "/issue/html_url"
+        body = { fields = { summary = "{title}", labels = ["bug"], text = "Bug: {description}" } }
+        "#,
+    )
+    .unwrap();
+    let issue = NewIssue::parse("Title | Details").unwrap();
+    let request = tracker::issue_request(&config, &issue).unwrap();
+
+    assert_eq!(request.url, "http://localhost/issues");
+    assert_eq!(
+        request.body,
+        json!({ "fields": { "summary": "Title", "labels": ["bug"], "text": "Bug: Details" } })
+    );
+}
+
+#[tokio::test]
+async fn files_a_linear_issue() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/graphql")
+        .match_header("authorization", "lin_api_key")
+        .match_body(Matcher::PartialJson(
+            json!({ "variables": { "input": { "title": "Title" } } }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data":{"issueCreate":{"success":true,"issue":{"identifier":"ENG-7","url":"https://linear.app/t/issue/ENG-7"}}}}"#,
+        )
+        .create_async()
+        .await;
+
+    let config = linear(&format!("{}/graphql", server.url()));
+    let issue = NewIssue::parse("Title").unwrap();
+    let request = tracker::issue_request(&config, &issue).unwrap();
+    let link = tracker::create_issue(&config, &request).await.unwrap();
+
+    assert_eq!(link, "https://linear.app/t/issue/ENG-7");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn graphql_errors_are_reported() {
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/graphql")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"errors":[{"message":"Team not found"}]}"#)
+        .create_async()
+        .await;
+
+    let config = linear(&format!("{}/graphql", server.url()));
+    let request = tracker::issue_request(&config, &NewIssue::parse("Title").unwrap()).unwrap();
+    match tracker::create_issue(&config, &request).await {
+        Err(TrackerError::MissingLink(errors)) => assert!(errors.contains("Team not found")),
+        other => panic!("expected a missing link, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn http_tracker_errors_are_reported() {
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/issues")
+        .with_status(401)
+        .with_body("bad token")
+        .create_async()
+        .await;
+
+    let config = TrackerConfig {
+        kind: TrackerKind::Http,
+        create: HttpTemplate {
+            url: Some(format!("{}/issues", server.url())),
+            ..HttpTemplate::default()
+        },
+        ..TrackerConfig::default()
+    };
+    let request = tracker::issue_request(&config, &NewIssue::parse("Title").unwrap()).unwrap();
+    match tracker::create_issue(&config, &request).await {
+        Err(TrackerError::Api { status, body }) => {
+            assert_eq!(status, 401);
+            assert_eq!(body, "bad token");
+        }
+        other => panic!("expected an API error, got {:?}", other),
+    }
+}
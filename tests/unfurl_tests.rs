@@ -0,0 +1,255 @@
+use std::time::{Duration, Instant};
+
+use mockito::{Matcher, Server};
+use serde_json::json;
+use serenity::model::id::ChannelId;
+use the_bot::guild_settings::GuildSettings;
+use the_bot::unfurl::{
+    self, SourceKind, Ticket, TicketCache, TicketRef, TicketSource, UnfurlConfig,
+};
+
+fn source(kind: SourceKind, api_url: &str) -> TicketSource {
+    TicketSource {
+        kind,
+        keys: vec!["ENG".to_string()],
+        repo: Some("owner/repo".to_string()),
+        api_url: Some(api_url.to_string()),
+        user: None,
+        token: Some("secret".to_string()),
+        token_env: None,
+    }
+}
+
+fn config() -> UnfurlConfig {
+    UnfurlConfig {
+        sources: vec![
+            source(SourceKind::Linear, "http://localhost"),
+            source(SourceKind::GitHub, "http://localhost"),
+        ],
+        ..UnfurlConfig::default()
+    }
+}
+
+fn ticket(id: &str) -> Ticket {
+    Ticket {
+        id: id.to_string(),
+        title: "Fix login".to_string(),
+        status: "In Progress".to_string(),
+        assignee: Some("Rahim".to_string()),
+        url: format!("https://linear.app/t/issue/{}", id),
+    }
+}
+
+#[test]
+fn parses_references() {
+    assert_eq!(
+        TicketRef::parse("(eng-123),"),
+        Some(TicketRef::Key("ENG-123".to_string()))
+    );
+    assert_eq!(TicketRef::parse("#456."), Some(TicketRef::Number(456)));
+    assert_eq!(TicketRef::parse("<#123456789012345678>"), None);
+    assert_eq!(TicketRef::parse("#1234567890"), None);
+    assert_eq!(TicketRef::parse("well-known"), None);
+    assert_eq!(TicketRef::parse("123-456"), None);
+}
+
+#[test]
+fn finds_only_configured_references() {
+    let found = unfurl::find_references(
+        "ENG-1 and OPS-2, see #3 and ENG-1 again but not `ENG-4` or https://x.io/ENG-5",
+        &config(),
+    );
+    assert_eq!(
+        found,
+        vec![TicketRef::Key("ENG-1".to_string()), TicketRef::Number(3)]
+    );
+
+    let limited = UnfurlConfig {
+        max_per_message: 2,
+        ..config()
+    };
+    assert_eq!(
+        unfurl::find_references("ENG-1 ENG-2 ENG-3", &limited).len(),
+        2
+    );
+    assert!(unfurl::find_references("ENG-1 #2", &UnfurlConfig::default()).is_empty());
+}
+
+#[test]
+fn cache_expires() {
+    let config = UnfurlConfig {
+        cache_secs: 60,
+        ..UnfurlConfig::default()
+    };
+    let mut cache = TicketCache::new(&config);
+    let now = Instant::now();
+    let reference = TicketRef::Key("ENG-1".to_string());
+
+    assert_eq!(cache.get(&reference, now), None);
+    cache.insert(reference.clone(), Some(ticket("ENG-1")), now);
+    assert_eq!(cache.get(&reference, now), Some(Some(ticket("ENG-1"))));
+
+    let missing = TicketRef::Number(7);
+    cache.insert(missing.clone(), None, now);
+    assert_eq!(cache.get(&missing, now), Some(None));
+
+    assert_eq!(cache.get(&reference, now + Duration::from_secs(61)), None);
+}
+
+#[test]
+fn message_is_compact() {
+    let mut unassigned = ticket("ENG-2");
+    unassigned.assignee = None;
+    let message = unfurl::unfurl_message(&[ticket("ENG-1"), unassigned]);
+    assert_eq!(
+        message,
+        "🎫 [ENG-1](<https://linear.app/t/issue/ENG-1>) **Fix login** · In Progress · Rahim\n\
+         🎫 [ENG-2](<https://linear.app/t/issue/ENG-2>) **Fix login** · In Progress · unassigned"
+    );
+}
+
+#[test]
+fn unfurl_is_toggled_per_channel() {
+    let mut settings = GuildSettings::default();
+    let channel = ChannelId::new(42);
+
+    settings.apply_unfurl(channel, "on");
+    settings.apply_unfurl(channel, "on");
+    assert_eq!(settings.unfurl_channels, vec![channel]);
+    assert!(settings.describe().contains("unfurl channels: <#42>"));
+
+    settings.apply_unfurl(channel, "off");
+    assert!(settings.unfurl_channels.is_empty());
+}
+
+#[tokio::test]
+async fn looks_up_linear_issues() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/graphql")
+        .match_header("authorization", "secret")
+        .match_body(Matcher::PartialJson(
+            json!({ "variables": { "id": "ENG-1" } }),
+        ))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "data": { "issue": {
+                "identifier": "ENG-1",
+                "title": "Fix login",
+                "url": "https://linear.app/t/issue/ENG-1",
+                "state": { "name": "In Progress" },
+                "assignee": { "name": "Rahim" },
+            }}})
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let linear = source(SourceKind::Linear, &format!("{}/graphql", server.url()));
+    let found = unfurl::lookup(&linear, &TicketRef::Key("ENG-1".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(found, Some(ticket("ENG-1")));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn unknown_linear_issue_is_none() {
+    let mut server = Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/graphql")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"data":null,"errors":[{"message":"Entity not found"}]}"#)
+        .create_async()
+        .await;
+
+    let linear = source(SourceKind::Linear, &format!("{}/graphql", server.url()));
+    let found = unfurl::lookup(&linear, &TicketRef::Key("ENG-9".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(found, None);
+}
+
+#[tokio::test]
+async fn looks_up_jira_issues() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/rest/api/2/issue/ENG-1")
+        .match_query(Matcher::Any)
+        .match_header("authorization", "Bearer secret")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "key": "ENG-1", "fields": {
+                "summary": "Fix login",
+                "status": { "name": "To Do" },
+                "assignee": null,
+            }})
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let jira = source(SourceKind::Jira, &server.url());
+    let found = unfurl::lookup(&jira, &TicketRef::Key("ENG-1".to_string()))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.title, "Fix login");
+    assert_eq!(found.status, "To Do");
+    assert_eq!(found.assignee, None);
+    assert_eq!(found.url, format!("{}/browse/ENG-1", server.url()));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn looks_up_github_issues() {
+    let mut server = Server::new_async().await;
+    let _found = server
+        .mock("GET", "/repos/owner/repo/issues/3")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "title": "Crash on start",
+                "state": "open",
+                "assignee": { "login": "karim" },
+                "html_url": "https://github.com/owner/repo/issues/3",
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let _missing = server
+        .mock("GET", "/repos/owner/repo/issues/4")
+        .with_status(404)
+        .create_async()
+        .await;
+    let _broken = server
+        .mock("GET", "/repos/owner/repo/issues/5")
+        .with_status(500)
+        .with_body("oops")
+        .create_async()
+        .await;
+
+    let github = source(SourceKind::GitHub, &server.url());
+    let found = unfurl::lookup(&github, &TicketRef::Number(3))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.id, "#3");
+    assert_eq!(found.assignee.as_deref(), Some("karim"));
+
+    assert_eq!(
+        unfurl::lookup(&github, &TicketRef::Number(4))
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(matches!(
+        unfurl::lookup(&github, &TicketRef::Number(5)).await,
+        Err(unfurl::UnfurlError::Api { status: 500, .. })
+    ));
+}
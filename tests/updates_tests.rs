@@ -0,0 +1,118 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use serenity::model::id::{GuildId, UserId};
+use the_bot::storage::Storage;
+use the_bot::updates::{self, Since, StatusUpdate, UpdatesQuery};
+
+// Wed 15 Nov 2023 10:00 UTC, 16:00 in Dhaka
+const NOW: i64 = 1_700_042_400;
+
+fn update(user: u64, name: &str, text: &str, posted_at: i64) -> StatusUpdate {
+    StatusUpdate {
+        id: 0,
+        guild: Some(GuildId::new(100)),
+        user: UserId::new(user),
+        user_name: name.to_string(),
+        text: text.to_string(),
+        posted_at,
+    }
+}
+
+#[test]
+fn parses_queries() {
+    assert_eq!(
+        UpdatesQuery::parse(""),
+        Some(UpdatesQuery {
+            user: None,
+            since: Since::Days(7),
+            export: false
+        })
+    );
+    assert_eq!(
+        UpdatesQuery::parse("export <@!5> yesterday"),
+        Some(UpdatesQuery {
+            user: Some(UserId::new(5)),
+            since: Since::Yesterday,
+            export: true
+        })
+    );
+    assert_eq!(Since::parse("3d"), Some(Since::Days(3)));
+    assert_eq!(Since::parse("100000000d"), Some(Since::Days(366)));
+    assert_eq!(
+        Since::parse("2023-11-01"),
+        Some(Since::Date(NaiveDate::from_ymd_opt(2023, 11, 1).unwrap()))
+    );
+    assert_eq!(UpdatesQuery::parse("whenever"), None);
+}
+
+#[test]
+fn periods_start_at_local_midnight() {
+    let now = Utc.timestamp_opt(NOW, 0).unwrap();
+    let dhaka: Tz = "Asia/Dhaka".parse().unwrap();
+
+    // midnight in Dhaka is 18:00 UTC the day before
+    let today = Utc.with_ymd_and_hms(2023, 11, 14, 18, 0, 0).unwrap();
+    assert_eq!(Since::Today.timestamp(now, dhaka), today.timestamp());
+    // too far back to be a date, so everything
+    assert!(Since::Days(i64::MAX).timestamp(now, dhaka) < 0);
+    assert_eq!(
+        Since::Days(7).timestamp(now, Tz::UTC),
+        Utc.with_ymd_and_hms(2023, 11, 9, 0, 0, 0)
+            .unwrap()
+            .timestamp()
+    );
+}
+
+#[test]
+fn stores_and_filters_updates() {
+    let storage = Storage::in_memory().unwrap();
+    let guild = Some(GuildId::new(100));
+    storage
+        .add_update(&update(1, "Rahim", "old", NOW - 10 * 86_400))
+        .unwrap();
+    storage
+        .add_update(&update(1, "Rahim", "fixed the build", NOW - 60))
+        .unwrap();
+    storage
+        .add_update(&update(2, "Karim", "reviews", NOW - 30))
+        .unwrap();
+    storage
+        .add_update(&StatusUpdate {
+            guild: None,
+            ..update(3, "Dm", "elsewhere", NOW)
+        })
+        .unwrap();
+
+    let recent = storage.updates(guild, NOW - 86_400, None).unwrap();
+    let texts: Vec<&str> = recent.iter().map(|u| u.text.as_str()).collect();
+    assert_eq!(texts, vec!["fixed the build", "reviews"]);
+
+    let karim = storage.updates(guild, 0, Some(UserId::new(2))).unwrap();
+    assert_eq!(karim.len(), 1);
+
+    let members = [UserId::new(1), UserId::new(2), UserId::new(3)];
+    assert_eq!(updates::missing(&members, &recent), vec![UserId::new(3)]);
+}
+
+#[test]
+fn calls_out_missing_members() {
+    let message = updates::talha_message(&[UserId::new(3), UserId::new(4)], "10% off", "?");
+    assert!(message.starts_with("<@3> <@4> no update today. 10% off"));
+    assert!(message.contains("`?update <text>`"));
+    assert!(updates::talha_message(&[], "10% off", "!").contains("🎉"));
+}
+
+#[test]
+fn exports_markdown_by_day() {
+    let updates = [
+        update(1, "Rahim", "fixed the build", NOW - 86_400),
+        update(2, "Karim", "reviews\nand a release", NOW),
+    ];
+    let report = updates::markdown(&updates, Tz::UTC, "Weekly");
+    assert_eq!(
+        report,
+        "# Weekly\n\n## Tuesday, 2023-11-14\n\n- **Rahim** (10:00): fixed the build\n\
+         \n## Wednesday, 2023-11-15\n\n- **Karim** (10:00): reviews\n  and a release\n"
+    );
+    assert!(updates::markdown(&[], Tz::UTC, "Weekly").contains("No updates."));
+}
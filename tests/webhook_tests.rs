@@ -0,0 +1,165 @@
+use std::fs;
+
+use serde_json::Value;
+use serenity::model::id::ChannelId;
+use the_bot::config::BotConfig;
+use the_bot::webhook::{self, GITHUB_PATH, ListenerSecrets, Post, WebhookConfig, WebhookRoute};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+const SECRET: &str = "It's a Secret to Everybody";
+
+fn fixture(name: &str) -> Vec<u8> {
+    fs::read(format!("tests/fixtures/github/{}.json", name)).unwrap()
+}
+
+fn announce(event: &str, name: &str) -> Option<String> {
+    let payload: Value = serde_json::from_slice(&fixture(name)).unwrap();
+    webhook::announcement(event, &payload).map(|a| a.content)
+}
+
+/// Start a listener on a free port, returning its URL and what it wants posted.
+async fn start(config: WebhookConfig) -> (String, mpsc::UnboundedReceiver<Post>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), GITHUB_PATH);
+    let (posts, receiver) = mpsc::unbounded_channel();
+    let secrets = ListenerSecrets {
+        github: Some(SECRET.to_string()),
+        notify: None,
+    };
+    let router = webhook::router(config, secrets, posts);
+    tokio::spawn(webhook::serve(listener, router));
+    (url, receiver)
+}
+
+async fn deliver(url: &str, event: &str, body: Vec<u8>, signature: &str) -> u16 {
+    reqwest::Client::new()
+        .post(url)
+        .header("X-GitHub-Event", event)
+        .header("X-Hub-Signature-256", signature)
+        .body(body)
+        .send()
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
+
+#[test]
+fn verifies_github_signatures() {
+    // the example from GitHub's webhook docs
+    let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+    assert!(webhook::verify_signature(
+        SECRET,
+        b"Hello, World!",
+        signature
+    ));
+    assert_eq!(webhook::sign(SECRET, b"Hello, World!"), signature);
+
+    assert!(!webhook::verify_signature(
+        SECRET,
+        b"Hello, World?",
+        signature
+    ));
+    assert!(!webhook::verify_signature(
+        "wrong",
+        b"Hello, World!",
+        signature
+    ));
+    assert!(!webhook::verify_signature(
+        SECRET,
+        b"Hello, World!",
+        "sha1=abc"
+    ));
+}
+
+#[test]
+fn announces_pull_requests_checks_and_releases() {
+    let opened = announce("pull_request", "pull_request_opened").unwrap();
+    assert!(opened.contains("PR opened in **octo-org/the-app** by octocat"));
+
+    let review = announce("pull_request", "pull_request_review_requested").unwrap();
+    assert!(review.contains("wants a review from hubot"));
+
+    let merged = announce("pull_request", "pull_request_merged").unwrap();
+    assert!(merged.contains("Merged in **octo-org/the-app** by hubot"));
+
+    let failed = announce("check_suite", "check_suite_failure").unwrap();
+    assert!(failed.contains("`dark-mode` (ec26c3e)"));
+    assert!(failed.contains("Don't fix it just revert!"));
+
+    let release = announce("release", "release_published").unwrap();
+    assert!(release.contains("v1.2.0 is out: **Dark mode**"));
+
+    assert!(announce("issues", "pull_request_opened").is_none());
+}
+
+#[test]
+fn routes_by_repo_and_event() {
+    let config = BotConfig::parse(
+        r#"
+        [webhook]
+        bind = "127.0.0.1:8080"
+
+        [[webhook.routes]]
+        repos = ["octo-org/the-app"]
+        events = ["pull_request"]
+        channel = 1
+
+        [[webhook.routes]]
+        events = ["release"]
+        channel = 2
+
+        [[webhook.routes]]
+        channel = 2
+        "#,
+    )
+    .unwrap()
+    .webhook;
+
+    assert_eq!(
+        config.channels_for("octo-org/the-app", "pull_request"),
+        vec![ChannelId::new(1), ChannelId::new(2)]
+    );
+    assert_eq!(
+        config.channels_for("octo-org/other", "release"),
+        vec![ChannelId::new(2)]
+    );
+}
+
+#[tokio::test]
+async fn signed_deliveries_are_posted_to_routed_channels() {
+    let config = WebhookConfig {
+        routes: vec![WebhookRoute {
+            repos: vec![],
+            events: vec!["pull_request".to_string()],
+            channel: ChannelId::new(7),
+        }],
+        ..WebhookConfig::default()
+    };
+    let (url, mut posts) = start(config).await;
+
+    let body = fixture("pull_request_merged");
+    let signature = webhook::sign(SECRET, &body);
+    assert_eq!(deliver(&url, "pull_request", body, &signature).await, 200);
+
+    let post = posts.recv().await.unwrap();
+    assert_eq!(post.channel, ChannelId::new(7));
+    assert!(post.content.contains("Add dark mode"));
+
+    // not routed anywhere, so nothing is posted
+    let body = fixture("release_published");
+    let signature = webhook::sign(SECRET, &body);
+    assert_eq!(deliver(&url, "release", body, &signature).await, 200);
+    assert!(posts.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn unsigned_deliveries_are_rejected() {
+    let (url, mut posts) = start(WebhookConfig::default()).await;
+
+    let body = fixture("pull_request_opened");
+    let forged = webhook::sign("not the secret", &body);
+    assert_eq!(deliver(&url, "pull_request", body, &forged).await, 401);
+    assert!(posts.try_recv().is_err());
+}